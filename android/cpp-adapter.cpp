@@ -16,3 +16,23 @@ Java_com_reactnativers_ReactNativeRsModule_nativeExecute(JNIEnv *env, jclass typ
 
     return result;
 }
+
+extern "C"
+JNIEXPORT jlong JNICALL
+Java_com_reactnativers_ReactNativeRsModule_nativeRegisterBuffer(JNIEnv *env, jclass type, jbyteArray data) {
+    jsize len = env->GetArrayLength(data);
+    jbyte *bytes = env->GetByteArrayElements(data, nullptr);
+    if (bytes == nullptr) {
+        return 0;
+    }
+    uint64_t id = reactnativers::register_buffer(reinterpret_cast<const uint8_t *>(bytes), len);
+    env->ReleaseByteArrayElements(data, bytes, JNI_ABORT);
+
+    return static_cast<jlong>(id);
+}
+
+extern "C"
+JNIEXPORT jboolean JNICALL
+Java_com_reactnativers_ReactNativeRsModule_nativeReleaseBuffer(JNIEnv *env, jclass type, jlong id) {
+    return reactnativers::release_buffer(static_cast<uint64_t>(id)) ? JNI_TRUE : JNI_FALSE;
+}
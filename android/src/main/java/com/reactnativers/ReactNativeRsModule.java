@@ -1,5 +1,6 @@
 package com.reactnativers;
 
+import android.util.Base64;
 import android.util.Log;
 import androidx.annotation.NonNull;
 import android.content.Context;
@@ -34,6 +35,8 @@ public class ReactNativeRsModule extends ReactNativeRsSpec {
   }
 
   public static native String nativeExecute(String cmd);
+  public static native long nativeRegisterBuffer(byte[] data);
+  public static native boolean nativeReleaseBuffer(long id);
 
   @ReactMethod
   public void getAppHomeDir(Promise promise) {
@@ -66,4 +69,24 @@ public class ReactNativeRsModule extends ReactNativeRsSpec {
       }
     }).start();
   }
+
+  @ReactMethod
+  public void registerBuffer(final String base64, final Promise promise) {
+    try {
+      byte[] data = Base64.decode(base64, Base64.DEFAULT);
+      // Ids are unsigned 64-bit, more than a JS number holds exactly
+      promise.resolve(Long.toUnsignedString(nativeRegisterBuffer(data)));
+    } catch (IllegalArgumentException err) {
+      promise.reject("BUFFER_ERROR", "Buffer data isn't valid base64", err);
+    }
+  }
+
+  @ReactMethod
+  public void releaseBuffer(final String id, final Promise promise) {
+    try {
+      promise.resolve(nativeReleaseBuffer(Long.parseUnsignedLong(id)));
+    } catch (NumberFormatException err) {
+      promise.resolve(false);
+    }
+  }
 }
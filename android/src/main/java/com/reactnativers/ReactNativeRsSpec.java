@@ -11,4 +11,6 @@ abstract class ReactNativeRsSpec extends ReactContextBaseJavaModule {
 
   public abstract void execute(String cmd, Promise promise);
   public abstract void getAppHomeDir(Promise promise);
+  public abstract void registerBuffer(String base64, Promise promise);
+  public abstract void releaseBuffer(String id, Promise promise);
 }
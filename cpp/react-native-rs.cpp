@@ -8,4 +8,12 @@ namespace reactnativers {
 	void free_string(const char *ptr) {
 		return rust_free_string(ptr);
 	}
+
+	uint64_t register_buffer(const uint8_t *data, size_t len) {
+		return rust_register_buffer(data, len);
+	}
+
+	bool release_buffer(uint64_t id) {
+		return rust_release_buffer(id);
+	}
 }
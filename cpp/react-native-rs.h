@@ -1,14 +1,21 @@
 #ifndef REACTNATIVERS_H
 #define REACTNATIVERS_H
 
+#include <cstddef>
+#include <cstdint>
+
 extern "C" {
   const char *rust_execute(const char*);
   void rust_free_string(const char*);
+  uint64_t rust_register_buffer(const uint8_t*, size_t);
+  bool rust_release_buffer(uint64_t);
 }
 
 namespace reactnativers {
   const char* execute(const char * cmd);
   void free_string(const char *ptr);
+  uint64_t register_buffer(const uint8_t *data, size_t len);
+  bool release_buffer(uint64_t id);
 }
 
 #endif /* REACTNATIVERS_H */
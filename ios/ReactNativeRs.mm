@@ -37,6 +37,28 @@ RCT_EXPORT_METHOD(execute:(NSString*)cmd
     });
 }
 
+RCT_EXPORT_METHOD(registerBuffer:(NSString*)base64
+  resolve:(RCTPromiseResolveBlock)resolve
+  reject:(RCTPromiseRejectBlock)reject)
+{
+    NSData *data = [[NSData alloc] initWithBase64EncodedString:base64 options:0];
+    if (data == nil) {
+        reject(@"BUFFER_ERROR", @"Buffer data isn't valid base64", nil);
+        return;
+    }
+    uint64_t id = reactnativers::register_buffer((const uint8_t *)data.bytes, data.length);
+    // Ids are 64-bit, more than a JS number holds exactly
+    resolve([NSString stringWithFormat:@"%llu", id]);
+}
+
+RCT_EXPORT_METHOD(releaseBuffer:(NSString*)id
+  resolve:(RCTPromiseResolveBlock)resolve
+  reject:(RCTPromiseRejectBlock)reject)
+{
+    bool released = reactnativers::release_buffer(strtoull([id UTF8String], NULL, 10));
+    resolve(@(released));
+}
+
 // Don't compile this code when we build for the old architecture.
 #ifdef RCT_NEW_ARCH_ENABLED
 - (std::shared_ptr<facebook::react::TurboModule>)getTurboModule:
@@ -4,6 +4,8 @@ jest.mock('react-native', () => ({
     ReactNativeRs: {
       execute: jest.fn(),
       getAppHomeDir: jest.fn(),
+      registerBuffer: jest.fn(),
+      releaseBuffer: jest.fn(),
     },
   },
   Platform: {
@@ -13,6 +15,8 @@ jest.mock('react-native', () => ({
     getEnforcing: jest.fn(() => ({
       execute: jest.fn(),
       getAppHomeDir: jest.fn(),
+      registerBuffer: jest.fn(),
+      releaseBuffer: jest.fn(),
     })),
   },
 }));
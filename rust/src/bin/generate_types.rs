@@ -1,47 +1,330 @@
 #[cfg(feature = "ts-rs")]
 fn main() {
-    use react_native_rs::cmd::Command;
+    use react_native_rs::cmd::{
+        command_signatures, verify_command_signatures, verify_enum_spellings, Command,
+    };
     use react_native_rs::commands::{
+        benchmark::{BenchmarkInput, BenchmarkResult, BenchmarkRun},
+        crypto::{
+            CipherAlgorithm, DecryptInput, DecryptResult, EncryptInput, EncryptResult,
+            KeyAgreementInput, KeyAgreementResult, OpenInput, PlaintextEncoding, SealInput,
+            SealResult, X25519KeypairInput, X25519KeypairResult,
+        },
         fibonacci::{FibonacciInput, FibonacciResult},
-        hash::{HashDataInput, HashDataResult, HashAlgorithm},
-        prime::{FindPrimesInput, FindPrimesResult},
-        sort::{SortNumbersInput, SortNumbersResult, SortAlgorithm},
+        hash::{
+            DataEncoding, DeriveKeyInput, DeriveKeyResult, FileDigest, HashAlgorithm,
+            HashDataInput, HashDataResult, HashFileInput, HashFileResult, HmacInput, HmacResult,
+            KdfAlgorithm, MacAlgorithm, OutputEncoding,
+        },
+        merkle::{
+            MerkleInput, MerkleProof, MerkleProofStep, MerkleResult, MerkleSide, MerkleVerifyInput,
+            MerkleVerifyResult,
+        },
+        otp::{
+            HotpGenerateInput, HotpResult, OtpAlgorithm, OtpParams, TotpGenerateInput, TotpResult,
+            TotpVerifyInput, TotpVerifyResult,
+        },
+        password::{
+            NeedsRehashInput, NeedsRehashResult, PasswordAlgorithm, PasswordHashInput,
+            PasswordHashResult, PasswordParams, PasswordVerifyInput, PasswordVerifyResult,
+        },
+        prime::{FindPrimesInput, FindPrimesResult, PrimePage},
+        random::{RandomInput, RandomMode, RandomResult, UuidVersion},
+        schema::{command_schema, type_schemas, SchemaInput, SchemaResult},
+        sequence::{Sequence, SequenceInput, SequenceResult},
+        signing::{
+            ConvertKeyInput, GenerateKeypairInput, KeyFormat, KeyKind, KeyPairResult, SignInput,
+            SignResult, SignatureAlgorithm, VerifyInput, VerifyResult,
+        },
+        sort::{
+            KeyCoercion, NanPlacement, NullPlacement, RecordsOutput, SortAlgorithm, SortDirection,
+            SortKey, SortNumbersInput, SortNumbersResult, SortRecordsInput, SortRecordsResult,
+            SortValues, SortValuesInput, SortValuesResult, StringOrder,
+        },
+        sort_file::{LineFormat, SortFileInput, SortFileResult},
+        stats::{Correlation, Histogram, HistogramOptions, StatsInput, StatsResult, VarianceKind},
+    };
+    use react_native_rs::data_dir::{DataDirStatus, SetDataDirInput};
+    use react_native_rs::error::{ErrorCode, FieldError};
+    use react_native_rs::math::ArithmeticMode;
+    use react_native_rs::progress::{Progress, ProgressInput};
+    use react_native_rs::session::{
+        RecordingStatus, ReplayEntry, ReplaySessionInput, ReplaySessionResult, ReplayStatus,
+        ResultDiff, StartRecordingInput,
     };
-    
+    use std::path::{Path, PathBuf};
+    use ts_rs::TS;
+
     #[cfg(feature = "image-processing")]
     use react_native_rs::commands::image_processing::{
-        ImageProcessingInput, ImageProcessingResult, ImageFilter
+        ImageFilter, ImageProcessingInput, ImageProcessingResult,
     };
-    
-    println!("Generating TypeScript types...");
-    
+
+    fn export<T: TS + 'static>(out_dir: &Path) {
+        T::export_to(out_dir.join(format!("{}.ts", T::name())))
+            .unwrap_or_else(|err| panic!("Failed to export {}: {err}", T::name()));
+    }
+
+    // `ts-rs` 7 ignores `TS_RS_EXPORT_DIR`, so the configured directory is applied here
+    let out_dir = std::env::var("TS_RS_EXPORT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("bindings"));
+    std::fs::create_dir_all(&out_dir).expect("Failed to create the output directory");
+
+    println!("Generating TypeScript types in {}...", out_dir.display());
+
+    let signatures = command_signatures();
+    if let Err(err) = verify_command_signatures(&signatures) {
+        panic!("Command signatures are out of sync with the Command enum: {err}");
+    }
+    if let Err(err) = verify_enum_spellings() {
+        panic!("Generated enum spellings differ from the ones serde accepts: {err}");
+    }
+
     // Export all types
-    Command::export().expect("Failed to export Command");
-    
-    FibonacciInput::export().expect("Failed to export FibonacciInput");
-    FibonacciResult::export().expect("Failed to export FibonacciResult");
-    
-    HashDataInput::export().expect("Failed to export HashDataInput");
-    HashDataResult::export().expect("Failed to export HashDataResult");
-    HashAlgorithm::export().expect("Failed to export HashAlgorithm");
-    
-    SortNumbersInput::export().expect("Failed to export SortNumbersInput");
-    SortNumbersResult::export().expect("Failed to export SortNumbersResult");
-    SortAlgorithm::export().expect("Failed to export SortAlgorithm");
-    
-    FindPrimesInput::export().expect("Failed to export FindPrimesInput");
-    FindPrimesResult::export().expect("Failed to export FindPrimesResult");
-    
+    export::<Command>(&out_dir);
+    export::<ErrorCode>(&out_dir);
+    export::<FieldError>(&out_dir);
+
+    export::<FibonacciInput>(&out_dir);
+    export::<FibonacciResult>(&out_dir);
+    export::<ArithmeticMode>(&out_dir);
+
+    export::<SequenceInput>(&out_dir);
+    export::<SequenceResult>(&out_dir);
+    export::<Sequence>(&out_dir);
+
+    export::<HashDataInput>(&out_dir);
+    export::<HashDataResult>(&out_dir);
+    export::<HashAlgorithm>(&out_dir);
+    export::<OutputEncoding>(&out_dir);
+    export::<DataEncoding>(&out_dir);
+    export::<HashFileInput>(&out_dir);
+    export::<HashFileResult>(&out_dir);
+    export::<FileDigest>(&out_dir);
+    export::<MacAlgorithm>(&out_dir);
+    export::<HmacInput>(&out_dir);
+    export::<HmacResult>(&out_dir);
+    export::<KdfAlgorithm>(&out_dir);
+    export::<DeriveKeyInput>(&out_dir);
+    export::<DeriveKeyResult>(&out_dir);
+    export::<PasswordAlgorithm>(&out_dir);
+    export::<PasswordParams>(&out_dir);
+    export::<PasswordHashInput>(&out_dir);
+    export::<PasswordHashResult>(&out_dir);
+    export::<PasswordVerifyInput>(&out_dir);
+    export::<PasswordVerifyResult>(&out_dir);
+    export::<NeedsRehashInput>(&out_dir);
+    export::<NeedsRehashResult>(&out_dir);
+    export::<CipherAlgorithm>(&out_dir);
+    export::<PlaintextEncoding>(&out_dir);
+    export::<EncryptInput>(&out_dir);
+    export::<EncryptResult>(&out_dir);
+    export::<DecryptInput>(&out_dir);
+    export::<DecryptResult>(&out_dir);
+    export::<X25519KeypairInput>(&out_dir);
+    export::<X25519KeypairResult>(&out_dir);
+    export::<KeyAgreementInput>(&out_dir);
+    export::<KeyAgreementResult>(&out_dir);
+    export::<SealInput>(&out_dir);
+    export::<SealResult>(&out_dir);
+    export::<OpenInput>(&out_dir);
+    export::<UuidVersion>(&out_dir);
+    export::<RandomMode>(&out_dir);
+    export::<RandomInput>(&out_dir);
+    export::<RandomResult>(&out_dir);
+    export::<SignatureAlgorithm>(&out_dir);
+    export::<KeyFormat>(&out_dir);
+    export::<KeyKind>(&out_dir);
+    export::<GenerateKeypairInput>(&out_dir);
+    export::<ConvertKeyInput>(&out_dir);
+    export::<KeyPairResult>(&out_dir);
+    export::<SignInput>(&out_dir);
+    export::<SignResult>(&out_dir);
+    export::<VerifyInput>(&out_dir);
+    export::<VerifyResult>(&out_dir);
+
+    export::<MerkleInput>(&out_dir);
+    export::<MerkleSide>(&out_dir);
+    export::<MerkleProofStep>(&out_dir);
+    export::<MerkleProof>(&out_dir);
+    export::<MerkleResult>(&out_dir);
+    export::<MerkleVerifyInput>(&out_dir);
+    export::<MerkleVerifyResult>(&out_dir);
+
+    export::<OtpAlgorithm>(&out_dir);
+    export::<OtpParams>(&out_dir);
+    export::<TotpGenerateInput>(&out_dir);
+    export::<TotpResult>(&out_dir);
+    export::<TotpVerifyInput>(&out_dir);
+    export::<TotpVerifyResult>(&out_dir);
+    export::<HotpGenerateInput>(&out_dir);
+    export::<HotpResult>(&out_dir);
+
+    export::<SortNumbersInput>(&out_dir);
+    export::<SortNumbersResult>(&out_dir);
+    export::<SortAlgorithm>(&out_dir);
+    export::<SortValues>(&out_dir);
+    export::<NanPlacement>(&out_dir);
+    export::<StringOrder>(&out_dir);
+    export::<SortValuesInput>(&out_dir);
+    export::<SortValuesResult>(&out_dir);
+    export::<SortDirection>(&out_dir);
+    export::<NullPlacement>(&out_dir);
+    export::<KeyCoercion>(&out_dir);
+    export::<SortKey>(&out_dir);
+    export::<RecordsOutput>(&out_dir);
+    export::<SortRecordsInput>(&out_dir);
+    export::<SortRecordsResult>(&out_dir);
+    export::<LineFormat>(&out_dir);
+    export::<SortFileInput>(&out_dir);
+    export::<SortFileResult>(&out_dir);
+
+    export::<VarianceKind>(&out_dir);
+    export::<HistogramOptions>(&out_dir);
+    export::<StatsInput>(&out_dir);
+    export::<Histogram>(&out_dir);
+    export::<Correlation>(&out_dir);
+    export::<StatsResult>(&out_dir);
+
+    export::<PrimePage>(&out_dir);
+    export::<FindPrimesInput>(&out_dir);
+    export::<FindPrimesResult>(&out_dir);
+
+    export::<BenchmarkInput>(&out_dir);
+    export::<BenchmarkResult>(&out_dir);
+    export::<BenchmarkRun>(&out_dir);
+
+    export::<SchemaInput>(&out_dir);
+    export::<SchemaResult>(&out_dir);
+
+    export::<SetDataDirInput>(&out_dir);
+    export::<DataDirStatus>(&out_dir);
+    export::<ProgressInput>(&out_dir);
+    export::<Progress>(&out_dir);
+
+    export::<StartRecordingInput>(&out_dir);
+    export::<RecordingStatus>(&out_dir);
+    export::<ReplaySessionInput>(&out_dir);
+    export::<ReplaySessionResult>(&out_dir);
+    export::<ReplayEntry>(&out_dir);
+    export::<ReplayStatus>(&out_dir);
+    export::<ResultDiff>(&out_dir);
+
     #[cfg(feature = "image-processing")]
     {
-        ImageProcessingInput::export().expect("Failed to export ImageProcessingInput");
-        ImageProcessingResult::export().expect("Failed to export ImageProcessingResult");
-        ImageFilter::export().expect("Failed to export ImageFilter");
+        export::<ImageProcessingInput>(&out_dir);
+        export::<ImageProcessingResult>(&out_dir);
+        export::<ImageFilter>(&out_dir);
+    }
+
+    let client = client::generate(&signatures);
+    std::fs::write(out_dir.join("client.ts"), client).expect("Failed to write client.ts");
+
+    let schema_dir = out_dir.join("schemas");
+    std::fs::create_dir_all(&schema_dir).expect("Failed to create the schema directory");
+    let schemas = type_schemas()
+        .into_iter()
+        .chain([("Command".to_string(), command_schema())]);
+    for (name, schema) in schemas {
+        let schema = serde_json::to_string_pretty(&schema).expect("Failed to serialize schema");
+        std::fs::write(schema_dir.join(format!("{name}.json")), schema + "\n")
+            .unwrap_or_else(|err| panic!("Failed to write the {name} schema: {err}"));
     }
-    
+
     println!("TypeScript types generated successfully!");
 }
 
+/// Generation of the typed `RustClient` module from the command signatures.
+#[cfg(feature = "ts-rs")]
+mod client {
+    use react_native_rs::cmd::CommandSignature;
+    use std::collections::BTreeSet;
+    use std::fmt::Write;
+
+    pub fn generate(signatures: &[CommandSignature]) -> String {
+        let mut out = String::new();
+        out.push_str(
+            "// This file was generated by `generate-types`. Do not edit this file manually.\n",
+        );
+
+        let imports: BTreeSet<&str> = signatures
+            .iter()
+            .flat_map(|s| s.imports.iter().map(String::as_str))
+            .chain(["Command", "ErrorCode"])
+            .collect();
+        for import in imports {
+            writeln!(out, "import type {{ {import} }} from \"./{import}\";").unwrap();
+        }
+
+        out.push_str("\nexport type { ErrorCode };\n");
+        out.push_str("\n/** Name of every command, as used in the `cmd` tag. */\n");
+        out.push_str("export type CommandName = Command[\"cmd\"];\n");
+        out.push_str("\n/** The `Command` variant with the given name. */\n");
+        out.push_str(
+            "export type CommandOf<K extends CommandName> = Extract<Command, { cmd: K }>;\n",
+        );
+
+        out.push_str("\n/** Result type of every command, keyed by command name. */\n");
+        out.push_str("export interface CommandResultMap {\n");
+        for signature in signatures {
+            writeln!(out, "  {:?}: {};", signature.cmd, signature.result).unwrap();
+        }
+        out.push_str("}\n");
+
+        out.push_str("\n/** Sends a command over the bridge and resolves to its result. */\n");
+        out.push_str(
+            "export type Executor = <K extends CommandName>(command: CommandOf<K>) => \
+             Promise<CommandResultMap[K]>;\n",
+        );
+
+        out.push_str("\n/** Typed wrapper exposing one method per command. */\n");
+        out.push_str("export class RustClient {\n");
+        out.push_str("  constructor(private readonly execute: Executor) {}\n");
+        for signature in signatures {
+            out.push('\n');
+            let method = camel_case(signature.cmd);
+            let cmd = signature.cmd;
+            match &signature.params {
+                Some(params) => {
+                    writeln!(
+                        out,
+                        "  {method}(params: {params}): Promise<{}> {{",
+                        signature.result
+                    )
+                    .unwrap();
+                    writeln!(
+                        out,
+                        "    return this.execute<{cmd:?}>({{ cmd: {cmd:?}, params }});"
+                    )
+                    .unwrap();
+                }
+                None => {
+                    writeln!(out, "  {method}(): Promise<{}> {{", signature.result).unwrap();
+                    writeln!(out, "    return this.execute<{cmd:?}>({{ cmd: {cmd:?} }});").unwrap();
+                }
+            }
+            out.push_str("  }\n");
+        }
+        out.push_str("}\n");
+
+        out
+    }
+
+    fn camel_case(snake: &str) -> String {
+        let mut parts = snake.split('_');
+        let mut out = parts.next().unwrap_or_default().to_string();
+        for part in parts {
+            let mut chars = part.chars();
+            if let Some(first) = chars.next() {
+                out.extend(first.to_uppercase());
+                out.push_str(chars.as_str());
+            }
+        }
+        out
+    }
+}
+
 #[cfg(not(feature = "ts-rs"))]
 fn main() {
     println!("TypeScript generation requires the 'ts-rs' feature to be enabled.");
@@ -0,0 +1,276 @@
+use react_native_rs::{rust_execute, rust_free_string};
+use serde_json::{json, Value};
+use std::ffi::{CStr, CString};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "\
+Usage: rnrs [OPTIONS] [FILE]
+
+Executes one JSON command per line through `rust_execute` and prints each
+`CommandResult` to stdout. Commands are read from FILE, or from stdin when
+FILE is omitted or `-`. Empty lines and lines starting with `#` are skipped.
+
+Options:
+  -p, --pretty        Pretty-print results, expanding the `res` payload
+  -t, --time          Report the wall-clock time of every run on stderr
+  -r, --repeat <N>    Execute every command N times (default: 1)
+      --record <FILE> Record the executed commands to a session file
+      --replay <FILE> Replay a recorded session and print the diff report
+      --data-dir <DIR> Resolve file paths against DIR (default: current directory)
+  -h, --help          Print this help";
+
+struct Options {
+    pretty: bool,
+    time: bool,
+    repeat: u32,
+    record: Option<String>,
+    replay: Option<String>,
+    data_dir: Option<String>,
+    input: Option<String>,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        pretty: false,
+        time: false,
+        repeat: 1,
+        record: None,
+        replay: None,
+        data_dir: None,
+        input: None,
+    };
+
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-p" | "--pretty" => options.pretty = true,
+            "-t" | "--time" => options.time = true,
+            "-r" | "--repeat" => {
+                let value = args.next().ok_or("missing value for --repeat")?;
+                options.repeat = match value.parse() {
+                    Ok(0) | Err(_) => return Err(format!("invalid repeat count: {value}")),
+                    Ok(n) => n,
+                };
+            }
+            "--record" => {
+                options.record = Some(args.next().ok_or("missing value for --record")?);
+            }
+            "--replay" => {
+                options.replay = Some(args.next().ok_or("missing value for --replay")?);
+            }
+            "--data-dir" => {
+                options.data_dir = Some(args.next().ok_or("missing value for --data-dir")?);
+            }
+            "-h" | "--help" => return Err(String::new()),
+            "-" => options.input = None,
+            flag if flag.starts_with('-') => return Err(format!("unknown option: {flag}")),
+            path => {
+                if options.input.is_some() {
+                    return Err(format!("unexpected argument: {path}"));
+                }
+                options.input = Some(path.to_string());
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+/// Runs a raw command through the same FFI entry point the mobile apps use.
+fn execute(raw_cmd: &str) -> Result<String, String> {
+    let raw_cmd = CString::new(raw_cmd).map_err(|err| format!("invalid command: {err}"))?;
+    let res_ptr = rust_execute(raw_cmd.as_ptr());
+    let res = unsafe { CStr::from_ptr(res_ptr) }
+        .to_string_lossy()
+        .into_owned();
+    rust_free_string(res_ptr as *mut _);
+    Ok(res)
+}
+
+/// Returns whether a serialized `CommandResult` reports an error or a panic.
+fn is_failure(res: &Value) -> bool {
+    !res["error"].is_null() || res["panic"].as_bool().unwrap_or(false)
+}
+
+fn render(res: &str, pretty: bool) -> (String, bool) {
+    let Ok(mut value) = serde_json::from_str::<Value>(res) else {
+        return (res.to_string(), true);
+    };
+    let failed = is_failure(&value);
+
+    if !pretty {
+        return (res.to_string(), failed);
+    }
+
+    // `res` holds the serialized command output, expand it for readability
+    if let Some(Value::String(inner)) = value.get("res") {
+        if let Ok(inner) = serde_json::from_str::<Value>(inner) {
+            value["res"] = inner;
+        }
+    }
+
+    let rendered = serde_json::to_string_pretty(&value).unwrap_or_else(|_| res.to_string());
+    (rendered, failed)
+}
+
+fn report_timings(label: &str, timings: &[Duration]) {
+    let Some(min) = timings.iter().min() else {
+        return;
+    };
+    let max = timings.iter().max().unwrap_or(min);
+    let total: Duration = timings.iter().sum();
+    let mean = total / timings.len() as u32;
+
+    eprintln!(
+        "{label}: {} runs, min {:.3} ms, mean {:.3} ms, max {:.3} ms",
+        timings.len(),
+        min.as_secs_f64() * 1000.0,
+        mean.as_secs_f64() * 1000.0,
+        max.as_secs_f64() * 1000.0,
+    );
+}
+
+fn run(options: &Options, input: impl BufRead) -> io::Result<bool> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut all_ok = true;
+
+    for (line_no, line) in input.lines().enumerate() {
+        let line = line?;
+        let raw_cmd = line.trim();
+        if raw_cmd.is_empty() || raw_cmd.starts_with('#') {
+            continue;
+        }
+
+        let label = format!("line {}", line_no + 1);
+        let mut timings = Vec::with_capacity(options.repeat as usize);
+
+        for run in 1..=options.repeat {
+            let start = Instant::now();
+            let res = execute(raw_cmd);
+            let elapsed = start.elapsed();
+
+            let res = match res {
+                Ok(res) => res,
+                Err(err) => {
+                    eprintln!("{label}: {err}");
+                    all_ok = false;
+                    break;
+                }
+            };
+
+            let (rendered, failed) = render(&res, options.pretty);
+            all_ok &= !failed;
+            writeln!(out, "{rendered}")?;
+            out.flush()?;
+
+            if options.time {
+                eprintln!(
+                    "{label} run {run}: {:.3} ms",
+                    elapsed.as_secs_f64() * 1000.0
+                );
+            }
+            timings.push(elapsed);
+        }
+
+        if options.time && options.repeat > 1 {
+            report_timings(&label, &timings);
+        }
+    }
+
+    Ok(all_ok)
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) if err.is_empty() => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("error: {err}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let data_dir = match &options.data_dir {
+        Some(path) => std::path::absolute(path),
+        None => std::env::current_dir(),
+    };
+    let res = data_dir.map_err(|err| err.to_string()).and_then(|path| {
+        let data_dir_cmd = json!({"cmd": "set_data_dir", "params": {"path": path}});
+        match render(&execute(&data_dir_cmd.to_string())?, false) {
+            (_, false) => Ok(path),
+            (res, true) => Err(res),
+        }
+    });
+    let data_dir = match res {
+        Ok(path) => path,
+        Err(err) => {
+            eprintln!("error: failed to set the data directory: {err}");
+            return ExitCode::from(2);
+        }
+    };
+
+    if let Some(path) = &options.replay {
+        let path = session_path(path, &data_dir);
+        let replay_cmd = json!({"cmd": "replay_session", "params": {"path": path}});
+        let input = io::Cursor::new(replay_cmd.to_string());
+        return exit_code(run(&options, input));
+    }
+
+    if let Some(path) = &options.record {
+        let path = session_path(path, &data_dir);
+        let record_cmd = json!({"cmd": "start_recording", "params": {"path": path}});
+        let res = execute(&record_cmd.to_string()).and_then(|res| match render(&res, false) {
+            (_, false) => Ok(()),
+            (res, true) => Err(res),
+        });
+        if let Err(err) = res {
+            eprintln!("error: failed to start recording: {err}");
+            return ExitCode::from(2);
+        }
+    }
+
+    let res = match &options.input {
+        Some(path) => match File::open(path) {
+            Ok(file) => run(&options, BufReader::new(file)),
+            Err(err) => {
+                eprintln!("error: failed to open {path}: {err}");
+                return ExitCode::from(2);
+            }
+        },
+        None => run(&options, io::stdin().lock()),
+    };
+
+    if options.record.is_some() {
+        let _ = execute(&json!({"cmd": "stop_recording"}).to_string());
+    }
+
+    exit_code(res)
+}
+
+/// Session files are resolved against the data directory like every other file, so absolute
+/// paths inside of it are made relative. Other paths are left for the command to reject.
+fn session_path(path: &str, data_dir: &Path) -> String {
+    match Path::new(path).strip_prefix(data_dir) {
+        Ok(relative) => relative.display().to_string(),
+        Err(_) => path.to_string(),
+    }
+}
+
+fn exit_code(res: io::Result<bool>) -> ExitCode {
+    match res {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
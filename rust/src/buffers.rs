@@ -0,0 +1,47 @@
+//! Binary buffers handed over by the host, so commands can refer to them by id instead of
+//! receiving their bytes encoded in the JSON command.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+lazy_static::lazy_static! {
+    static ref BUFFERS: Mutex<HashMap<u64, Arc<Vec<u8>>>> = Mutex::new(HashMap::new());
+}
+
+/// Stores a buffer, returning its id. Ids are never reused.
+pub fn insert(bytes: Vec<u8>) -> u64 {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut buffers) = BUFFERS.lock() {
+        buffers.insert(id, Arc::new(bytes));
+    }
+    id
+}
+
+pub fn get(id: u64) -> Option<Arc<Vec<u8>>> {
+    BUFFERS.lock().ok()?.get(&id).cloned()
+}
+
+/// Drops a buffer, returning whether it existed. Commands still using it keep their copy.
+pub fn remove(id: u64) -> bool {
+    BUFFERS
+        .lock()
+        .map(|mut buffers| buffers.remove(&id).is_some())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_lifecycle() {
+        let id = insert(vec![1, 2, 3]);
+        assert_eq!(get(id).as_deref(), Some(&vec![1, 2, 3]));
+        assert!(remove(id));
+        assert!(get(id).is_none());
+        assert!(!remove(id));
+    }
+}
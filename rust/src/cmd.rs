@@ -1,18 +1,58 @@
+#[cfg(feature = "image-processing")]
+use crate::commands::image_processing::{process_image, ImageProcessingInput};
 use crate::commands::{
+    benchmark::{benchmark, BenchmarkInput},
+    crypto::{
+        decrypt, encrypt, key_agreement, open, seal, x25519_keypair, DecryptInput, EncryptInput,
+        KeyAgreementInput, OpenInput, SealInput, X25519KeypairInput,
+    },
     fibonacci::{fibonacci, FibonacciInput},
-    hash::{hash_data, HashDataInput},
-    image_processing::{process_image, ImageProcessingInput},
+    hash::{
+        derive_key, hash_data, hash_file, hmac, DeriveKeyInput, HashDataInput, HashFileInput,
+        HmacInput,
+    },
+    merkle::{merkle, merkle_verify, MerkleInput, MerkleVerifyInput},
+    otp::{
+        hotp_generate, totp_generate, totp_verify, HotpGenerateInput, TotpGenerateInput,
+        TotpVerifyInput,
+    },
+    password::{
+        needs_rehash, password_hash, password_verify, NeedsRehashInput, PasswordHashInput,
+        PasswordVerifyInput,
+    },
     prime::{find_primes, FindPrimesInput},
-    sort::{sort_numbers, SortNumbersInput},
+    random::{random, RandomInput},
+    schema::{schema, SchemaInput},
+    sequence::{sequence, SequenceInput},
+    signing::{
+        convert_key, generate_keypair, sign, verify, ConvertKeyInput, GenerateKeypairInput,
+        SignInput, VerifyInput,
+    },
+    sort::{
+        sort_numbers, sort_records, sort_values, SortNumbersInput, SortRecordsInput,
+        SortValuesInput,
+    },
+    sort_file::{sort_file, SortFileInput},
+    stats::{stats, StatsInput},
+};
+use crate::data_dir::{set_data_dir, SetDataDirInput};
+use crate::error::{push_field_errors, CommandError, FieldError};
+use crate::progress::{progress, ProgressInput};
+use crate::session::{
+    replay_session, start_recording, stop_recording, ReplaySessionInput, StartRecordingInput,
 };
 use eyre::WrapErr;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::{Arc, Mutex};
+use validator::Validate;
 
 #[cfg(feature = "ts-rs")]
 use ts_rs::TS;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
 #[serde(tag = "cmd", content = "params", rename_all = "snake_case")]
@@ -21,21 +61,161 @@ pub enum Command {
     Logs,
     /// Calculate fibonacci number
     Fibonacci(FibonacciInput),
-    /// Hash data using SHA-256
+    /// Evaluate terms of a linear recurrence or another integer sequence
+    Sequence(SequenceInput),
+    /// Hash data with a selectable algorithm
     HashData(HashDataInput),
+    /// Hash a file in the data directory with one or more algorithms
+    HashFile(HashFileInput),
+    /// Compute or verify an HMAC
+    Hmac(HmacInput),
+    /// Derive a key with HKDF or PBKDF2
+    DeriveKey(DeriveKeyInput),
+    /// Hash a password into a PHC string with Argon2id or scrypt
+    PasswordHash(PasswordHashInput),
+    /// Verify a password against a PHC string
+    PasswordVerify(PasswordVerifyInput),
+    /// Check whether a PHC string uses outdated parameters
+    NeedsRehash(NeedsRehashInput),
+    /// Encrypt data into a self-describing envelope
+    Encrypt(EncryptInput),
+    /// Decrypt an envelope produced by `encrypt`
+    Decrypt(DecryptInput),
+    /// Generate an X25519 keypair
+    X25519Keypair(X25519KeypairInput),
+    /// Derive a shared key from an X25519 private key and a peer public key
+    KeyAgreement(KeyAgreementInput),
+    /// Encrypt data anonymously to an X25519 public key
+    Seal(SealInput),
+    /// Decrypt data produced by `seal`
+    Open(OpenInput),
+    /// Generate secure random bytes, numbers and identifiers, or pick and shuffle items
+    Random(RandomInput),
+    /// Generate an Ed25519 or ECDSA P-256 keypair
+    GenerateKeypair(GenerateKeypairInput),
+    /// Convert a signing key between the raw, PKCS#8 and JWK formats
+    ConvertKey(ConvertKeyInput),
+    /// Sign data with a private key
+    Sign(SignInput),
+    /// Verify a signature with a public key
+    Verify(VerifyInput),
+    /// Compute the Merkle root of a list of items, with inclusion proofs
+    Merkle(MerkleInput),
+    /// Check a Merkle inclusion proof against a root
+    MerkleVerify(MerkleVerifyInput),
+    /// Generate the current TOTP code of a secret or `otpauth://` URI
+    TotpGenerate(TotpGenerateInput),
+    /// Verify a TOTP code, allowing for clock drift
+    TotpVerify(TotpVerifyInput),
+    /// Generate the HOTP code of a counter
+    HotpGenerate(HotpGenerateInput),
     /// Sort an array of numbers
     SortNumbers(SortNumbersInput),
+    /// Sort integers, floats or strings
+    SortValues(SortValuesInput),
+    /// Sort JSON records by one or more keys
+    SortRecords(SortRecordsInput),
+    /// Sort the lines of a file larger than memory
+    SortFile(SortFileInput),
+    /// Compute summary statistics, quantiles, a histogram and correlation of numbers
+    Stats(StatsInput),
     /// Find prime numbers up to a limit
     FindPrimes(FindPrimesInput),
     /// Process image with filters
     #[cfg(feature = "image-processing")]
     ProcessImage(ImageProcessingInput),
+    /// Start recording every executed command to a session file
+    StartRecording(StartRecordingInput),
+    /// Stop the active session recording
+    StopRecording,
+    /// Re-execute a recorded session and diff the results
+    ReplaySession(ReplaySessionInput),
+    /// Time repeated executions of other commands
+    Benchmark(BenchmarkInput),
+    /// Get the JSON Schema of command parameters
+    Schema(SchemaInput),
+    /// Set the directory that file paths are relative to
+    SetDataDir(SetDataDirInput),
+    /// Poll the progress of a long running command
+    Progress(ProgressInput),
+}
+
+impl Command {
+    /// Checks the declared parameter constraints, reporting every violation at once.
+    pub fn validate(&self) -> Result<(), CommandError> {
+        let mut errors = Vec::new();
+        self.collect_field_errors("", &mut errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(CommandError::invalid_params(errors))
+        }
+    }
+
+    fn collect_field_errors(&self, prefix: &str, out: &mut Vec<FieldError>) {
+        let res = match self {
+            Command::Logs | Command::StopRecording | Command::Schema(_) => Ok(()),
+            Command::Fibonacci(input) => input.validate(),
+            Command::Sequence(input) => input.validate(),
+            Command::HashData(input) => input.validate(),
+            Command::HashFile(input) => input.validate(),
+            Command::Hmac(input) => input.validate(),
+            Command::DeriveKey(input) => input.validate(),
+            Command::PasswordHash(input) => input.validate(),
+            Command::PasswordVerify(input) => input.validate(),
+            Command::NeedsRehash(input) => input.validate(),
+            Command::Encrypt(input) => input.validate(),
+            Command::Decrypt(input) => input.validate(),
+            Command::X25519Keypair(input) => input.validate(),
+            Command::KeyAgreement(input) => input.validate(),
+            Command::Seal(input) => input.validate(),
+            Command::Open(input) => input.validate(),
+            Command::Random(input) => input.validate(),
+            Command::GenerateKeypair(input) => input.validate(),
+            Command::ConvertKey(input) => input.validate(),
+            Command::Sign(input) => input.validate(),
+            Command::Verify(input) => input.validate(),
+            Command::Merkle(input) => input.validate(),
+            Command::MerkleVerify(input) => input.validate(),
+            Command::TotpGenerate(input) => input.validate(),
+            Command::TotpVerify(input) => input.validate(),
+            Command::HotpGenerate(input) => input.validate(),
+            Command::SortNumbers(input) => input.validate(),
+            Command::SortValues(input) => input.validate(),
+            Command::SortRecords(input) => input.validate(),
+            Command::SortFile(input) => input.validate(),
+            Command::Stats(input) => input.validate(),
+            Command::FindPrimes(input) => input.validate(),
+            #[cfg(feature = "image-processing")]
+            Command::ProcessImage(input) => input.validate(),
+            Command::StartRecording(input) => input.validate(),
+            Command::ReplaySession(input) => input.validate(),
+            Command::SetDataDir(input) => input.validate(),
+            Command::Progress(input) => input.validate(),
+            Command::Benchmark(input) => {
+                let nested_prefix = format!("{prefix}command.params.");
+                input.command.collect_field_errors(&nested_prefix, out);
+                for (i, variant) in input.compare.iter().enumerate() {
+                    let nested_prefix = format!("{prefix}compare[{i}].params.");
+                    variant.collect_field_errors(&nested_prefix, out);
+                }
+                input.validate()
+            }
+        };
+
+        if let Err(errors) = res {
+            push_field_errors(&errors, prefix, out);
+        }
+    }
 }
 
 pub async fn execute_cmd(
     cmd: Arc<Command>,
     logs: &'static Mutex<Vec<String>>,
 ) -> Result<String, eyre::Error> {
+    cmd.validate()?;
+
     match &*cmd {
         Command::Logs => {
             let Ok(mut logs) = logs.lock() else {
@@ -46,18 +226,341 @@ pub async fn execute_cmd(
             parse_result(logs)
         }
         Command::Fibonacci(input) => parse_result(fibonacci(input).await?),
+        Command::Sequence(input) => parse_result(sequence(input).await?),
         Command::HashData(input) => parse_result(hash_data(input).await?),
+        Command::HashFile(input) => parse_result(hash_file(input).await?),
+        Command::Hmac(input) => parse_result(hmac(input).await?),
+        Command::DeriveKey(input) => parse_result(derive_key(input).await?),
+        Command::PasswordHash(input) => parse_result(password_hash(input).await?),
+        Command::PasswordVerify(input) => parse_result(password_verify(input).await?),
+        Command::NeedsRehash(input) => parse_result(needs_rehash(input).await?),
+        Command::Encrypt(input) => parse_result(encrypt(input).await?),
+        Command::Decrypt(input) => parse_result(decrypt(input).await?),
+        Command::X25519Keypair(input) => parse_result(x25519_keypair(input).await?),
+        Command::KeyAgreement(input) => parse_result(key_agreement(input).await?),
+        Command::Seal(input) => parse_result(seal(input).await?),
+        Command::Open(input) => parse_result(open(input).await?),
+        Command::Random(input) => parse_result(random(input).await?),
+        Command::GenerateKeypair(input) => parse_result(generate_keypair(input).await?),
+        Command::ConvertKey(input) => parse_result(convert_key(input).await?),
+        Command::Sign(input) => parse_result(sign(input).await?),
+        Command::Verify(input) => parse_result(verify(input).await?),
+        Command::Merkle(input) => parse_result(merkle(input).await?),
+        Command::MerkleVerify(input) => parse_result(merkle_verify(input).await?),
+        Command::TotpGenerate(input) => parse_result(totp_generate(input).await?),
+        Command::TotpVerify(input) => parse_result(totp_verify(input).await?),
+        Command::HotpGenerate(input) => parse_result(hotp_generate(input).await?),
         Command::SortNumbers(input) => parse_result(sort_numbers(input).await?),
+        Command::SortValues(input) => parse_result(sort_values(input).await?),
+        Command::SortRecords(input) => parse_result(sort_records(input).await?),
+        Command::SortFile(input) => parse_result(sort_file(input).await?),
+        Command::Stats(input) => parse_result(stats(input).await?),
         Command::FindPrimes(input) => parse_result(find_primes(input).await?),
         #[cfg(feature = "image-processing")]
         Command::ProcessImage(input) => parse_result(process_image(input).await?),
+        Command::StartRecording(input) => parse_result(start_recording(input)?),
+        Command::StopRecording => parse_result(stop_recording()?),
+        Command::ReplaySession(input) => parse_result(replay_session(input, logs).await?),
+        Command::Benchmark(input) => parse_result(benchmark(input, logs).await?),
+        Command::Schema(input) => parse_result(schema(input).await?),
+        Command::SetDataDir(input) => parse_result(set_data_dir(input)?),
+        Command::Progress(input) => parse_result(progress(input)?),
     }
 }
 
+/// Boxed variant of [`execute_cmd`] for commands that execute other commands.
+///
+/// The explicit return type breaks the recursion in the `execute_cmd` future type, which the
+/// compiler can't otherwise prove `Send` for.
+pub fn execute_nested(
+    cmd: Arc<Command>,
+    logs: &'static Mutex<Vec<String>>,
+) -> Pin<Box<dyn Future<Output = Result<String, eyre::Error>> + Send>> {
+    Box::pin(execute_cmd(cmd, logs))
+}
+
 fn parse_result(res: impl Serialize) -> Result<String, eyre::Error> {
     serde_json::to_string(&res).wrap_err("failed to serialize command execution result")
 }
 
+/// TypeScript signature of a command, used to generate the typed client.
+#[cfg(feature = "ts-rs")]
+#[derive(Debug)]
+pub struct CommandSignature {
+    /// Wire name of the command, as used in the `cmd` tag
+    pub cmd: &'static str,
+    pub params: Option<String>,
+    pub result: String,
+    /// Exported TypeScript types the signature refers to
+    pub imports: Vec<String>,
+}
+
+#[cfg(feature = "ts-rs")]
+impl CommandSignature {
+    fn new<R: TS + 'static>(cmd: &'static str) -> Self {
+        Self {
+            cmd,
+            params: None,
+            result: ts_type::<R>(),
+            imports: ts_imports::<R>(),
+        }
+    }
+
+    fn with_params<P: TS + 'static, R: TS + 'static>(cmd: &'static str) -> Self {
+        let mut signature = Self::new::<R>(cmd);
+        signature.params = Some(ts_type::<P>());
+        signature.imports.extend(ts_imports::<P>());
+        signature
+    }
+}
+
+/// Name of exported types, inline definition of everything else (e.g. `Array<string>`).
+#[cfg(feature = "ts-rs")]
+fn ts_type<T: TS + 'static>() -> String {
+    match ts_rs::Dependency::from_ty::<T>() {
+        Some(dependency) => dependency.ts_name,
+        None => T::inline(),
+    }
+}
+
+#[cfg(feature = "ts-rs")]
+fn ts_imports<T: TS + 'static>() -> Vec<String> {
+    match ts_rs::Dependency::from_ty::<T>() {
+        Some(dependency) => vec![dependency.ts_name],
+        None => T::dependencies().into_iter().map(|d| d.ts_name).collect(),
+    }
+}
+
+/// Signatures of every `Command` variant.
+///
+/// Kept next to the enum so that new commands declare their result type here as well,
+/// [`verify_command_signatures`] fails until both agree.
+#[cfg(feature = "ts-rs")]
+pub fn command_signatures() -> Vec<CommandSignature> {
+    use crate::commands::{
+        benchmark::BenchmarkResult,
+        crypto::{
+            DecryptResult, EncryptResult, KeyAgreementResult, SealResult, X25519KeypairResult,
+        },
+        fibonacci::FibonacciResult,
+        hash::{DeriveKeyResult, HashDataResult, HashFileResult, HmacResult},
+        merkle::{MerkleResult, MerkleVerifyResult},
+        otp::{HotpResult, TotpResult, TotpVerifyResult},
+        password::{NeedsRehashResult, PasswordHashResult, PasswordVerifyResult},
+        prime::FindPrimesResult,
+        random::RandomResult,
+        schema::SchemaResult,
+        sequence::SequenceResult,
+        signing::{KeyPairResult, SignResult, VerifyResult},
+        sort::{SortNumbersResult, SortRecordsResult, SortValuesResult},
+        sort_file::SortFileResult,
+        stats::StatsResult,
+    };
+    use crate::data_dir::DataDirStatus;
+    use crate::progress::Progress;
+    use crate::session::{RecordingStatus, ReplaySessionResult};
+
+    #[allow(unused_mut)]
+    let mut signatures = vec![
+        CommandSignature::new::<Vec<String>>("logs"),
+        CommandSignature::with_params::<FibonacciInput, FibonacciResult>("fibonacci"),
+        CommandSignature::with_params::<SequenceInput, SequenceResult>("sequence"),
+        CommandSignature::with_params::<HashDataInput, HashDataResult>("hash_data"),
+        CommandSignature::with_params::<HashFileInput, HashFileResult>("hash_file"),
+        CommandSignature::with_params::<HmacInput, HmacResult>("hmac"),
+        CommandSignature::with_params::<DeriveKeyInput, DeriveKeyResult>("derive_key"),
+        CommandSignature::with_params::<PasswordHashInput, PasswordHashResult>("password_hash"),
+        CommandSignature::with_params::<PasswordVerifyInput, PasswordVerifyResult>(
+            "password_verify",
+        ),
+        CommandSignature::with_params::<NeedsRehashInput, NeedsRehashResult>("needs_rehash"),
+        CommandSignature::with_params::<EncryptInput, EncryptResult>("encrypt"),
+        CommandSignature::with_params::<DecryptInput, DecryptResult>("decrypt"),
+        CommandSignature::with_params::<X25519KeypairInput, X25519KeypairResult>("x25519_keypair"),
+        CommandSignature::with_params::<KeyAgreementInput, KeyAgreementResult>("key_agreement"),
+        CommandSignature::with_params::<SealInput, SealResult>("seal"),
+        CommandSignature::with_params::<OpenInput, DecryptResult>("open"),
+        CommandSignature::with_params::<RandomInput, RandomResult>("random"),
+        CommandSignature::with_params::<GenerateKeypairInput, KeyPairResult>("generate_keypair"),
+        CommandSignature::with_params::<ConvertKeyInput, KeyPairResult>("convert_key"),
+        CommandSignature::with_params::<SignInput, SignResult>("sign"),
+        CommandSignature::with_params::<VerifyInput, VerifyResult>("verify"),
+        CommandSignature::with_params::<MerkleInput, MerkleResult>("merkle"),
+        CommandSignature::with_params::<MerkleVerifyInput, MerkleVerifyResult>("merkle_verify"),
+        CommandSignature::with_params::<TotpGenerateInput, TotpResult>("totp_generate"),
+        CommandSignature::with_params::<TotpVerifyInput, TotpVerifyResult>("totp_verify"),
+        CommandSignature::with_params::<HotpGenerateInput, HotpResult>("hotp_generate"),
+        CommandSignature::with_params::<SortNumbersInput, SortNumbersResult>("sort_numbers"),
+        CommandSignature::with_params::<SortValuesInput, SortValuesResult>("sort_values"),
+        CommandSignature::with_params::<SortRecordsInput, SortRecordsResult>("sort_records"),
+        CommandSignature::with_params::<SortFileInput, SortFileResult>("sort_file"),
+        CommandSignature::with_params::<StatsInput, StatsResult>("stats"),
+        CommandSignature::with_params::<FindPrimesInput, FindPrimesResult>("find_primes"),
+        CommandSignature::with_params::<StartRecordingInput, RecordingStatus>("start_recording"),
+        CommandSignature::new::<RecordingStatus>("stop_recording"),
+        CommandSignature::with_params::<ReplaySessionInput, ReplaySessionResult>("replay_session"),
+        CommandSignature::with_params::<BenchmarkInput, BenchmarkResult>("benchmark"),
+        CommandSignature::with_params::<SchemaInput, SchemaResult>("schema"),
+        CommandSignature::with_params::<SetDataDirInput, DataDirStatus>("set_data_dir"),
+        CommandSignature::with_params::<ProgressInput, Progress>("progress"),
+    ];
+
+    #[cfg(feature = "image-processing")]
+    signatures.push(CommandSignature::with_params::<
+        ImageProcessingInput,
+        crate::commands::image_processing::ImageProcessingResult,
+    >("process_image"));
+
+    signatures
+}
+
+/// Checks the signatures against the variants `ts-rs` derives from the `Command` enum.
+#[cfg(feature = "ts-rs")]
+pub fn verify_command_signatures(signatures: &[CommandSignature]) -> Result<(), String> {
+    let decl = Command::decl();
+    let variants: Vec<(&str, Option<&str>)> = decl
+        .split("{ \"cmd\": \"")
+        .skip(1)
+        .map(|variant| {
+            let (cmd, rest) = variant.split_once('"').unwrap_or((variant, ""));
+            let params = rest
+                .strip_prefix(", \"params\": ")
+                .and_then(|rest| rest.split_once(" }"))
+                .map(|(params, _)| params);
+            (cmd, params)
+        })
+        .collect();
+
+    for (cmd, params) in &variants {
+        let Some(signature) = signatures.iter().find(|s| s.cmd == *cmd) else {
+            return Err(format!("command `{cmd}` has no signature"));
+        };
+        if signature.params.as_deref() != *params {
+            return Err(format!(
+                "command `{cmd}` takes {params:?} but its signature declares {:?}",
+                signature.params
+            ));
+        }
+    }
+
+    if let Some(stale) = signatures
+        .iter()
+        .find(|s| !variants.iter().any(|(cmd, _)| *cmd == s.cmd))
+    {
+        return Err(format!("signature for unknown command `{}`", stale.cmd));
+    }
+
+    Ok(())
+}
+
+/// Checks that every literal `ts-rs` emits for the unit-only enum `T` is a spelling serde reads,
+/// and the one it writes back. Both derive `rename_all` on their own, and disagree on digits.
+#[cfg(feature = "ts-rs")]
+pub fn verify_enum_literals<T>() -> Result<(), String>
+where
+    T: TS + Serialize + serde::de::DeserializeOwned,
+{
+    let decl = T::decl();
+    for literal in decl.split('"').skip(1).step_by(2) {
+        let value: T = serde_json::from_value(serde_json::Value::from(literal)).map_err(|err| {
+            format!(
+                "`{}` literal \"{literal}\" isn't accepted: {err}",
+                T::name()
+            )
+        })?;
+        let written = serde_json::to_value(&value).map_err(|err| err.to_string())?;
+        if written != literal {
+            return Err(format!(
+                "`{}` literal \"{literal}\" is written back as {written}",
+                T::name()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Like [`verify_enum_literals`], for the `tag` values of an internally tagged enum `T`.
+#[cfg(feature = "ts-rs")]
+pub fn verify_tag_literals<T>(tag: &str) -> Result<(), String>
+where
+    T: TS + serde::de::DeserializeOwned,
+{
+    let decl = T::decl();
+    for variant in decl.split(&format!("{{ \"{tag}\": \"")).skip(1) {
+        let literal = variant.split('"').next().unwrap_or_default();
+        // Only the tag is given, so only an unknown variant means it's misspelled
+        let value = serde_json::json!({ tag: literal });
+        if let Err(err) = serde_json::from_value::<T>(value) {
+            if err.to_string().contains("unknown variant") {
+                return Err(format!(
+                    "`{}` {tag} \"{literal}\" isn't accepted: {err}",
+                    T::name()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks every enum spelling of the generated TypeScript against what serde accepts.
+#[cfg(feature = "ts-rs")]
+pub fn verify_enum_spellings() -> Result<(), String> {
+    use crate::commands::{
+        crypto::{CipherAlgorithm, PlaintextEncoding},
+        hash::{DataEncoding, HashAlgorithm, KdfAlgorithm, MacAlgorithm, OutputEncoding},
+        merkle::MerkleSide,
+        otp::OtpAlgorithm,
+        password::PasswordAlgorithm,
+        random::{RandomMode, UuidVersion},
+        sequence::Sequence,
+        signing::{KeyFormat, KeyKind, SignatureAlgorithm},
+        sort::{
+            KeyCoercion, NanPlacement, NullPlacement, RecordsOutput, SortAlgorithm, SortDirection,
+            SortValues, StringOrder,
+        },
+        sort_file::LineFormat,
+        stats::VarianceKind,
+    };
+    use crate::error::ErrorCode;
+    use crate::math::ArithmeticMode;
+    use crate::session::ReplayStatus;
+
+    verify_enum_literals::<ArithmeticMode>()?;
+    verify_enum_literals::<CipherAlgorithm>()?;
+    verify_enum_literals::<DataEncoding>()?;
+    verify_enum_literals::<ErrorCode>()?;
+    verify_enum_literals::<HashAlgorithm>()?;
+    verify_enum_literals::<KdfAlgorithm>()?;
+    verify_enum_literals::<KeyCoercion>()?;
+    verify_enum_literals::<KeyFormat>()?;
+    verify_enum_literals::<KeyKind>()?;
+    verify_enum_literals::<MacAlgorithm>()?;
+    verify_enum_literals::<MerkleSide>()?;
+    verify_enum_literals::<NanPlacement>()?;
+    verify_enum_literals::<NullPlacement>()?;
+    verify_enum_literals::<OtpAlgorithm>()?;
+    verify_enum_literals::<OutputEncoding>()?;
+    verify_enum_literals::<PasswordAlgorithm>()?;
+    verify_enum_literals::<PlaintextEncoding>()?;
+    verify_enum_literals::<RecordsOutput>()?;
+    verify_enum_literals::<ReplayStatus>()?;
+    verify_enum_literals::<SignatureAlgorithm>()?;
+    verify_enum_literals::<SortAlgorithm>()?;
+    verify_enum_literals::<SortDirection>()?;
+    verify_enum_literals::<StringOrder>()?;
+    verify_enum_literals::<UuidVersion>()?;
+    verify_enum_literals::<VarianceKind>()?;
+    #[cfg(feature = "image-processing")]
+    verify_enum_literals::<crate::commands::image_processing::ImageFilter>()?;
+
+    verify_tag_literals::<Command>("cmd")?;
+    verify_tag_literals::<LineFormat>("type")?;
+    verify_tag_literals::<RandomMode>("type")?;
+    verify_tag_literals::<Sequence>("family")?;
+    verify_tag_literals::<SortValues>("type")?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,7 +569,7 @@ mod tests {
     fn parse_fibonacci_cmd() {
         let cmd_str = r#"{"cmd": "fibonacci", "params": {"n": 10}}"#;
         match serde_json::from_str::<Command>(cmd_str) {
-            Ok(Command::Fibonacci(FibonacciInput { n })) => {
+            Ok(Command::Fibonacci(FibonacciInput { n, .. })) => {
                 assert_eq!(n, 10);
             }
             Ok(_) => panic!("wrong command"),
@@ -78,11 +581,56 @@ mod tests {
     fn parse_hash_cmd() {
         let cmd_str = r#"{"cmd": "hash_data", "params": {"data": "hello world"}}"#;
         match serde_json::from_str::<Command>(cmd_str) {
-            Ok(Command::HashData(HashDataInput { data })) => {
+            Ok(Command::HashData(HashDataInput { data, .. })) => {
                 assert_eq!(data, "hello world");
             }
             Ok(_) => panic!("wrong command"),
             Err(err) => panic!("failed to parse command: {err}"),
         }
     }
+
+    #[test]
+    fn validate_reports_field_errors() {
+        let cmd = Command::FindPrimes(FindPrimesInput {
+            start: None,
+            limit: u64::MAX,
+            use_parallel: false,
+            count_only: None,
+            page: None,
+        });
+        let err = cmd.validate().unwrap_err();
+        assert_eq!(err.code, crate::error::ErrorCode::InvalidParams);
+        assert_eq!(err.field_errors.len(), 1);
+        assert_eq!(err.field_errors[0].field, "limit");
+        assert_eq!(err.field_errors[0].code, "range");
+    }
+
+    #[test]
+    fn validate_nested_benchmark_commands() {
+        let cmd_str = r#"{"cmd": "benchmark", "params": {
+            "command": {"cmd": "fibonacci", "params": {"n": 4294967295, "mode": "big_int"}},
+            "iterations": 0, "warmup": 0
+        }}"#;
+        let cmd = serde_json::from_str::<Command>(cmd_str).unwrap();
+        let fields: Vec<String> = cmd
+            .validate()
+            .unwrap_err()
+            .field_errors
+            .into_iter()
+            .map(|e| e.field)
+            .collect();
+        assert_eq!(fields, vec!["command.params.n", "iterations"]);
+    }
+
+    #[cfg(feature = "ts-rs")]
+    #[test]
+    fn command_signatures_match_enum() {
+        verify_command_signatures(&command_signatures()).unwrap();
+    }
+
+    #[cfg(feature = "ts-rs")]
+    #[test]
+    fn enum_spellings_match_typescript() {
+        verify_enum_spellings().unwrap();
+    }
 }
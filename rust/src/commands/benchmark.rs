@@ -0,0 +1,229 @@
+use crate::cmd::{execute_nested, Command};
+use eyre::{eyre, Result, WrapErr};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use validator::Validate;
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+pub const MAX_BENCHMARK_ITERATIONS: u32 = 10_000;
+pub const MAX_BENCHMARK_WARMUP: u32 = 1_000;
+pub const MAX_BENCHMARK_VARIANTS: u64 = 16;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct BenchmarkInput {
+    pub command: Box<Command>,
+    /// Variants of the command to measure side by side with it
+    #[serde(default)]
+    #[validate(length(max = MAX_BENCHMARK_VARIANTS))]
+    pub compare: Vec<Command>,
+    #[serde(default = "default_iterations")]
+    #[validate(range(min = 1, max = MAX_BENCHMARK_ITERATIONS))]
+    pub iterations: u32,
+    #[serde(default = "default_warmup")]
+    #[validate(range(max = MAX_BENCHMARK_WARMUP))]
+    pub warmup: u32,
+}
+
+fn default_iterations() -> u32 {
+    10
+}
+
+fn default_warmup() -> u32 {
+    1
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct BenchmarkResult {
+    pub iterations: u32,
+    pub warmup: u32,
+    /// One entry per benchmarked command, starting with `command` itself
+    pub runs: Vec<BenchmarkRun>,
+    pub computation_time_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct BenchmarkRun {
+    pub command: Command,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub mean_ms: f64,
+    pub std_dev_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    /// Executions per second, based on the mean time
+    pub throughput: f64,
+    /// Mean time relative to the first run, below 1.0 means faster
+    pub relative_mean: f64,
+}
+
+pub async fn benchmark(
+    input: &BenchmarkInput,
+    logs: &'static Mutex<Vec<String>>,
+) -> Result<BenchmarkResult> {
+    let start = Instant::now();
+
+    if input.iterations == 0 {
+        return Err(eyre!("benchmark requires at least one iteration"));
+    }
+
+    let commands = std::iter::once(input.command.as_ref()).chain(input.compare.iter());
+    let mut runs: Vec<BenchmarkRun> = Vec::with_capacity(input.compare.len() + 1);
+
+    for command in commands {
+        check_benchmarkable(command)?;
+
+        let cmd = Arc::new(command.clone());
+        for _ in 0..input.warmup {
+            execute_nested(cmd.clone(), logs)
+                .await
+                .wrap_err("benchmarked command failed during warmup")?;
+        }
+
+        let mut timings = Vec::with_capacity(input.iterations as usize);
+        for iteration in 0..input.iterations {
+            let run_start = Instant::now();
+            execute_nested(cmd.clone(), logs)
+                .await
+                .wrap_err_with(|| format!("benchmarked command failed on iteration {iteration}"))?;
+            timings.push(run_start.elapsed());
+        }
+
+        let baseline_mean = runs.first().map(|run| run.mean_ms);
+        runs.push(summarize(command.clone(), timings, baseline_mean));
+    }
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(BenchmarkResult {
+        iterations: input.iterations,
+        warmup: input.warmup,
+        runs,
+        computation_time_ms,
+    })
+}
+
+fn check_benchmarkable(command: &Command) -> Result<()> {
+    match command {
+        Command::Benchmark(_) => Err(eyre!("benchmarks can't be nested")),
+        Command::StartRecording(_) | Command::StopRecording | Command::ReplaySession(_) => {
+            Err(eyre!("session commands can't be benchmarked"))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn summarize(
+    command: Command,
+    mut timings: Vec<Duration>,
+    baseline_mean: Option<f64>,
+) -> BenchmarkRun {
+    timings.sort_unstable();
+    let samples: Vec<f64> = timings.iter().map(|t| t.as_secs_f64() * 1000.0).collect();
+
+    let count = samples.len() as f64;
+    let mean_ms = samples.iter().sum::<f64>() / count;
+    let variance = samples.iter().map(|s| (s - mean_ms).powi(2)).sum::<f64>() / count;
+
+    BenchmarkRun {
+        command,
+        min_ms: samples[0],
+        max_ms: samples[samples.len() - 1],
+        mean_ms,
+        std_dev_ms: variance.sqrt(),
+        p50_ms: percentile(&samples, 50.0),
+        p90_ms: percentile(&samples, 90.0),
+        p95_ms: percentile(&samples, 95.0),
+        p99_ms: percentile(&samples, 99.0),
+        throughput: if mean_ms > 0.0 {
+            1000.0 / mean_ms
+        } else {
+            f64::INFINITY
+        },
+        relative_mean: match baseline_mean {
+            Some(baseline) if baseline > 0.0 => mean_ms / baseline,
+            _ => 1.0,
+        },
+    }
+}
+
+/// Nearest-rank percentile over sorted samples.
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::sort::{SortAlgorithm, SortNumbersInput};
+
+    static LOGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    fn sort_cmd(algorithm: SortAlgorithm) -> Command {
+        Command::SortNumbers(SortNumbersInput {
+            numbers: (0..1000).rev().collect(),
+            algorithm,
+            direction: None,
+            stable: None,
+            argsort: None,
+            dedup: None,
+            top_k: None,
+            bottom_k: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_benchmark_compare() {
+        let input = BenchmarkInput {
+            command: Box::new(sort_cmd(SortAlgorithm::Parallel)),
+            compare: vec![sort_cmd(SortAlgorithm::Sequential)],
+            iterations: 5,
+            warmup: 1,
+        };
+
+        let result = benchmark(&input, &LOGS).await.unwrap();
+        assert_eq!(result.runs.len(), 2);
+        assert_eq!(result.runs[0].relative_mean, 1.0);
+        for run in &result.runs {
+            assert!(run.min_ms <= run.p50_ms && run.p50_ms <= run.max_ms);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_benchmark_rejects_nesting() {
+        let inner = BenchmarkInput {
+            command: Box::new(sort_cmd(SortAlgorithm::Sequential)),
+            compare: vec![],
+            iterations: 1,
+            warmup: 0,
+        };
+        let input = BenchmarkInput {
+            command: Box::new(Command::Benchmark(inner)),
+            compare: vec![],
+            iterations: 1,
+            warmup: 0,
+        };
+
+        assert!(benchmark(&input, &LOGS).await.is_err());
+    }
+
+    #[test]
+    fn test_percentile() {
+        let samples: Vec<f64> = (1..=100).map(f64::from).collect();
+        assert_eq!(percentile(&samples, 50.0), 50.0);
+        assert_eq!(percentile(&samples, 99.0), 99.0);
+        assert_eq!(percentile(&[3.0], 90.0), 3.0);
+    }
+}
@@ -0,0 +1,853 @@
+//! Authenticated encryption of data at rest.
+//!
+//! Ciphertexts are wrapped in a self-describing envelope so they can be decrypted without
+//! knowing how they were produced:
+//!
+//! ```text
+//! version (1 byte) | algorithm (1 byte) | nonce (12 bytes) | ciphertext | tag (16 bytes)
+//! ```
+//!
+//! The version and algorithm bytes are authenticated along with the associated data.
+//!
+//! Data is sealed to an X25519 public key by prefixing the envelope with an ephemeral public key,
+//! the envelope key being derived with HKDF-SHA256 from the Diffie-Hellman shared secret, salted
+//! with both public keys.
+
+use crate::commands::hash::{
+    decode_input, encode_output, DataEncoding, MacAlgorithm, OutputEncoding, MAX_DERIVED_KEY_LEN,
+};
+use crate::error::{CommandError, ErrorCode, FieldError};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::Aes256Gcm;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::ChaCha20Poly1305;
+use eyre::Result;
+use hkdf::Hkdf;
+use rand::{rngs::OsRng, RngCore};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Sha512};
+use std::time::Instant;
+use validator::{Validate, ValidationError, ValidationErrors};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+/// Largest `data` accepted by `encrypt`, and `envelope` accepted by `decrypt`, in characters
+pub const MAX_CRYPTO_DATA_LEN: u64 = 64 * 1024 * 1024;
+
+/// Version byte of the envelopes written by `encrypt`
+pub const ENVELOPE_VERSION: u8 = 1;
+
+pub const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const HEADER_LEN: usize = 2 + NONCE_LEN;
+/// HKDF info of the envelope keys of sealed data
+const SEAL_INFO: &[u8] = b"react-native-rs seal v1";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub enum CipherAlgorithm {
+    #[default]
+    #[serde(rename = "aes_256_gcm")]
+    Aes256Gcm,
+    /// Faster than AES on devices without AES instructions
+    #[serde(rename = "chacha20_poly1305")]
+    ChaCha20Poly1305,
+}
+
+impl CipherAlgorithm {
+    fn id(self) -> u8 {
+        match self {
+            Self::Aes256Gcm => 1,
+            Self::ChaCha20Poly1305 => 2,
+        }
+    }
+
+    fn of_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(Self::Aes256Gcm),
+            2 => Some(Self::ChaCha20Poly1305),
+            _ => None,
+        }
+    }
+}
+
+/// How decrypted data is returned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub enum PlaintextEncoding {
+    /// As text, failing if the data isn't valid UTF-8
+    #[default]
+    #[serde(rename = "utf8")]
+    Utf8,
+    #[serde(rename = "base64")]
+    Base64,
+    #[serde(rename = "hex")]
+    Hex,
+    /// As a byte array
+    #[serde(rename = "bytes")]
+    Bytes,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct EncryptInput {
+    #[validate(length(max = MAX_CRYPTO_DATA_LEN))]
+    pub data: String,
+    /// How `data` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub encoding: Option<DataEncoding>,
+    /// 32-byte key
+    pub key: String,
+    /// How `key` is decoded, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub key_encoding: Option<DataEncoding>,
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub algorithm: Option<CipherAlgorithm>,
+    /// Associated data that is authenticated but not encrypted, it must be given again to decrypt
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub aad: Option<String>,
+    /// How `aad` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub aad_encoding: Option<DataEncoding>,
+    /// How the envelope is returned, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output_encoding: Option<OutputEncoding>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct EncryptResult {
+    /// Encoded envelope, empty with the `bytes` encoding
+    pub envelope: String,
+    /// Envelope bytes, only set with the `bytes` encoding
+    pub bytes: Option<Vec<u8>>,
+    pub algorithm: CipherAlgorithm,
+    pub output_encoding: OutputEncoding,
+    pub computation_time_ms: u64,
+}
+
+pub async fn encrypt(input: &EncryptInput) -> Result<EncryptResult> {
+    let start = Instant::now();
+    let algorithm = input.algorithm.unwrap_or_default();
+    let output_encoding = input.output_encoding.unwrap_or(OutputEncoding::Base64);
+
+    let data = decode_input("data", &input.data, input.encoding.unwrap_or_default())?;
+    let key = decode_key("key", &input.key, input.key_encoding)?;
+    let aad = decode_aad(input.aad.as_deref(), input.aad_encoding)?;
+
+    let envelope = seal_envelope(algorithm, &key, &aad, &data);
+    let (envelope, bytes) = encode_output(envelope, output_encoding);
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(EncryptResult {
+        envelope,
+        bytes,
+        algorithm,
+        output_encoding,
+        computation_time_ms,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct DecryptInput {
+    /// Envelope produced by `encrypt`
+    #[validate(length(max = MAX_CRYPTO_DATA_LEN))]
+    pub envelope: String,
+    /// How `envelope` is decoded, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub encoding: Option<DataEncoding>,
+    /// 32-byte key
+    pub key: String,
+    /// How `key` is decoded, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub key_encoding: Option<DataEncoding>,
+    /// The associated data given to `encrypt`
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub aad: Option<String>,
+    /// How `aad` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub aad_encoding: Option<DataEncoding>,
+    /// How the decrypted data is returned, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output_encoding: Option<PlaintextEncoding>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct DecryptResult {
+    /// Decrypted data, empty with the `bytes` encoding
+    pub data: String,
+    /// Decrypted bytes, only set with the `bytes` encoding
+    pub bytes: Option<Vec<u8>>,
+    /// Algorithm read from the envelope
+    pub algorithm: CipherAlgorithm,
+    pub output_encoding: PlaintextEncoding,
+    pub computation_time_ms: u64,
+}
+
+pub async fn decrypt(input: &DecryptInput) -> Result<DecryptResult> {
+    let start = Instant::now();
+    let output_encoding = input.output_encoding.unwrap_or_default();
+
+    let envelope_encoding = input.encoding.unwrap_or(DataEncoding::Base64);
+    let envelope = decode_input("envelope", &input.envelope, envelope_encoding)?;
+    let key = decode_key("key", &input.key, input.key_encoding)?;
+    let aad = decode_aad(input.aad.as_deref(), input.aad_encoding)?;
+
+    let (algorithm, plaintext) = open_envelope("envelope", &key, &aad, &envelope)?;
+    let (data, bytes) = encode_plaintext(plaintext, output_encoding)?;
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(DecryptResult {
+        data,
+        bytes,
+        algorithm,
+        output_encoding,
+        computation_time_ms,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct X25519KeypairInput {
+    /// How the keys are returned, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output_encoding: Option<OutputEncoding>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct X25519KeypairResult {
+    /// Encoded 32-byte private key, empty with the `bytes` encoding
+    pub private_key: String,
+    /// Private key bytes, only set with the `bytes` encoding
+    pub private_key_bytes: Option<Vec<u8>>,
+    /// Encoded 32-byte public key, empty with the `bytes` encoding
+    pub public_key: String,
+    /// Public key bytes, only set with the `bytes` encoding
+    pub public_key_bytes: Option<Vec<u8>>,
+    pub output_encoding: OutputEncoding,
+    pub computation_time_ms: u64,
+}
+
+pub async fn x25519_keypair(input: &X25519KeypairInput) -> Result<X25519KeypairResult> {
+    let start = Instant::now();
+    let output_encoding = input.output_encoding.unwrap_or(OutputEncoding::Base64);
+
+    let secret = StaticSecret::random_from_rng(OsRng);
+    let public = PublicKey::from(&secret);
+    let (private_key, private_key_bytes) =
+        encode_output(secret.to_bytes().to_vec(), output_encoding);
+    let (public_key, public_key_bytes) = encode_output(public.to_bytes().to_vec(), output_encoding);
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(X25519KeypairResult {
+        private_key,
+        private_key_bytes,
+        public_key,
+        public_key_bytes,
+        output_encoding,
+        computation_time_ms,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct KeyAgreementInput {
+    /// Our X25519 private key
+    pub private_key: String,
+    /// The peer's X25519 public key
+    pub public_key: String,
+    /// How both keys are decoded, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub key_encoding: Option<DataEncoding>,
+    /// Hash function of the HKDF step, `sha256` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub hash: Option<MacAlgorithm>,
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub salt: Option<String>,
+    /// How `salt` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub salt_encoding: Option<DataEncoding>,
+    /// Context binding the key to its purpose
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub info: Option<String>,
+    /// How `info` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub info_encoding: Option<DataEncoding>,
+    /// Length of the derived key in bytes, 32 by default
+    #[serde(default = "default_key_length")]
+    #[schemars(range(min = 1, max = MAX_DERIVED_KEY_LEN))]
+    pub length: u32,
+    /// How the key is returned, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output_encoding: Option<OutputEncoding>,
+}
+
+fn default_key_length() -> u32 {
+    KEY_LEN as u32
+}
+
+impl Validate for KeyAgreementInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.length == 0 || self.length > MAX_DERIVED_KEY_LEN {
+            let mut err = ValidationError::new("range");
+            err.add_param("min".into(), &1);
+            err.add_param("max".into(), &MAX_DERIVED_KEY_LEN);
+            errors.add("length", err);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct KeyAgreementResult {
+    /// Encoded derived key, empty with the `bytes` encoding
+    pub key: String,
+    /// Derived key bytes, only set with the `bytes` encoding
+    pub bytes: Option<Vec<u8>>,
+    pub hash: MacAlgorithm,
+    pub output_encoding: OutputEncoding,
+    pub computation_time_ms: u64,
+}
+
+pub async fn key_agreement(input: &KeyAgreementInput) -> Result<KeyAgreementResult> {
+    let start = Instant::now();
+    let hash = input.hash.unwrap_or_default();
+    let output_encoding = input.output_encoding.unwrap_or(OutputEncoding::Base64);
+
+    let private_key = decode_key("private_key", &input.private_key, input.key_encoding)?;
+    let public_key = decode_key("public_key", &input.public_key, input.key_encoding)?;
+    let shared = shared_secret("public_key", &StaticSecret::from(private_key), public_key)?;
+    let salt = input
+        .salt
+        .as_deref()
+        .map(|salt| decode_input("salt", salt, input.salt_encoding.unwrap_or_default()))
+        .transpose()?;
+    let info = match input.info.as_deref() {
+        Some(info) => decode_input("info", info, input.info_encoding.unwrap_or_default())?.to_vec(),
+        None => Vec::new(),
+    };
+
+    let mut key = vec![0; input.length as usize];
+    let salt = salt.as_deref();
+    match hash {
+        MacAlgorithm::Sha256 => Hkdf::<Sha256>::new(salt, &shared).expand(&info, &mut key),
+        MacAlgorithm::Sha512 => Hkdf::<Sha512>::new(salt, &shared).expand(&info, &mut key),
+    }
+    .map_err(|_| eyre::eyre!("HKDF can't derive {} bytes", input.length))?;
+    let (key, bytes) = encode_output(key, output_encoding);
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(KeyAgreementResult {
+        key,
+        bytes,
+        hash,
+        output_encoding,
+        computation_time_ms,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SealInput {
+    #[validate(length(max = MAX_CRYPTO_DATA_LEN))]
+    pub data: String,
+    /// How `data` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub encoding: Option<DataEncoding>,
+    /// The recipient's X25519 public key
+    pub public_key: String,
+    /// How `public_key` is decoded, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub key_encoding: Option<DataEncoding>,
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub algorithm: Option<CipherAlgorithm>,
+    /// Associated data that is authenticated but not encrypted, it must be given again to open
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub aad: Option<String>,
+    /// How `aad` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub aad_encoding: Option<DataEncoding>,
+    /// How the sealed data is returned, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output_encoding: Option<OutputEncoding>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SealResult {
+    /// Encoded ephemeral public key followed by the envelope, empty with the `bytes` encoding
+    pub sealed: String,
+    /// Sealed bytes, only set with the `bytes` encoding
+    pub bytes: Option<Vec<u8>>,
+    pub algorithm: CipherAlgorithm,
+    pub output_encoding: OutputEncoding,
+    pub computation_time_ms: u64,
+}
+
+pub async fn seal(input: &SealInput) -> Result<SealResult> {
+    let start = Instant::now();
+    let algorithm = input.algorithm.unwrap_or_default();
+    let output_encoding = input.output_encoding.unwrap_or(OutputEncoding::Base64);
+
+    let data = decode_input("data", &input.data, input.encoding.unwrap_or_default())?;
+    let recipient = decode_key("public_key", &input.public_key, input.key_encoding)?;
+    let aad = decode_aad(input.aad.as_deref(), input.aad_encoding)?;
+
+    let ephemeral = StaticSecret::random_from_rng(OsRng);
+    let ephemeral_public = PublicKey::from(&ephemeral).to_bytes();
+    let shared = shared_secret("public_key", &ephemeral, recipient)?;
+    let key = seal_key(&shared, ephemeral_public, recipient);
+    let envelope = seal_envelope(algorithm, &key, &aad, &data);
+    let (sealed, bytes) = encode_output(
+        [&ephemeral_public, envelope.as_slice()].concat(),
+        output_encoding,
+    );
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(SealResult {
+        sealed,
+        bytes,
+        algorithm,
+        output_encoding,
+        computation_time_ms,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct OpenInput {
+    /// Data produced by `seal`
+    #[validate(length(max = MAX_CRYPTO_DATA_LEN))]
+    pub sealed: String,
+    /// How `sealed` is decoded, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub encoding: Option<DataEncoding>,
+    /// The recipient's X25519 private key
+    pub private_key: String,
+    /// How `private_key` is decoded, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub key_encoding: Option<DataEncoding>,
+    /// The associated data given to `seal`
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub aad: Option<String>,
+    /// How `aad` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub aad_encoding: Option<DataEncoding>,
+    /// How the opened data is returned, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output_encoding: Option<PlaintextEncoding>,
+}
+
+pub async fn open(input: &OpenInput) -> Result<DecryptResult> {
+    let start = Instant::now();
+    let output_encoding = input.output_encoding.unwrap_or_default();
+
+    let sealed_encoding = input.encoding.unwrap_or(DataEncoding::Base64);
+    let sealed = decode_input("sealed", &input.sealed, sealed_encoding)?;
+    let private_key = decode_key("private_key", &input.private_key, input.key_encoding)?;
+    let aad = decode_aad(input.aad.as_deref(), input.aad_encoding)?;
+
+    if sealed.len() < KEY_LEN {
+        return Err(format_error("sealed", "too short to be sealed data").into());
+    }
+    let (ephemeral_public, envelope) = sealed.split_at(KEY_LEN);
+    let ephemeral_public = ephemeral_public
+        .try_into()
+        .expect("the slice is a key long");
+    let secret = StaticSecret::from(private_key);
+    let recipient = PublicKey::from(&secret).to_bytes();
+    let shared = shared_secret("sealed", &secret, ephemeral_public)?;
+    let key = seal_key(&shared, ephemeral_public, recipient);
+    let (algorithm, plaintext) = open_envelope("sealed", &key, &aad, envelope)?;
+    let (data, bytes) = encode_plaintext(plaintext, output_encoding)?;
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(DecryptResult {
+        data,
+        bytes,
+        algorithm,
+        output_encoding,
+        computation_time_ms,
+    })
+}
+
+/// Computes the X25519 shared secret, rejecting low order public keys that make it predictable.
+fn shared_secret(
+    field: &str,
+    secret: &StaticSecret,
+    public_key: [u8; KEY_LEN],
+) -> Result<[u8; KEY_LEN], CommandError> {
+    let shared = secret.diffie_hellman(&PublicKey::from(public_key));
+    if !shared.was_contributory() {
+        return Err(format_error(field, "not a valid X25519 public key"));
+    }
+    Ok(shared.to_bytes())
+}
+
+/// Derives the envelope key of sealed data from the shared secret and both public keys.
+fn seal_key(
+    shared: &[u8; KEY_LEN],
+    ephemeral: [u8; KEY_LEN],
+    recipient: [u8; KEY_LEN],
+) -> [u8; KEY_LEN] {
+    let mut key = [0; KEY_LEN];
+    Hkdf::<Sha256>::new(Some(&[ephemeral, recipient].concat()), shared)
+        .expand(SEAL_INFO, &mut key)
+        .expect("the key is shorter than the HKDF limit");
+    key
+}
+
+/// Encrypts `data` into an envelope with a random nonce.
+fn seal_envelope(algorithm: CipherAlgorithm, key: &[u8], aad: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut header = [0; HEADER_LEN];
+    header[0] = ENVELOPE_VERSION;
+    header[1] = algorithm.id();
+    OsRng.fill_bytes(&mut header[2..]);
+
+    let nonce = header[2..].into();
+    let aad = [&header, aad].concat();
+    let payload = Payload {
+        msg: data,
+        aad: &aad,
+    };
+    let ciphertext = match algorithm {
+        CipherAlgorithm::Aes256Gcm => Aes256Gcm::new(key.into()).encrypt(nonce, payload),
+        CipherAlgorithm::ChaCha20Poly1305 => {
+            ChaCha20Poly1305::new(key.into()).encrypt(nonce, payload)
+        }
+    }
+    .expect("the data is smaller than the AEAD limits");
+
+    [&header, ciphertext.as_slice()].concat()
+}
+
+/// Decrypts the envelope in the parameter `field`, reporting malformed envelopes on it.
+fn open_envelope(
+    field: &str,
+    key: &[u8],
+    aad: &[u8],
+    envelope: &[u8],
+) -> Result<(CipherAlgorithm, Vec<u8>), CommandError> {
+    if envelope.len() < HEADER_LEN + TAG_LEN {
+        return Err(format_error(field, "too short to be an envelope"));
+    }
+    let (header, ciphertext) = envelope.split_at(HEADER_LEN);
+    if header[0] != ENVELOPE_VERSION {
+        return Err(format_error(
+            field,
+            format!("unsupported version {}", header[0]),
+        ));
+    }
+    let algorithm = CipherAlgorithm::of_id(header[1])
+        .ok_or_else(|| format_error(field, format!("unknown algorithm {}", header[1])))?;
+
+    let nonce = header[2..].into();
+    let aad = [header, aad].concat();
+    let payload = Payload {
+        msg: ciphertext,
+        aad: &aad,
+    };
+    let plaintext = match algorithm {
+        CipherAlgorithm::Aes256Gcm => Aes256Gcm::new(key.into()).decrypt(nonce, payload),
+        CipherAlgorithm::ChaCha20Poly1305 => {
+            ChaCha20Poly1305::new(key.into()).decrypt(nonce, payload)
+        }
+    }
+    .map_err(|_| {
+        CommandError::new(
+            ErrorCode::AuthenticationFailed,
+            "decryption failed: wrong key or associated data, or tampered envelope",
+        )
+    })?;
+
+    Ok((algorithm, plaintext))
+}
+
+fn encode_plaintext(
+    plaintext: Vec<u8>,
+    encoding: PlaintextEncoding,
+) -> Result<(String, Option<Vec<u8>>)> {
+    Ok(match encoding {
+        PlaintextEncoding::Utf8 => match String::from_utf8(plaintext) {
+            Ok(data) => (data, None),
+            Err(_) => eyre::bail!("the decrypted data isn't UTF-8, use another output_encoding"),
+        },
+        PlaintextEncoding::Base64 => (STANDARD.encode(plaintext), None),
+        PlaintextEncoding::Hex => encode_output(plaintext, OutputEncoding::Hex),
+        PlaintextEncoding::Bytes => (String::new(), Some(plaintext)),
+    })
+}
+
+/// Decodes a 32-byte key parameter, base64 by default.
+fn decode_key(
+    field: &str,
+    key: &str,
+    encoding: Option<DataEncoding>,
+) -> Result<[u8; KEY_LEN], CommandError> {
+    let key = decode_input(field, key, encoding.unwrap_or(DataEncoding::Base64))?;
+    key.as_ref().try_into().map_err(|_| {
+        CommandError::invalid_params(vec![FieldError {
+            field: field.to_string(),
+            code: "length".to_string(),
+            msg: format!("must be {KEY_LEN} bytes, got {}", key.len()),
+        }])
+    })
+}
+
+fn decode_aad(aad: Option<&str>, encoding: Option<DataEncoding>) -> Result<Vec<u8>, CommandError> {
+    match aad {
+        Some(aad) => Ok(decode_input("aad", aad, encoding.unwrap_or_default())?.to_vec()),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn format_error(field: &str, msg: impl Into<String>) -> CommandError {
+    CommandError::invalid_params(vec![FieldError {
+        field: field.to_string(),
+        code: "format".to_string(),
+        msg: msg.into(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+
+    fn encrypt_input(algorithm: CipherAlgorithm, aad: Option<&str>) -> EncryptInput {
+        EncryptInput {
+            data: "attack at dawn".to_string(),
+            encoding: None,
+            key: KEY.to_string(),
+            key_encoding: None,
+            algorithm: Some(algorithm),
+            aad: aad.map(str::to_string),
+            aad_encoding: None,
+            output_encoding: None,
+        }
+    }
+
+    fn decrypt_input(envelope: String, aad: Option<&str>) -> DecryptInput {
+        DecryptInput {
+            envelope,
+            encoding: None,
+            key: KEY.to_string(),
+            key_encoding: None,
+            aad: aad.map(str::to_string),
+            aad_encoding: None,
+            output_encoding: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_round_trip() {
+        for algorithm in [
+            CipherAlgorithm::Aes256Gcm,
+            CipherAlgorithm::ChaCha20Poly1305,
+        ] {
+            let input = encrypt_input(algorithm, Some("record 7"));
+            let first = encrypt(&input).await.unwrap();
+            let second = encrypt(&input).await.unwrap();
+            // Fresh nonces make every envelope different
+            assert_ne!(first.envelope, second.envelope);
+
+            let envelope = STANDARD.decode(&first.envelope).unwrap();
+            assert_eq!(envelope[..2], [ENVELOPE_VERSION, algorithm.id()]);
+            assert_eq!(envelope.len(), HEADER_LEN + 14 + TAG_LEN);
+
+            let result = decrypt(&decrypt_input(first.envelope, Some("record 7")))
+                .await
+                .unwrap();
+            assert_eq!(result.data, "attack at dawn");
+            assert_eq!(result.algorithm, algorithm);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_failures() {
+        let envelope = encrypt(&encrypt_input(CipherAlgorithm::Aes256Gcm, Some("record 7")))
+            .await
+            .unwrap()
+            .envelope;
+
+        let err = decrypt(&decrypt_input(envelope.clone(), Some("record 8")))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            crate::error::error_code(&err),
+            ErrorCode::AuthenticationFailed
+        );
+
+        // Flipping the algorithm byte is caught by the authentication of the header
+        let mut tampered = STANDARD.decode(&envelope).unwrap();
+        tampered[1] = CipherAlgorithm::ChaCha20Poly1305.id();
+        let err = decrypt(&decrypt_input(STANDARD.encode(tampered), Some("record 7")))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            crate::error::error_code(&err),
+            ErrorCode::AuthenticationFailed
+        );
+
+        let err = decrypt(&decrypt_input("AQE=".to_string(), None))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            crate::error::field_errors(&err).unwrap()[0].field,
+            "envelope"
+        );
+    }
+
+    async fn keypair() -> (String, String) {
+        let keypair = x25519_keypair(&X25519KeypairInput {
+            output_encoding: None,
+        })
+        .await
+        .unwrap();
+        (keypair.private_key, keypair.public_key)
+    }
+
+    #[tokio::test]
+    async fn test_key_agreement() {
+        let (alice_private, alice_public) = keypair().await;
+        let (bob_private, bob_public) = keypair().await;
+        let agree = |private_key: String, public_key: String| KeyAgreementInput {
+            private_key,
+            public_key,
+            key_encoding: None,
+            hash: None,
+            salt: None,
+            salt_encoding: None,
+            info: Some("chat v1".to_string()),
+            info_encoding: None,
+            length: 32,
+            output_encoding: None,
+        };
+
+        let alice = key_agreement(&agree(alice_private.clone(), bob_public))
+            .await
+            .unwrap();
+        let bob = key_agreement(&agree(bob_private, alice_public))
+            .await
+            .unwrap();
+        assert_eq!(alice.key, bob.key);
+
+        // The all-zero public key has a low order and would yield a predictable secret
+        let err = key_agreement(&agree(alice_private, STANDARD.encode([0; KEY_LEN])))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            crate::error::field_errors(&err).unwrap()[0].field,
+            "public_key"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_seal_open() {
+        let (private_key, public_key) = keypair().await;
+        let (other_private_key, _) = keypair().await;
+
+        let sealed = seal(&SealInput {
+            data: "hi bob".to_string(),
+            encoding: None,
+            public_key,
+            key_encoding: None,
+            algorithm: Some(CipherAlgorithm::ChaCha20Poly1305),
+            aad: None,
+            aad_encoding: None,
+            output_encoding: None,
+        })
+        .await
+        .unwrap()
+        .sealed;
+        let open_input = |private_key: String| OpenInput {
+            sealed: sealed.clone(),
+            encoding: None,
+            private_key,
+            key_encoding: None,
+            aad: None,
+            aad_encoding: None,
+            output_encoding: None,
+        };
+
+        let result = open(&open_input(private_key)).await.unwrap();
+        assert_eq!(result.data, "hi bob");
+        assert_eq!(result.algorithm, CipherAlgorithm::ChaCha20Poly1305);
+
+        let err = open(&open_input(other_private_key)).await.unwrap_err();
+        assert_eq!(
+            crate::error::error_code(&err),
+            ErrorCode::AuthenticationFailed
+        );
+    }
+
+    #[cfg(feature = "ts-rs")]
+    #[test]
+    fn test_enum_literals_match_typescript() {
+        crate::cmd::verify_enum_literals::<PlaintextEncoding>().unwrap();
+    }
+}
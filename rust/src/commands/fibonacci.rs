@@ -1,76 +1,199 @@
+use crate::math::{
+    check_modulus, fibonacci_pair_big, fibonacci_pair_mod, fibonacci_u64, ArithmeticMode,
+    MAX_FIBONACCI_U64_N,
+};
 use eyre::Result;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
+use validator::{Validate, ValidationError, ValidationErrors};
 
 #[cfg(feature = "ts-rs")]
 use ts_rs::TS;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Largest `n` accepted by the `fibonacci` command in `big_int` mode
+pub const MAX_FIBONACCI_N: u64 = 10_000_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
 pub struct FibonacciInput {
-    pub n: u32,
+    /// Index of the Fibonacci number, at most 10 000 000 in `big_int` mode
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub n: u64,
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub mode: Option<ArithmeticMode>,
+    /// Required in `modular` mode
+    #[serde(default)]
+    #[schemars(range(min = 1))]
+    #[cfg_attr(feature = "ts-rs", ts(optional, type = "number"))]
+    pub modulus: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
 pub struct FibonacciResult {
+    /// F(n) in `fixed` mode, saturated at `u64::MAX` when `overflowed`, and F(n) mod
+    /// `modulus` in `modular` mode
     pub value: u64,
+    /// Exact decimal digits of F(n), only set in `big_int` mode
+    pub decimal: Option<String>,
+    /// Whether F(n) doesn't fit in `value`
+    pub overflowed: bool,
+    pub mode: ArithmeticMode,
     pub computation_time_ms: u64,
-    pub n: u32,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub n: u64,
 }
 
 pub async fn fibonacci(input: &FibonacciInput) -> Result<FibonacciResult> {
     let start = Instant::now();
-    
-    // Use iterative approach for better performance
-    let value = if input.n == 0 {
-        0
-    } else if input.n == 1 {
-        1
-    } else {
-        let mut prev = 0u64;
-        let mut curr = 1u64;
-        
-        for _ in 2..=input.n {
-            let next = prev.saturating_add(curr);
-            prev = curr;
-            curr = next;
+    let mode = input.mode.unwrap_or_default();
+
+    let overflowed = mode != ArithmeticMode::Modular && input.n > MAX_FIBONACCI_U64_N;
+    let (value, decimal) = match mode {
+        ArithmeticMode::Fixed => (fibonacci_u64(input.n).unwrap_or(u64::MAX), None),
+        ArithmeticMode::BigInt => {
+            let (exact, _) = fibonacci_pair_big(input.n);
+            let value = u64::try_from(&exact).unwrap_or(u64::MAX);
+            (value, Some(exact.to_string()))
+        }
+        ArithmeticMode::Modular => {
+            let (value, _) = fibonacci_pair_mod(input.n, input.modulus.unwrap_or(1));
+            (value, None)
         }
-        
-        curr
     };
-    
+
     let computation_time_ms = start.elapsed().as_millis() as u64;
-    
+
     Ok(FibonacciResult {
         value,
+        decimal,
+        overflowed,
+        mode,
         computation_time_ms,
         n: input.n,
     })
 }
 
+// Implemented by hand since the limits depend on the mode
+impl Validate for FibonacciInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mode = self.mode.unwrap_or_default();
+        let mut errors = ValidationErrors::new();
+
+        if mode == ArithmeticMode::BigInt && self.n > MAX_FIBONACCI_N {
+            let mut err = ValidationError::new("range");
+            err.add_param("max".into(), &MAX_FIBONACCI_N);
+            errors.add("n", err);
+        }
+        check_modulus(mode, self.modulus, &mut errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn input(n: u64, mode: ArithmeticMode, modulus: Option<u64>) -> FibonacciInput {
+        FibonacciInput {
+            n,
+            mode: Some(mode),
+            modulus,
+        }
+    }
+
     #[tokio::test]
     async fn test_fibonacci() {
-        let input = FibonacciInput { n: 10 };
+        let input = FibonacciInput {
+            n: 10,
+            mode: None,
+            modulus: None,
+        };
         let result = fibonacci(&input).await.unwrap();
         assert_eq!(result.value, 55);
         assert_eq!(result.n, 10);
+        assert!(!result.overflowed);
     }
 
     #[tokio::test]
     async fn test_fibonacci_edge_cases() {
         // Test n = 0
-        let result = fibonacci(&FibonacciInput { n: 0 }).await.unwrap();
+        let result = fibonacci(&input(0, ArithmeticMode::Fixed, None))
+            .await
+            .unwrap();
         assert_eq!(result.value, 0);
 
         // Test n = 1
-        let result = fibonacci(&FibonacciInput { n: 1 }).await.unwrap();
+        let result = fibonacci(&input(1, ArithmeticMode::Fixed, None))
+            .await
+            .unwrap();
         assert_eq!(result.value, 1);
+
+        // Largest value that fits, then the first one that doesn't
+        let result = fibonacci(&input(93, ArithmeticMode::Fixed, None))
+            .await
+            .unwrap();
+        assert_eq!(result.value, 12_200_160_415_121_876_738);
+        assert!(!result.overflowed);
+        let result = fibonacci(&input(94, ArithmeticMode::Fixed, None))
+            .await
+            .unwrap();
+        assert_eq!(result.value, u64::MAX);
+        assert!(result.overflowed);
+    }
+
+    #[tokio::test]
+    async fn test_fibonacci_big_int() {
+        let result = fibonacci(&input(100, ArithmeticMode::BigInt, None))
+            .await
+            .unwrap();
+        assert_eq!(result.decimal.as_deref(), Some("354224848179261915075"));
+        assert!(result.overflowed);
+
+        for n in 0..=MAX_FIBONACCI_U64_N {
+            let (exact, _) = fibonacci_pair_big(n);
+            assert_eq!(Some(exact), fibonacci_u64(n).map(dashu_int::UBig::from));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fibonacci_modular() {
+        // F(100) mod 1_000_000_007
+        let result = fibonacci(&input(100, ArithmeticMode::Modular, Some(1_000_000_007)))
+            .await
+            .unwrap();
+        assert_eq!(result.value, 687_995_182);
+
+        // Pisano period of 10 is 60
+        let modular = |n, m| fibonacci_pair_mod(n, m).0;
+        assert_eq!(modular(u64::MAX, 10), modular(u64::MAX % 60, 10));
+        assert_eq!(modular(12, 1), 0);
+        assert_eq!(Some(modular(90, u64::MAX)), fibonacci_u64(90));
+    }
+
+    #[test]
+    fn test_fibonacci_mode_params() {
+        assert!(input(10, ArithmeticMode::Modular, None).validate().is_err());
+        assert!(input(10, ArithmeticMode::Modular, Some(0))
+            .validate()
+            .is_err());
+        assert!(input(10, ArithmeticMode::Fixed, Some(7))
+            .validate()
+            .is_err());
+        assert!(input(MAX_FIBONACCI_N + 1, ArithmeticMode::BigInt, None)
+            .validate()
+            .is_err());
+        assert!(input(u64::MAX, ArithmeticMode::Fixed, None)
+            .validate()
+            .is_ok());
     }
 }
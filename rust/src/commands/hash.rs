@@ -1,74 +1,816 @@
-use eyre::Result;
+use crate::buffers;
+use crate::data_dir;
+use crate::error::{CommandError, FieldError};
+use crate::progress::ProgressHandle;
+use base64::{
+    alphabet,
+    engine::{
+        general_purpose::{GeneralPurpose, GeneralPurposeConfig, STANDARD, URL_SAFE_NO_PAD},
+        DecodePaddingMode,
+    },
+    Engine,
+};
+use blake2::Blake2b512;
+use eyre::{Result, WrapErr};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use md5::Md5;
+use rayon::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use sha3::Sha3_256;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Deref;
+use std::sync::Arc;
 use std::time::Instant;
+use subtle::ConstantTimeEq;
+use validator::{Validate, ValidationError, ValidationErrors};
+use xxhash_rust::xxh3::Xxh3;
 
 #[cfg(feature = "ts-rs")]
 use ts_rs::TS;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Largest `data` accepted by the `hash_data` command, in characters
+pub const MAX_HASH_DATA_LEN: u64 = 64 * 1024 * 1024;
+
+/// Size of the chunks `hash_file` reads at once
+const HASH_FILE_CHUNK_LEN: usize = 1024 * 1024;
+
+/// PBKDF2 iterations when none are given, the OWASP recommendation for PBKDF2-HMAC-SHA256
+pub const DEFAULT_PBKDF2_ITERATIONS: u32 = 600_000;
+/// Largest number of PBKDF2 iterations, which already takes seconds on a phone
+pub const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+/// Largest derived key in bytes, well below the 255 hash blocks HKDF can expand to
+pub const MAX_DERIVED_KEY_LEN: u32 = 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
 pub struct HashDataInput {
+    #[validate(length(max = MAX_HASH_DATA_LEN))]
     pub data: String,
+    /// How `data` is decoded into the hashed bytes, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub encoding: Option<DataEncoding>,
     #[serde(default = "default_algorithm")]
     pub algorithm: HashAlgorithm,
+    /// How the digest is returned, `hex` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output_encoding: Option<OutputEncoding>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
 pub enum HashAlgorithm {
     #[serde(rename = "sha256")]
     Sha256,
+    #[serde(rename = "sha512")]
+    Sha512,
+    #[serde(rename = "sha3_256")]
+    Sha3_256,
+    /// Only for compatibility with legacy checksums, not collision resistant
+    #[serde(rename = "sha1")]
+    Sha1,
+    /// Only for compatibility with legacy checksums, not collision resistant
+    #[serde(rename = "md5")]
+    Md5,
+    /// BLAKE2b with a 512-bit digest
+    #[serde(rename = "blake2b")]
+    Blake2b,
+    #[serde(rename = "blake3")]
+    Blake3,
+    /// Non-cryptographic, big-endian 32-bit checksum
+    #[serde(rename = "crc32")]
+    Crc32,
+    /// Non-cryptographic, big-endian 64-bit XXH3
+    #[serde(rename = "xxh3")]
+    Xxh3,
 }
 
 fn default_algorithm() -> HashAlgorithm {
     HashAlgorithm::Sha256
 }
 
+/// How binary input is carried in a string parameter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub enum DataEncoding {
+    /// The UTF-8 bytes of the string itself
+    #[default]
+    #[serde(rename = "utf8")]
+    Utf8,
+    /// Standard base64, padding optional
+    #[serde(rename = "base64")]
+    Base64,
+    /// Hexadecimal, in either case
+    #[serde(rename = "hex")]
+    Hex,
+    /// Id of a buffer registered through `rust_register_buffer`
+    #[serde(rename = "buffer")]
+    Buffer,
+}
+
+/// Bytes decoded from a string parameter.
+pub enum InputBytes<'a> {
+    Borrowed(&'a [u8]),
+    Owned(Vec<u8>),
+    Shared(Arc<Vec<u8>>),
+}
+
+impl Deref for InputBytes<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Borrowed(bytes) => bytes,
+            Self::Owned(bytes) => bytes,
+            Self::Shared(bytes) => bytes,
+        }
+    }
+}
+
+const BASE64_INDIFFERENT: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Decodes the parameter `field`, reporting failures as an `invalid_params` error on it.
+pub fn decode_input<'a>(
+    field: &str,
+    data: &'a str,
+    encoding: DataEncoding,
+) -> Result<InputBytes<'a>, CommandError> {
+    let decoded = match encoding {
+        DataEncoding::Utf8 => Ok(InputBytes::Borrowed(data.as_bytes())),
+        DataEncoding::Base64 => BASE64_INDIFFERENT
+            .decode(data)
+            .map(InputBytes::Owned)
+            .map_err(|err| format!("invalid base64: {err}")),
+        DataEncoding::Hex => decode_hex(data).map(InputBytes::Owned),
+        DataEncoding::Buffer => data
+            .parse()
+            .ok()
+            .and_then(buffers::get)
+            .map(InputBytes::Shared)
+            .ok_or_else(|| format!("no registered buffer with id `{data}`")),
+    };
+
+    decoded.map_err(|msg| {
+        CommandError::invalid_params(vec![FieldError {
+            field: field.to_string(),
+            code: "encoding".to_string(),
+            msg,
+        }])
+    })
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.len().is_multiple_of(2) {
+        return Err("invalid hex: odd number of digits".to_string());
+    }
+
+    hex.as_bytes()
+        .chunks(2)
+        .enumerate()
+        .map(|(i, pair)| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| format!("invalid hex: bad digit at offset {}", i * 2))
+        })
+        .collect()
+}
+
+/// Text representation of binary output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub enum OutputEncoding {
+    /// Lowercase hexadecimal
+    #[default]
+    #[serde(rename = "hex")]
+    Hex,
+    /// Standard base64 with padding
+    #[serde(rename = "base64")]
+    Base64,
+    /// URL-safe base64 without padding
+    #[serde(rename = "base64url")]
+    Base64url,
+    /// Array of byte values
+    #[serde(rename = "bytes")]
+    Bytes,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
 pub struct HashDataResult {
+    /// Encoded digest, empty with the `bytes` encoding
     pub hash: String,
+    /// Digest bytes, only set with the `bytes` encoding
+    pub bytes: Option<Vec<u8>>,
     pub algorithm: HashAlgorithm,
+    pub output_encoding: OutputEncoding,
+    /// Number of bytes hashed, after decoding `data`
     pub input_length: usize,
     pub computation_time_ms: u64,
 }
 
+/// Incremental state of any [`HashAlgorithm`].
+pub enum Hasher {
+    Sha256(Sha256),
+    Sha512(Sha512),
+    Sha3_256(Sha3_256),
+    Sha1(Sha1),
+    Md5(Md5),
+    Blake2b(Blake2b512),
+    Blake3(Box<blake3::Hasher>),
+    Crc32(crc32fast::Hasher),
+    Xxh3(Box<Xxh3>),
+}
+
+impl Hasher {
+    pub fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+            HashAlgorithm::Sha512 => Self::Sha512(Sha512::new()),
+            HashAlgorithm::Sha3_256 => Self::Sha3_256(Sha3_256::new()),
+            HashAlgorithm::Sha1 => Self::Sha1(Sha1::new()),
+            HashAlgorithm::Md5 => Self::Md5(Md5::new()),
+            HashAlgorithm::Blake2b => Self::Blake2b(Blake2b512::new()),
+            HashAlgorithm::Blake3 => Self::Blake3(Box::new(blake3::Hasher::new())),
+            HashAlgorithm::Crc32 => Self::Crc32(crc32fast::Hasher::new()),
+            HashAlgorithm::Xxh3 => Self::Xxh3(Box::new(Xxh3::new())),
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::Sha512(hasher) => hasher.update(data),
+            Self::Sha3_256(hasher) => hasher.update(data),
+            Self::Sha1(hasher) => hasher.update(data),
+            Self::Md5(hasher) => hasher.update(data),
+            Self::Blake2b(hasher) => hasher.update(data),
+            Self::Blake3(hasher) => {
+                hasher.update(data);
+            }
+            Self::Crc32(hasher) => hasher.update(data),
+            Self::Xxh3(hasher) => hasher.update(data),
+        }
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        match self {
+            Self::Sha256(hasher) => hasher.finalize().to_vec(),
+            Self::Sha512(hasher) => hasher.finalize().to_vec(),
+            Self::Sha3_256(hasher) => hasher.finalize().to_vec(),
+            Self::Sha1(hasher) => hasher.finalize().to_vec(),
+            Self::Md5(hasher) => hasher.finalize().to_vec(),
+            Self::Blake2b(hasher) => hasher.finalize().to_vec(),
+            Self::Blake3(hasher) => hasher.finalize().as_bytes().to_vec(),
+            Self::Crc32(hasher) => hasher.finalize().to_be_bytes().to_vec(),
+            Self::Xxh3(hasher) => hasher.digest().to_be_bytes().to_vec(),
+        }
+    }
+}
+
+/// Hashes `data` in one go.
+pub fn digest(algorithm: HashAlgorithm, data: &[u8]) -> Vec<u8> {
+    let mut hasher = Hasher::new(algorithm);
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// Encodes binary output, returning the text form and, for `bytes`, the raw bytes.
+pub fn encode_output(bytes: Vec<u8>, encoding: OutputEncoding) -> (String, Option<Vec<u8>>) {
+    match encoding {
+        OutputEncoding::Hex => (encode_hex(&bytes), None),
+        OutputEncoding::Base64 => (STANDARD.encode(&bytes), None),
+        OutputEncoding::Base64url => (URL_SAFE_NO_PAD.encode(&bytes), None),
+        OutputEncoding::Bytes => (String::new(), Some(bytes)),
+    }
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    hex
+}
+
 pub async fn hash_data(input: &HashDataInput) -> Result<HashDataResult> {
     let start = Instant::now();
-    
-    let hash = match input.algorithm {
-        HashAlgorithm::Sha256 => {
-            let mut hasher = Sha256::new();
-            hasher.update(input.data.as_bytes());
-            format!("{:x}", hasher.finalize())
+    let output_encoding = input.output_encoding.unwrap_or_default();
+
+    let data = decode_input("data", &input.data, input.encoding.unwrap_or_default())?;
+    let digest = digest(input.algorithm, &data);
+    let (hash, bytes) = encode_output(digest, output_encoding);
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(HashDataResult {
+        hash,
+        bytes,
+        algorithm: input.algorithm,
+        output_encoding,
+        input_length: data.len(),
+        computation_time_ms,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct HashFileInput {
+    /// Path relative to the data directory
+    #[validate(length(min = 1))]
+    pub path: String,
+    /// Algorithms computed together in a single pass over the file
+    #[validate(length(min = 1, max = 16))]
+    pub algorithms: Vec<HashAlgorithm>,
+    /// How the digests are returned, `hex` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output_encoding: Option<OutputEncoding>,
+    /// First byte to hash, the start of the file by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional, type = "number"))]
+    pub offset: Option<u64>,
+    /// Number of bytes to hash, up to the end of the file by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional, type = "number"))]
+    pub length: Option<u64>,
+    /// Id to poll the number of bytes hashed so far with the `progress` command
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub progress_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct HashFileResult {
+    /// One digest per requested algorithm, in the same order
+    pub digests: Vec<FileDigest>,
+    pub output_encoding: OutputEncoding,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub file_size: u64,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub offset: u64,
+    /// Number of bytes hashed
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub input_length: u64,
+    pub computation_time_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct FileDigest {
+    pub algorithm: HashAlgorithm,
+    /// Encoded digest, empty with the `bytes` encoding
+    pub hash: String,
+    /// Digest bytes, only set with the `bytes` encoding
+    pub bytes: Option<Vec<u8>>,
+}
+
+pub async fn hash_file(input: &HashFileInput) -> Result<HashFileResult> {
+    let start = Instant::now();
+    let output_encoding = input.output_encoding.unwrap_or_default();
+    let path = data_dir::resolve("path", &input.path)?;
+
+    let mut file = File::open(&path).wrap_err_with(|| format!("failed to open {}", input.path))?;
+    let file_size = file
+        .metadata()
+        .wrap_err_with(|| format!("failed to read the metadata of {}", input.path))?
+        .len();
+
+    let offset = input.offset.unwrap_or(0);
+    let available = file_size.checked_sub(offset).ok_or_else(|| {
+        range_error(
+            "offset",
+            format!("must be at most the file size ({file_size})"),
+        )
+    })?;
+    let length = match input.length {
+        Some(length) if length > available => {
+            return Err(range_error(
+                "length",
+                format!("the range ends past the end of the file ({file_size} bytes)"),
+            )
+            .into());
         }
+        Some(length) => length,
+        None => available,
     };
-    
+
+    let algorithms = input.algorithms.clone();
+    let progress_id = input.progress_id.clone();
+    let digests = tokio::task::spawn_blocking(move || -> Result<Vec<Vec<u8>>> {
+        let progress = ProgressHandle::start(progress_id.as_deref(), length);
+        file.seek(SeekFrom::Start(offset))?;
+
+        let mut hashers: Vec<Hasher> = algorithms.into_iter().map(Hasher::new).collect();
+        let mut reader = file.take(length);
+        let mut chunk = vec![0; HASH_FILE_CHUNK_LEN];
+        loop {
+            let read = reader.read(&mut chunk)?;
+            if read == 0 {
+                break;
+            }
+            let chunk = &chunk[..read];
+            if hashers.len() > 1 {
+                hashers
+                    .par_iter_mut()
+                    .for_each(|hasher| hasher.update(chunk));
+            } else {
+                hashers.iter_mut().for_each(|hasher| hasher.update(chunk));
+            }
+            progress.advance(read as u64);
+        }
+
+        Ok(hashers.into_iter().map(Hasher::finalize).collect())
+    })
+    .await?
+    .wrap_err_with(|| format!("failed to read {}", input.path))?;
+
+    let digests = input
+        .algorithms
+        .iter()
+        .zip(digests)
+        .map(|(&algorithm, digest)| {
+            let (hash, bytes) = encode_output(digest, output_encoding);
+            FileDigest {
+                algorithm,
+                hash,
+                bytes,
+            }
+        })
+        .collect();
+
     let computation_time_ms = start.elapsed().as_millis() as u64;
-    
-    Ok(HashDataResult {
+
+    Ok(HashFileResult {
+        digests,
+        output_encoding,
+        file_size,
+        offset,
+        input_length: length,
+        computation_time_ms,
+    })
+}
+
+/// Hash functions usable with HMAC and the key derivation functions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub enum MacAlgorithm {
+    #[default]
+    #[serde(rename = "sha256")]
+    Sha256,
+    #[serde(rename = "sha512")]
+    Sha512,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct HmacInput {
+    #[validate(length(max = MAX_HASH_DATA_LEN))]
+    pub data: String,
+    /// How `data` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub encoding: Option<DataEncoding>,
+    pub key: String,
+    /// How `key` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub key_encoding: Option<DataEncoding>,
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub algorithm: Option<MacAlgorithm>,
+    /// How the MAC is returned, `hex` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output_encoding: Option<OutputEncoding>,
+    /// MAC to verify against, compared in constant time
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub expected: Option<String>,
+    /// How `expected` is decoded, `hex` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub expected_encoding: Option<DataEncoding>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct HmacResult {
+    /// Encoded MAC, empty with the `bytes` encoding
+    pub mac: String,
+    /// MAC bytes, only set with the `bytes` encoding
+    pub bytes: Option<Vec<u8>>,
+    pub algorithm: MacAlgorithm,
+    pub output_encoding: OutputEncoding,
+    /// Whether the MAC matches `expected`, only set when it is given
+    pub valid: Option<bool>,
+    pub computation_time_ms: u64,
+}
+
+pub async fn hmac(input: &HmacInput) -> Result<HmacResult> {
+    let start = Instant::now();
+    let algorithm = input.algorithm.unwrap_or_default();
+    let output_encoding = input.output_encoding.unwrap_or_default();
+
+    let data = decode_input("data", &input.data, input.encoding.unwrap_or_default())?;
+    let key = decode_input("key", &input.key, input.key_encoding.unwrap_or_default())?;
+    let expected = input
+        .expected
+        .as_deref()
+        .map(|expected| {
+            let encoding = input.expected_encoding.unwrap_or(DataEncoding::Hex);
+            decode_input("expected", expected, encoding)
+        })
+        .transpose()?;
+
+    let mac = match algorithm {
+        MacAlgorithm::Sha256 => compute_hmac::<Hmac<Sha256>>(&key, &data),
+        MacAlgorithm::Sha512 => compute_hmac::<Hmac<Sha512>>(&key, &data),
+    };
+    // Only the length of the MAC may leak through timing, not its content
+    let valid = expected.map(|expected| bool::from(mac.ct_eq(&expected)));
+    let (mac, bytes) = encode_output(mac, output_encoding);
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(HmacResult {
+        mac,
+        bytes,
+        algorithm,
+        output_encoding,
+        valid,
+        computation_time_ms,
+    })
+}
+
+/// HMAC of `data`, shared with the one-time password commands.
+pub fn compute_hmac<M: Mac + hmac::digest::KeyInit>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub enum KdfAlgorithm {
+    /// HKDF (RFC 5869), to derive keys from secrets that are already uniformly random
+    #[serde(rename = "hkdf")]
+    Hkdf,
+    /// PBKDF2-HMAC (RFC 8018), to derive keys from passwords
+    #[serde(rename = "pbkdf2")]
+    Pbkdf2,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct DeriveKeyInput {
+    pub algorithm: KdfAlgorithm,
+    /// Hash function of the HMAC, `sha256` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub hash: Option<MacAlgorithm>,
+    /// Input keying material of HKDF, or the password of PBKDF2
+    pub secret: String,
+    /// How `secret` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub secret_encoding: Option<DataEncoding>,
+    /// Optional with HKDF, required with PBKDF2
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub salt: Option<String>,
+    /// How `salt` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub salt_encoding: Option<DataEncoding>,
+    /// Context binding the key to its purpose, HKDF only
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub info: Option<String>,
+    /// How `info` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub info_encoding: Option<DataEncoding>,
+    /// PBKDF2 only, [`DEFAULT_PBKDF2_ITERATIONS`] by default
+    #[serde(default)]
+    #[schemars(range(min = 1, max = MAX_PBKDF2_ITERATIONS))]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub iterations: Option<u32>,
+    /// Length of the derived key in bytes, 32 by default
+    #[serde(default = "default_key_length")]
+    #[schemars(range(min = 1, max = MAX_DERIVED_KEY_LEN))]
+    pub length: u32,
+    /// How the key is returned, `hex` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output_encoding: Option<OutputEncoding>,
+}
+
+fn default_key_length() -> u32 {
+    32
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct DeriveKeyResult {
+    /// Encoded key, empty with the `bytes` encoding
+    pub key: String,
+    /// Key bytes, only set with the `bytes` encoding
+    pub bytes: Option<Vec<u8>>,
+    pub algorithm: KdfAlgorithm,
+    pub hash: MacAlgorithm,
+    /// Iterations used by PBKDF2
+    pub iterations: Option<u32>,
+    pub output_encoding: OutputEncoding,
+    pub computation_time_ms: u64,
+}
+
+pub async fn derive_key(input: &DeriveKeyInput) -> Result<DeriveKeyResult> {
+    let start = Instant::now();
+    let hash = input.hash.unwrap_or_default();
+    let output_encoding = input.output_encoding.unwrap_or_default();
+
+    let secret = decode_input(
+        "secret",
+        &input.secret,
+        input.secret_encoding.unwrap_or_default(),
+    )?;
+    let salt = decode_optional("salt", input.salt.as_deref(), input.salt_encoding)?;
+    let info = decode_optional("info", input.info.as_deref(), input.info_encoding)?;
+
+    let mut key = vec![0; input.length as usize];
+    let iterations = match input.algorithm {
+        KdfAlgorithm::Hkdf => {
+            let salt = salt.as_deref();
+            let info = info.as_deref().unwrap_or_default();
+            match hash {
+                MacAlgorithm::Sha256 => Hkdf::<Sha256>::new(salt, &secret).expand(info, &mut key),
+                MacAlgorithm::Sha512 => Hkdf::<Sha512>::new(salt, &secret).expand(info, &mut key),
+            }
+            .map_err(|_| eyre::eyre!("HKDF can't derive {} bytes", input.length))?;
+            None
+        }
+        KdfAlgorithm::Pbkdf2 => {
+            let iterations = input.iterations.unwrap_or(DEFAULT_PBKDF2_ITERATIONS);
+            let (secret, salt) = (secret.to_vec(), salt.map(|salt| salt.to_vec()));
+            // Hundreds of thousands of iterations take a while, keep them off the async runtime
+            key = tokio::task::spawn_blocking(move || {
+                match hash {
+                    MacAlgorithm::Sha256 => pbkdf2::pbkdf2_hmac::<Sha256>(
+                        &secret,
+                        salt.as_deref().unwrap_or_default(),
+                        iterations,
+                        &mut key,
+                    ),
+                    MacAlgorithm::Sha512 => pbkdf2::pbkdf2_hmac::<Sha512>(
+                        &secret,
+                        salt.as_deref().unwrap_or_default(),
+                        iterations,
+                        &mut key,
+                    ),
+                }
+                key
+            })
+            .await?;
+            Some(iterations)
+        }
+    };
+    let (key, bytes) = encode_output(key, output_encoding);
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(DeriveKeyResult {
+        key,
+        bytes,
+        algorithm: input.algorithm,
         hash,
-        algorithm: input.algorithm.clone(),
-        input_length: input.data.len(),
+        iterations,
+        output_encoding,
         computation_time_ms,
     })
 }
 
+fn decode_optional<'a>(
+    field: &str,
+    data: Option<&'a str>,
+    encoding: Option<DataEncoding>,
+) -> Result<Option<InputBytes<'a>>, CommandError> {
+    data.map(|data| decode_input(field, data, encoding.unwrap_or_default()))
+        .transpose()
+}
+
+// Implemented by hand since the parameters and limits depend on the algorithm
+impl Validate for DeriveKeyInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let unsupported = |errors: &mut ValidationErrors, field: &'static str| {
+            errors.add(
+                field,
+                ValidationError::new("unsupported")
+                    .with_message(format!("not used by {:?}", self.algorithm).into()),
+            );
+        };
+
+        match self.algorithm {
+            KdfAlgorithm::Hkdf => {
+                if self.iterations.is_some() {
+                    unsupported(&mut errors, "iterations");
+                }
+            }
+            KdfAlgorithm::Pbkdf2 => {
+                if self.info.is_some() {
+                    unsupported(&mut errors, "info");
+                }
+                if self.salt.as_deref().is_none_or(str::is_empty) {
+                    errors.add(
+                        "salt",
+                        ValidationError::new("required")
+                            .with_message("PBKDF2 requires a salt".into()),
+                    );
+                }
+                if self
+                    .iterations
+                    .is_some_and(|iterations| iterations == 0 || iterations > MAX_PBKDF2_ITERATIONS)
+                {
+                    let mut err = ValidationError::new("range");
+                    err.add_param("min".into(), &1);
+                    err.add_param("max".into(), &MAX_PBKDF2_ITERATIONS);
+                    errors.add("iterations", err);
+                }
+            }
+        }
+
+        if self.length == 0 || self.length > MAX_DERIVED_KEY_LEN {
+            let mut err = ValidationError::new("range");
+            err.add_param("min".into(), &1);
+            err.add_param("max".into(), &MAX_DERIVED_KEY_LEN);
+            errors.add("length", err);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn range_error(field: &str, msg: String) -> CommandError {
+    CommandError::invalid_params(vec![FieldError {
+        field: field.to_string(),
+        code: "range".to_string(),
+        msg,
+    }])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn input(algorithm: HashAlgorithm, output_encoding: OutputEncoding) -> HashDataInput {
+        HashDataInput {
+            data: "hello world".to_string(),
+            encoding: None,
+            algorithm,
+            output_encoding: Some(output_encoding),
+        }
+    }
+
     #[tokio::test]
     async fn test_hash_data() {
         let input = HashDataInput {
             data: "hello world".to_string(),
+            encoding: None,
             algorithm: HashAlgorithm::Sha256,
+            output_encoding: None,
         };
-        
+
         let result = hash_data(&input).await.unwrap();
         assert_eq!(
             result.hash,
@@ -76,4 +818,238 @@ mod tests {
         );
         assert_eq!(result.input_length, 11);
     }
+
+    #[tokio::test]
+    async fn test_hash_algorithms() {
+        let cases = [
+            (HashAlgorithm::Md5, "5eb63bbbe01eeed093cb22bb8f5acdc3"),
+            (
+                HashAlgorithm::Sha1,
+                "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed",
+            ),
+            (
+                HashAlgorithm::Sha3_256,
+                "644bcc7e564373040999aac89e7622f3ca71fba1d972fd94a31c3bfbf24e3938",
+            ),
+            (
+                HashAlgorithm::Blake3,
+                "d74981efa70a0c880b8d8c1985d075dbcbf679b99a5f9914e5aaf96b831a9e24",
+            ),
+            (HashAlgorithm::Crc32, "0d4a1185"),
+        ];
+        for (algorithm, expected) in cases {
+            let result = hash_data(&input(algorithm, OutputEncoding::Hex))
+                .await
+                .unwrap();
+            assert_eq!(result.hash, expected, "{algorithm:?}");
+        }
+
+        let lengths = [
+            (HashAlgorithm::Sha512, 64),
+            (HashAlgorithm::Blake2b, 64),
+            (HashAlgorithm::Xxh3, 8),
+        ];
+        for (algorithm, length) in lengths {
+            let result = hash_data(&input(algorithm, OutputEncoding::Bytes))
+                .await
+                .unwrap();
+            assert_eq!(result.bytes.map(|b| b.len()), Some(length), "{algorithm:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_output_encodings() {
+        let result = hash_data(&input(HashAlgorithm::Md5, OutputEncoding::Base64))
+            .await
+            .unwrap();
+        assert_eq!(result.hash, "XrY7u+Ae7tCTyyK7j1rNww==");
+
+        let result = hash_data(&input(HashAlgorithm::Md5, OutputEncoding::Base64url))
+            .await
+            .unwrap();
+        assert_eq!(result.hash, "XrY7u-Ae7tCTyyK7j1rNww");
+        assert!(result.bytes.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_input_encodings() {
+        let expected = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+        let buffer = buffers::insert(b"hello world".to_vec());
+        let encoded = [
+            (DataEncoding::Base64, "aGVsbG8gd29ybGQ".to_string()),
+            (DataEncoding::Hex, "68656C6C6F20776F726C64".to_string()),
+            (DataEncoding::Buffer, buffer.to_string()),
+        ];
+        for (encoding, data) in encoded {
+            let mut input = input(HashAlgorithm::Sha256, OutputEncoding::Hex);
+            input.data = data;
+            input.encoding = Some(encoding);
+
+            let result = hash_data(&input).await.unwrap();
+            assert_eq!(result.hash, expected, "{encoding:?}");
+            assert_eq!(result.input_length, 11);
+        }
+        buffers::remove(buffer);
+    }
+
+    #[tokio::test]
+    async fn test_input_decode_errors() {
+        for (encoding, data) in [
+            (DataEncoding::Base64, "not base64!"),
+            (DataEncoding::Hex, "abc"),
+            (DataEncoding::Hex, "zz"),
+            (DataEncoding::Buffer, "0"),
+        ] {
+            let mut input = input(HashAlgorithm::Sha256, OutputEncoding::Hex);
+            input.data = data.to_string();
+            input.encoding = Some(encoding);
+
+            let err = hash_data(&input).await.unwrap_err();
+            let field_errors = crate::error::field_errors(&err).unwrap();
+            assert_eq!(field_errors[0].field, "data");
+            assert_eq!(field_errors[0].code, "encoding");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hash_file() {
+        let dir = std::env::temp_dir();
+        crate::data_dir::set_data_dir(&crate::data_dir::SetDataDirInput {
+            path: dir.display().to_string(),
+        })
+        .unwrap();
+        let name = format!("test_hash_file_{}.txt", std::process::id());
+        std::fs::write(dir.join(&name), "xxhello worldxx").unwrap();
+
+        let mut input = HashFileInput {
+            path: name.clone(),
+            algorithms: vec![HashAlgorithm::Sha256, HashAlgorithm::Md5],
+            output_encoding: None,
+            offset: Some(2),
+            length: Some(11),
+            progress_id: Some(name.clone()),
+        };
+        let result = hash_file(&input).await.unwrap();
+        assert_eq!(
+            result.digests[0].hash,
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+        assert_eq!(result.digests[1].hash, "5eb63bbbe01eeed093cb22bb8f5acdc3");
+        assert_eq!((result.file_size, result.input_length), (15, 11));
+
+        let progress =
+            crate::progress::progress(&crate::progress::ProgressInput { id: name.clone() })
+                .unwrap();
+        assert!(progress.done);
+        assert_eq!(progress.processed, 11);
+
+        input.length = Some(14);
+        let err = hash_file(&input).await.unwrap_err();
+        assert_eq!(crate::error::field_errors(&err).unwrap()[0].field, "length");
+
+        std::fs::remove_file(dir.join(&name)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_hmac() {
+        // RFC 4231, test case 2
+        let mut input = HmacInput {
+            data: "what do ya want for nothing?".to_string(),
+            encoding: None,
+            key: "Jefe".to_string(),
+            key_encoding: None,
+            algorithm: None,
+            output_encoding: None,
+            expected: None,
+            expected_encoding: None,
+        };
+        let result = hmac(&input).await.unwrap();
+        let sha256 = "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+        assert_eq!(result.mac, sha256);
+        assert_eq!(result.valid, None);
+
+        input.algorithm = Some(MacAlgorithm::Sha512);
+        let result = hmac(&input).await.unwrap();
+        assert!(result.mac.starts_with("164b7a7bfcf819e2e395fbe73b56e0a3"));
+
+        input.algorithm = None;
+        input.expected = Some(sha256.to_uppercase());
+        assert_eq!(hmac(&input).await.unwrap().valid, Some(true));
+        input.expected = Some(sha256[..62].to_string());
+        assert_eq!(hmac(&input).await.unwrap().valid, Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_derive_key() {
+        // RFC 5869, test case 1
+        let mut input = DeriveKeyInput {
+            algorithm: KdfAlgorithm::Hkdf,
+            hash: None,
+            secret: "0b".repeat(22),
+            secret_encoding: Some(DataEncoding::Hex),
+            salt: Some("000102030405060708090a0b0c".to_string()),
+            salt_encoding: Some(DataEncoding::Hex),
+            info: Some("f0f1f2f3f4f5f6f7f8f9".to_string()),
+            info_encoding: Some(DataEncoding::Hex),
+            iterations: None,
+            length: 42,
+            output_encoding: None,
+        };
+        assert!(input.validate().is_ok());
+        let result = derive_key(&input).await.unwrap();
+        assert_eq!(
+            result.key,
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+        assert_eq!(result.iterations, None);
+
+        input.algorithm = KdfAlgorithm::Pbkdf2;
+        input.secret = "password".to_string();
+        input.secret_encoding = None;
+        input.salt = Some("salt".to_string());
+        input.salt_encoding = None;
+        input.info = None;
+        input.iterations = Some(2);
+        input.length = 32;
+        assert!(input.validate().is_ok());
+        let result = derive_key(&input).await.unwrap();
+        assert_eq!(
+            result.key,
+            "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"
+        );
+        assert_eq!(result.iterations, Some(2));
+    }
+
+    #[test]
+    fn test_derive_key_params() {
+        let input = DeriveKeyInput {
+            algorithm: KdfAlgorithm::Pbkdf2,
+            hash: Some(MacAlgorithm::Sha512),
+            secret: "password".to_string(),
+            secret_encoding: None,
+            salt: None,
+            salt_encoding: None,
+            info: Some("context".to_string()),
+            info_encoding: None,
+            iterations: Some(0),
+            length: MAX_DERIVED_KEY_LEN + 1,
+            output_encoding: None,
+        };
+        let errors = input.validate().unwrap_err();
+        let mut fields: Vec<_> = errors
+            .errors()
+            .keys()
+            .map(|field| field.to_string())
+            .collect();
+        fields.sort();
+        assert_eq!(fields, ["info", "iterations", "length", "salt"]);
+    }
+
+    #[cfg(feature = "ts-rs")]
+    #[test]
+    fn test_enum_literals_match_typescript() {
+        crate::cmd::verify_enum_literals::<OutputEncoding>().unwrap();
+        crate::cmd::verify_enum_literals::<DataEncoding>().unwrap();
+        crate::cmd::verify_enum_literals::<KdfAlgorithm>().unwrap();
+    }
 }
@@ -1,25 +1,46 @@
 #[cfg(feature = "image-processing")]
 use eyre::Result;
 #[cfg(feature = "image-processing")]
+use schemars::JsonSchema;
+#[cfg(feature = "image-processing")]
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "image-processing")]
 use std::time::Instant;
+#[cfg(feature = "image-processing")]
+use validator::Validate;
 
 #[cfg(all(feature = "image-processing", feature = "ts-rs"))]
 use ts_rs::TS;
 
+/// Largest encoded image accepted by the `process_image` command, in bytes
 #[cfg(feature = "image-processing")]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub const MAX_IMAGE_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Largest width and height of a decoded image, in pixels
+#[cfg(feature = "image-processing")]
+pub const MAX_IMAGE_DIMENSION: u32 = 8192;
+
+/// Largest memory the decoder may allocate, since small encoded images can decode to huge ones
+#[cfg(feature = "image-processing")]
+pub const MAX_IMAGE_ALLOC: u64 = 256 * 1024 * 1024;
+
+/// Base64 length of an image of [`MAX_IMAGE_BYTES`]
+#[cfg(feature = "image-processing")]
+const MAX_IMAGE_DATA_LEN: u64 = MAX_IMAGE_BYTES.div_ceil(3) * 4;
+
+#[cfg(feature = "image-processing")]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
 pub struct ImageProcessingInput {
+    #[validate(length(max = MAX_IMAGE_DATA_LEN))]
     pub data: String, // Base64 encoded image data
     pub filter: ImageFilter,
     pub intensity: Option<f32>,
 }
 
 #[cfg(feature = "image-processing")]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
 pub enum ImageFilter {
@@ -47,13 +68,29 @@ pub struct ImageProcessingResult {
 #[cfg(feature = "image-processing")]
 pub async fn process_image(input: &ImageProcessingInput) -> Result<ImageProcessingResult> {
     use base64::{engine::general_purpose::STANDARD, Engine};
-    use image::{ImageFormat, DynamicImage};
+    use crate::error::{CommandError, FieldError};
+    use image::error::ImageError;
+    use image::io::{Limits, Reader};
+    use image::ImageFormat;
     
     let start = Instant::now();
     
     // Decode base64 image data
     let image_data = STANDARD.decode(&input.data)?;
-    let img = image::load_from_memory(&image_data)?;
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    limits.max_alloc = Some(MAX_IMAGE_ALLOC);
+    let mut reader = Reader::new(std::io::Cursor::new(&image_data)).with_guessed_format()?;
+    reader.limits(limits);
+    let img = reader.decode().map_err(|err| match err {
+        ImageError::Limits(err) => CommandError::invalid_params(vec![FieldError {
+            field: "data".to_string(),
+            code: "range".to_string(),
+            msg: format!("{err}, images are limited to {MAX_IMAGE_DIMENSION}x{MAX_IMAGE_DIMENSION} pixels"),
+        }]).into(),
+        err => eyre::Report::from(err),
+    })?;
     let original_size = (img.width(), img.height());
     
     // Apply the specified filter
@@ -88,6 +125,36 @@ pub async fn process_image(input: &ImageProcessingInput) -> Result<ImageProcessi
     })
 }
 
+#[cfg(all(test, feature = "image-processing"))]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use image::{DynamicImage, ImageFormat};
+
+    fn png(width: u32, height: u32) -> String {
+        let mut png = Vec::new();
+        DynamicImage::new_luma8(width, height)
+            .write_to(&mut std::io::Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        STANDARD.encode(png)
+    }
+
+    #[tokio::test]
+    async fn test_process_image_limits() {
+        let mut input = ImageProcessingInput {
+            data: png(4, 2),
+            filter: ImageFilter::Grayscale,
+            intensity: None,
+        };
+        assert_eq!(process_image(&input).await.unwrap().original_size, (4, 2));
+
+        // A few KiB of PNG would decode to far more pixels than allowed
+        input.data = png(MAX_IMAGE_DIMENSION + 1, 1);
+        let err = process_image(&input).await.unwrap_err();
+        assert_eq!(crate::error::field_errors(&err).unwrap()[0].field, "data");
+    }
+}
+
 // Stub implementation when image-processing feature is not enabled
 #[cfg(not(feature = "image-processing"))]
 use serde::{Deserialize, Serialize};
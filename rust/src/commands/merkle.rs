@@ -0,0 +1,421 @@
+use crate::commands::hash::{
+    decode_input, encode_output, DataEncoding, HashAlgorithm, Hasher, OutputEncoding,
+    MAX_HASH_DATA_LEN,
+};
+use crate::error::CommandError;
+use eyre::Result;
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use subtle::ConstantTimeEq;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+/// Largest number of leaves in a tree
+pub const MAX_MERKLE_ITEMS: u64 = 1_000_000;
+/// Largest number of leaves proofs are returned for, every proof holds about log2(n) hashes
+pub const MAX_MERKLE_PROOF_ITEMS: u64 = 65_536;
+/// Longest proof `merkle_verify` accepts, enough for any tree `merkle` builds
+pub const MAX_MERKLE_PROOF_LEN: u64 = 64;
+
+/// Domain separation prefixes of RFC 6962, so that a leaf can't be passed off as a node
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct MerkleInput {
+    /// Leaves of the tree, in order
+    pub items: Vec<String>,
+    /// How every item is decoded, `utf8` by default; with `buffer` items are buffer ids
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub encoding: Option<DataEncoding>,
+    /// `sha256` by default, the non-cryptographic checksums are rejected
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub algorithm: Option<HashAlgorithm>,
+    /// Whether to return the inclusion proof of every leaf, `false` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub proofs: Option<bool>,
+    /// How the root and proof hashes are returned, `hex` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output_encoding: Option<OutputEncoding>,
+}
+
+impl Validate for MerkleInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let max_items = if self.proofs == Some(true) {
+            MAX_MERKLE_PROOF_ITEMS
+        } else {
+            MAX_MERKLE_ITEMS
+        };
+        let total_len: u64 = self.items.iter().map(|item| item.len() as u64).sum();
+        if self.items.is_empty() || self.items.len() as u64 > max_items {
+            let mut err = ValidationError::new("length");
+            err.add_param("min".into(), &1);
+            err.add_param("max".into(), &max_items);
+            errors.add("items", err);
+        } else if total_len > MAX_HASH_DATA_LEN {
+            errors.add(
+                "items",
+                ValidationError::new("length").with_message(
+                    format!("the items total more than {MAX_HASH_DATA_LEN} characters").into(),
+                ),
+            );
+        }
+        check_algorithm(self.algorithm, &mut errors);
+        if self.output_encoding == Some(OutputEncoding::Bytes) {
+            errors.add(
+                "output_encoding",
+                ValidationError::new("unsupported")
+                    .with_message("hashes are returned as strings".into()),
+            );
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Side of the sibling a proof step combines with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum MerkleSide {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct MerkleProofStep {
+    /// Encoded hash of the sibling
+    pub hash: String,
+    pub side: MerkleSide,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct MerkleProof {
+    /// Index of the leaf in `items`
+    pub index: usize,
+    /// Siblings from the leaf up to the root
+    pub path: Vec<MerkleProofStep>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct MerkleResult {
+    pub root: String,
+    pub leaf_count: usize,
+    /// Number of levels above the leaves
+    pub depth: usize,
+    pub algorithm: HashAlgorithm,
+    pub output_encoding: OutputEncoding,
+    /// Proof of every leaf, only set when `proofs` is
+    pub proofs: Option<Vec<MerkleProof>>,
+    pub computation_time_ms: u64,
+}
+
+/// Builds the tree of RFC 6962: leaves are `H(0x00 || item)`, nodes `H(0x01 || left || right)`,
+/// and the last node of an odd level is promoted unchanged rather than paired with itself.
+pub async fn merkle(input: &MerkleInput) -> Result<MerkleResult> {
+    let start = Instant::now();
+    let algorithm = input.algorithm.unwrap_or(HashAlgorithm::Sha256);
+    let encoding = input.encoding.unwrap_or_default();
+    let with_proofs = input.proofs.unwrap_or(false);
+    let output_encoding = input.output_encoding.unwrap_or_default();
+
+    let leaves = input
+        .items
+        .par_iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let data = decode_input(&format!("items[{i}]"), item, encoding)?;
+            Ok(leaf_hash(algorithm, &data))
+        })
+        .collect::<Result<Vec<_>, CommandError>>()?;
+
+    // Every level is kept for the proofs, only the last one is needed for the root
+    let mut levels = vec![leaves];
+    while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+        let parents = level
+            .par_chunks(2)
+            .map(|pair| match pair {
+                [left, right] => node_hash(algorithm, left, right),
+                [single] => single.clone(),
+                _ => unreachable!("chunks hold one or two nodes"),
+            })
+            .collect();
+        if !with_proofs {
+            levels.clear();
+        }
+        levels.push(parents);
+    }
+
+    let encode = |hash: &[u8]| encode_output(hash.to_vec(), output_encoding).0;
+    let proofs = with_proofs.then(|| {
+        (0..input.items.len())
+            .into_par_iter()
+            .map(|index| MerkleProof {
+                index,
+                path: proof_path(&levels, index)
+                    .map(|(hash, side)| MerkleProofStep {
+                        hash: encode(hash),
+                        side,
+                    })
+                    .collect(),
+            })
+            .collect()
+    });
+    let root = encode(&levels.last().expect("the tree has a root")[0]);
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(MerkleResult {
+        root,
+        leaf_count: input.items.len(),
+        depth: merkle_depth(input.items.len()),
+        algorithm,
+        output_encoding,
+        proofs,
+        computation_time_ms,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct MerkleVerifyInput {
+    /// The leaf whose inclusion is checked
+    pub item: String,
+    /// How `item` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub encoding: Option<DataEncoding>,
+    /// Root returned by `merkle`
+    pub root: String,
+    /// Path of the leaf returned by `merkle`
+    pub proof: Vec<MerkleProofStep>,
+    /// Algorithm the tree was built with, `sha256` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub algorithm: Option<HashAlgorithm>,
+    /// How `root` and the proof hashes are decoded, `hex` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub hash_encoding: Option<DataEncoding>,
+}
+
+impl Validate for MerkleVerifyInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.item.len() as u64 > MAX_HASH_DATA_LEN {
+            let mut err = ValidationError::new("length");
+            err.add_param("max".into(), &MAX_HASH_DATA_LEN);
+            errors.add("item", err);
+        }
+        if self.proof.len() as u64 > MAX_MERKLE_PROOF_LEN {
+            let mut err = ValidationError::new("length");
+            err.add_param("max".into(), &MAX_MERKLE_PROOF_LEN);
+            errors.add("proof", err);
+        }
+        check_algorithm(self.algorithm, &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct MerkleVerifyResult {
+    pub valid: bool,
+    pub algorithm: HashAlgorithm,
+    pub computation_time_ms: u64,
+}
+
+pub async fn merkle_verify(input: &MerkleVerifyInput) -> Result<MerkleVerifyResult> {
+    let start = Instant::now();
+    let algorithm = input.algorithm.unwrap_or(HashAlgorithm::Sha256);
+    let hash_encoding = input.hash_encoding.unwrap_or(DataEncoding::Hex);
+
+    let item = decode_input("item", &input.item, input.encoding.unwrap_or_default())?;
+    let root = decode_input("root", &input.root, hash_encoding)?;
+
+    let mut hash = leaf_hash(algorithm, &item);
+    for (i, step) in input.proof.iter().enumerate() {
+        let sibling = decode_input(&format!("proof[{i}].hash"), &step.hash, hash_encoding)?;
+        hash = match step.side {
+            MerkleSide::Left => node_hash(algorithm, &sibling, &hash),
+            MerkleSide::Right => node_hash(algorithm, &hash, &sibling),
+        };
+    }
+    let valid = bool::from(hash.ct_eq(&root));
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(MerkleVerifyResult {
+        valid,
+        algorithm,
+        computation_time_ms,
+    })
+}
+
+fn leaf_hash(algorithm: HashAlgorithm, data: &[u8]) -> Vec<u8> {
+    let mut hasher = Hasher::new(algorithm);
+    hasher.update(&[LEAF_PREFIX]);
+    hasher.update(data);
+    hasher.finalize()
+}
+
+fn node_hash(algorithm: HashAlgorithm, left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut hasher = Hasher::new(algorithm);
+    hasher.update(&[NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize()
+}
+
+/// Siblings of a leaf from the bottom level up, levels where its node is promoted have none.
+fn proof_path(
+    levels: &[Vec<Vec<u8>>],
+    mut index: usize,
+) -> impl Iterator<Item = (&[u8], MerkleSide)> {
+    levels[..levels.len() - 1].iter().filter_map(move |level| {
+        let sibling = index ^ 1;
+        let side = if sibling < index {
+            MerkleSide::Left
+        } else {
+            MerkleSide::Right
+        };
+        index /= 2;
+        level.get(sibling).map(|hash| (hash.as_slice(), side))
+    })
+}
+
+fn merkle_depth(leaf_count: usize) -> usize {
+    leaf_count.next_power_of_two().trailing_zeros() as usize
+}
+
+fn check_algorithm(algorithm: Option<HashAlgorithm>, errors: &mut ValidationErrors) {
+    if matches!(algorithm, Some(HashAlgorithm::Crc32 | HashAlgorithm::Xxh3)) {
+        errors.add(
+            "algorithm",
+            ValidationError::new("unsupported")
+                .with_message("checksums can't authenticate a Merkle tree".into()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(items: &[&str], proofs: bool) -> MerkleInput {
+        MerkleInput {
+            items: items.iter().map(|item| item.to_string()).collect(),
+            encoding: None,
+            algorithm: None,
+            proofs: Some(proofs),
+            output_encoding: None,
+        }
+    }
+
+    fn verify_input(item: &str, root: &str, proof: Vec<MerkleProofStep>) -> MerkleVerifyInput {
+        MerkleVerifyInput {
+            item: item.to_string(),
+            encoding: None,
+            root: root.to_string(),
+            proof,
+            algorithm: None,
+            hash_encoding: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_merkle_root() {
+        // A single leaf is its own root
+        let result = merkle(&input(&["a"], false)).await.unwrap();
+        assert_eq!(
+            result.root,
+            encode_output(leaf_hash(HashAlgorithm::Sha256, b"a"), OutputEncoding::Hex).0
+        );
+        assert_eq!(result.depth, 0);
+
+        // The third leaf of three is promoted and paired with the node of the first two
+        let result = merkle(&input(&["a", "b", "c"], false)).await.unwrap();
+        let sha256 = HashAlgorithm::Sha256;
+        let ab = node_hash(sha256, &leaf_hash(sha256, b"a"), &leaf_hash(sha256, b"b"));
+        let root = node_hash(sha256, &ab, &leaf_hash(sha256, b"c"));
+        assert_eq!(result.root, encode_output(root, OutputEncoding::Hex).0);
+        assert_eq!((result.leaf_count, result.depth), (3, 2));
+        assert!(result.proofs.is_none());
+
+        // Swapping two leaves changes the root
+        let swapped = merkle(&input(&["b", "a", "c"], false)).await.unwrap();
+        assert_ne!(swapped.root, result.root);
+    }
+
+    #[tokio::test]
+    async fn test_merkle_proofs_verify() {
+        let items: Vec<String> = (0..11).map(|i| format!("piece {i}")).collect();
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+        let result = merkle(&input(&items, true)).await.unwrap();
+        let proofs = result.proofs.unwrap();
+        assert_eq!(proofs.len(), 11);
+        // The last leaf has no sibling on two of the four levels
+        assert_eq!(proofs[10].path.len(), 2);
+
+        for proof in &proofs {
+            let item = items[proof.index];
+            let input = verify_input(item, &result.root, proof.path.clone());
+            assert!(merkle_verify(&input).await.unwrap().valid, "leaf {item}");
+
+            let input = verify_input("tampered", &result.root, proof.path.clone());
+            assert!(!merkle_verify(&input).await.unwrap().valid);
+        }
+
+        // A proof only holds at its own position
+        let input = verify_input(items[0], &result.root, proofs[1].path.clone());
+        assert!(!merkle_verify(&input).await.unwrap().valid);
+    }
+
+    #[tokio::test]
+    async fn test_merkle_validation() {
+        let mut invalid = input(&[], false);
+        invalid.algorithm = Some(HashAlgorithm::Crc32);
+        invalid.output_encoding = Some(OutputEncoding::Bytes);
+        let errors = invalid.validate().unwrap_err();
+        let mut fields: Vec<_> = errors.field_errors().into_keys().collect();
+        fields.sort();
+        assert_eq!(fields, ["algorithm", "items", "output_encoding"]);
+
+        let proof = vec![MerkleProofStep {
+            hash: "not hex".to_string(),
+            side: MerkleSide::Left,
+        }];
+        let err = merkle_verify(&verify_input("a", "00", proof))
+            .await
+            .unwrap_err();
+        let errors = crate::error::field_errors(&err).unwrap();
+        assert_eq!(errors[0].field, "proof[0].hash");
+    }
+}
@@ -1,7 +1,18 @@
+pub mod benchmark;
+pub mod crypto;
 pub mod fibonacci;
 pub mod hash;
+pub mod merkle;
+pub mod otp;
+pub mod password;
 pub mod sort;
+pub mod sort_file;
 pub mod prime;
+pub mod random;
+pub mod schema;
+pub mod sequence;
+pub mod signing;
+pub mod stats;
 
 #[cfg(feature = "image-processing")]
 pub mod image_processing;
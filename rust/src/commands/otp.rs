@@ -0,0 +1,721 @@
+use crate::commands::hash::compute_hmac;
+use crate::error::{CommandError, FieldError};
+use eyre::Result;
+use hmac::Hmac;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use subtle::ConstantTimeEq;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+/// Code length when none is given, what every authenticator app expects
+pub const DEFAULT_OTP_DIGITS: u32 = 6;
+/// Shortest code, the minimum of RFC 4226
+pub const MIN_OTP_DIGITS: u32 = 6;
+/// Longest code, as many digits as the 31-bit truncated HMAC has
+pub const MAX_OTP_DIGITS: u32 = 10;
+/// TOTP time step in seconds when none is given, the RFC 6238 recommendation
+pub const DEFAULT_TOTP_PERIOD: u32 = 30;
+/// Longest TOTP time step in seconds
+pub const MAX_TOTP_PERIOD: u32 = 3600;
+/// Time steps accepted on either side of the current one when no window is given
+pub const DEFAULT_TOTP_WINDOW: u32 = 1;
+/// Largest verification window, beyond it codes stay valid for too long
+pub const MAX_TOTP_WINDOW: u32 = 10;
+/// Largest secret or `otpauth://` URI, in characters
+pub const MAX_OTP_SECRET_LEN: u64 = 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub enum OtpAlgorithm {
+    /// The only algorithm most authenticator apps support
+    #[default]
+    #[serde(rename = "sha1")]
+    Sha1,
+    #[serde(rename = "sha256")]
+    Sha256,
+    #[serde(rename = "sha512")]
+    Sha512,
+}
+
+/// Shared secret and code format, given directly or as an `otpauth://` URI.
+///
+/// Parameters given next to `otpauth_uri` take precedence over the ones in it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct OtpParams {
+    /// Base32 secret as shown by the issuer, case and spaces don't matter
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub secret: Option<String>,
+    /// `otpauth://` URI, usually scanned from a QR code, in place of `secret`
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub otpauth_uri: Option<String>,
+    /// `sha1` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub algorithm: Option<OtpAlgorithm>,
+    /// [`DEFAULT_OTP_DIGITS`] by default
+    #[serde(default)]
+    #[schemars(range(min = MIN_OTP_DIGITS, max = MAX_OTP_DIGITS))]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub digits: Option<u32>,
+}
+
+impl OtpParams {
+    fn check(&self, errors: &mut ValidationErrors) {
+        match (&self.secret, &self.otpauth_uri) {
+            (None, None) => errors.add(
+                "secret",
+                ValidationError::new("required")
+                    .with_message("either secret or otpauth_uri is required".into()),
+            ),
+            (Some(_), Some(_)) => errors.add(
+                "otpauth_uri",
+                ValidationError::new("unsupported")
+                    .with_message("can't be combined with secret".into()),
+            ),
+            _ => {}
+        }
+        for (field, value) in [("secret", &self.secret), ("otpauth_uri", &self.otpauth_uri)] {
+            if value
+                .as_ref()
+                .is_some_and(|value| value.len() as u64 > MAX_OTP_SECRET_LEN)
+            {
+                let mut err = ValidationError::new("length");
+                err.add_param("max".into(), &MAX_OTP_SECRET_LEN);
+                errors.add(field, err);
+            }
+        }
+        if self.digits.is_some_and(|digits| !digits_in_range(digits)) {
+            range(errors, "digits", MIN_OTP_DIGITS, MAX_OTP_DIGITS);
+        }
+    }
+
+    /// Decodes the secret and fills in the parameters missing from the input.
+    fn resolve(&self, kind: OtpKind) -> Result<Otp, CommandError> {
+        let uri = self
+            .otpauth_uri
+            .as_deref()
+            .map(|uri| OtpAuthUri::parse(uri, kind))
+            .transpose()?
+            .unwrap_or_default();
+
+        let secret = match (&self.secret, &uri.secret) {
+            (Some(secret), _) => decode_base32(secret).map_err(|msg| secret_error("secret", msg)),
+            (None, Some(secret)) => {
+                decode_base32(secret).map_err(|msg| secret_error("otpauth_uri", msg))
+            }
+            (None, None) => Err(uri_error("format", "the URI has no secret")),
+        }?;
+        let digits = self.digits.or(uri.digits).unwrap_or(DEFAULT_OTP_DIGITS);
+        if !digits_in_range(digits) {
+            return Err(uri_error(
+                "range",
+                format!("digits must be between {MIN_OTP_DIGITS} and {MAX_OTP_DIGITS}"),
+            ));
+        }
+
+        Ok(Otp {
+            secret,
+            algorithm: self.algorithm.or(uri.algorithm).unwrap_or_default(),
+            digits,
+            period: uri.period,
+            counter: uri.counter,
+            issuer: uri.issuer,
+            account: uri.account,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct TotpGenerateInput {
+    #[serde(flatten)]
+    #[cfg_attr(feature = "ts-rs", ts(flatten))]
+    pub params: OtpParams,
+    /// Time step in seconds, [`DEFAULT_TOTP_PERIOD`] by default
+    #[serde(default)]
+    #[schemars(range(min = 1, max = MAX_TOTP_PERIOD))]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub period: Option<u32>,
+    /// Unix time in seconds to generate the code for, now by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional, type = "number"))]
+    pub timestamp: Option<u64>,
+}
+
+impl Validate for TotpGenerateInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        self.params.check(&mut errors);
+        check_period(self.period, &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct TotpResult {
+    pub code: String,
+    /// Number of the time step the code belongs to
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub counter: u64,
+    /// Seconds until the next code
+    pub valid_for_secs: u32,
+    pub period: u32,
+    pub digits: u32,
+    pub algorithm: OtpAlgorithm,
+    /// Issuer named by the `otpauth://` URI
+    pub issuer: Option<String>,
+    /// Account named by the `otpauth://` URI
+    pub account: Option<String>,
+    pub computation_time_ms: u64,
+}
+
+pub async fn totp_generate(input: &TotpGenerateInput) -> Result<TotpResult> {
+    let start = Instant::now();
+    let otp = input.params.resolve(OtpKind::Totp)?;
+    let period = resolve_period(input.period, &otp)?;
+    let timestamp = input.timestamp.unwrap_or_else(unix_time);
+
+    let counter = timestamp / u64::from(period);
+    let code = otp.code(counter);
+    let valid_for_secs = period - (timestamp % u64::from(period)) as u32;
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(TotpResult {
+        code,
+        counter,
+        valid_for_secs,
+        period,
+        digits: otp.digits,
+        algorithm: otp.algorithm,
+        issuer: otp.issuer,
+        account: otp.account,
+        computation_time_ms,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct TotpVerifyInput {
+    #[serde(flatten)]
+    #[cfg_attr(feature = "ts-rs", ts(flatten))]
+    pub params: OtpParams,
+    /// Code entered by the user, spaces are ignored
+    pub code: String,
+    /// Time step in seconds, [`DEFAULT_TOTP_PERIOD`] by default
+    #[serde(default)]
+    #[schemars(range(min = 1, max = MAX_TOTP_PERIOD))]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub period: Option<u32>,
+    /// Time steps accepted on either side of the current one to allow for clock drift,
+    /// [`DEFAULT_TOTP_WINDOW`] by default
+    #[serde(default)]
+    #[schemars(range(max = MAX_TOTP_WINDOW))]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub window: Option<u32>,
+    /// Unix time in seconds to verify the code at, now by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional, type = "number"))]
+    pub timestamp: Option<u64>,
+}
+
+impl Validate for TotpVerifyInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        self.params.check(&mut errors);
+        check_period(self.period, &mut errors);
+        if self.window.is_some_and(|window| window > MAX_TOTP_WINDOW) {
+            range(&mut errors, "window", 0, MAX_TOTP_WINDOW);
+        }
+        if self.code.len() > 64 {
+            let mut err = ValidationError::new("length");
+            err.add_param("max".into(), &64);
+            errors.add("code", err);
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct TotpVerifyResult {
+    pub valid: bool,
+    /// Time steps between the matching code and the current one, negative when it is older
+    pub drift: Option<i32>,
+    /// Time step of the matching code, store it to reject codes that are used twice
+    #[cfg_attr(feature = "ts-rs", ts(type = "number | null"))]
+    pub counter: Option<u64>,
+    pub computation_time_ms: u64,
+}
+
+pub async fn totp_verify(input: &TotpVerifyInput) -> Result<TotpVerifyResult> {
+    let start = Instant::now();
+    let otp = input.params.resolve(OtpKind::Totp)?;
+    let period = resolve_period(input.period, &otp)?;
+    let window = input.window.unwrap_or(DEFAULT_TOTP_WINDOW) as i32;
+    let timestamp = input.timestamp.unwrap_or_else(unix_time);
+    let code: String = input.code.chars().filter(|c| !c.is_whitespace()).collect();
+
+    let current = timestamp / u64::from(period);
+    let mut matched: Option<(i32, u64)> = None;
+    // Every step of the window is checked so the time taken doesn't reveal which one matched
+    for drift in -window..=window {
+        let Some(counter) = current.checked_add_signed(drift.into()) else {
+            continue;
+        };
+        let candidate = otp.code(counter);
+        let equal = bool::from(candidate.as_bytes().ct_eq(code.as_bytes()));
+        if equal && matched.is_none_or(|(best, _)| drift.abs() < best.abs()) {
+            matched = Some((drift, counter));
+        }
+    }
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(TotpVerifyResult {
+        valid: matched.is_some(),
+        drift: matched.map(|(drift, _)| drift),
+        counter: matched.map(|(_, counter)| counter),
+        computation_time_ms,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct HotpGenerateInput {
+    #[serde(flatten)]
+    #[cfg_attr(feature = "ts-rs", ts(flatten))]
+    pub params: OtpParams,
+    /// Moving factor of the code, required unless the `otpauth://` URI has one
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional, type = "number"))]
+    pub counter: Option<u64>,
+}
+
+impl Validate for HotpGenerateInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        self.params.check(&mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct HotpResult {
+    pub code: String,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub counter: u64,
+    pub digits: u32,
+    pub algorithm: OtpAlgorithm,
+    /// Issuer named by the `otpauth://` URI
+    pub issuer: Option<String>,
+    /// Account named by the `otpauth://` URI
+    pub account: Option<String>,
+    pub computation_time_ms: u64,
+}
+
+pub async fn hotp_generate(input: &HotpGenerateInput) -> Result<HotpResult> {
+    let start = Instant::now();
+    let otp = input.params.resolve(OtpKind::Hotp)?;
+    let counter = input.counter.or(otp.counter).ok_or_else(|| {
+        CommandError::invalid_params(vec![FieldError {
+            field: "counter".to_string(),
+            code: "required".to_string(),
+            msg: "the counter is required unless the URI has one".to_string(),
+        }])
+    })?;
+
+    let code = otp.code(counter);
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(HotpResult {
+        code,
+        counter,
+        digits: otp.digits,
+        algorithm: otp.algorithm,
+        issuer: otp.issuer,
+        account: otp.account,
+        computation_time_ms,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OtpKind {
+    Totp,
+    Hotp,
+}
+
+/// Fully resolved parameters of a one-time password.
+struct Otp {
+    secret: Vec<u8>,
+    algorithm: OtpAlgorithm,
+    digits: u32,
+    period: Option<u32>,
+    counter: Option<u64>,
+    issuer: Option<String>,
+    account: Option<String>,
+}
+
+impl Otp {
+    /// HOTP value of RFC 4226, TOTP uses the time step as the counter
+    fn code(&self, counter: u64) -> String {
+        let message = counter.to_be_bytes();
+        let mac = match self.algorithm {
+            OtpAlgorithm::Sha1 => compute_hmac::<Hmac<Sha1>>(&self.secret, &message),
+            OtpAlgorithm::Sha256 => compute_hmac::<Hmac<Sha256>>(&self.secret, &message),
+            OtpAlgorithm::Sha512 => compute_hmac::<Hmac<Sha512>>(&self.secret, &message),
+        };
+
+        // Dynamic truncation, the low nibble of the last byte picks 4 bytes of the MAC
+        let offset = usize::from(mac[mac.len() - 1] & 0x0f);
+        let bytes = [
+            mac[offset],
+            mac[offset + 1],
+            mac[offset + 2],
+            mac[offset + 3],
+        ];
+        let value = u64::from(u32::from_be_bytes(bytes) & 0x7fff_ffff);
+
+        let digits = self.digits as usize;
+        format!("{:0digits$}", value % 10u64.pow(self.digits))
+    }
+}
+
+/// Parameters read from an `otpauth://TYPE/LABEL?secret=...` URI.
+#[derive(Debug, Default, PartialEq)]
+struct OtpAuthUri {
+    secret: Option<String>,
+    algorithm: Option<OtpAlgorithm>,
+    digits: Option<u32>,
+    period: Option<u32>,
+    counter: Option<u64>,
+    issuer: Option<String>,
+    account: Option<String>,
+}
+
+impl OtpAuthUri {
+    fn parse(uri: &str, kind: OtpKind) -> Result<Self, CommandError> {
+        let rest = uri
+            .get(.."otpauth://".len())
+            .filter(|scheme| scheme.eq_ignore_ascii_case("otpauth://"))
+            .map(|scheme| &uri[scheme.len()..])
+            .ok_or_else(|| uri_error("format", "expected an otpauth:// URI"))?;
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (otp_type, label) = path.split_once('/').unwrap_or((path, ""));
+
+        let expected = match kind {
+            OtpKind::Totp => "totp",
+            OtpKind::Hotp => "hotp",
+        };
+        if !otp_type.eq_ignore_ascii_case(expected) {
+            return Err(uri_error(
+                "unsupported",
+                format!("expected a {expected} URI, got `{otp_type}`"),
+            ));
+        }
+
+        // The label is either `account` or `issuer:account`
+        let label = percent_decode(label)?;
+        let (issuer, account) = match label.split_once(':') {
+            Some((issuer, account)) => (Some(issuer.to_string()), account.trim_start()),
+            None => (None, label.as_str()),
+        };
+        let mut parsed = OtpAuthUri {
+            issuer,
+            account: (!account.is_empty()).then(|| account.to_string()),
+            ..Default::default()
+        };
+
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value)?;
+            let number_error = || uri_error("format", format!("invalid {name} `{value}`"));
+            match name {
+                "secret" => parsed.secret = Some(value),
+                "issuer" => parsed.issuer = Some(value),
+                "algorithm" => {
+                    parsed.algorithm = Some(match value.to_ascii_uppercase().as_str() {
+                        "SHA1" => OtpAlgorithm::Sha1,
+                        "SHA256" => OtpAlgorithm::Sha256,
+                        "SHA512" => OtpAlgorithm::Sha512,
+                        _ => {
+                            return Err(uri_error(
+                                "unsupported",
+                                format!("unsupported algorithm `{value}`"),
+                            ))
+                        }
+                    })
+                }
+                "digits" => parsed.digits = Some(value.parse().map_err(|_| number_error())?),
+                "period" => parsed.period = Some(value.parse().map_err(|_| number_error())?),
+                "counter" => parsed.counter = Some(value.parse().map_err(|_| number_error())?),
+                // Images, colors and other hints for authenticator apps
+                _ => {}
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+fn percent_decode(value: &str) -> Result<String, CommandError> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let digit = |i: usize| tail.get(i).and_then(|&b| (b as char).to_digit(16));
+            let (Some(high), Some(low)) = (digit(0), digit(1)) else {
+                return Err(uri_error("format", "invalid percent-encoding"));
+            };
+            bytes.push((high * 16 + low) as u8);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| uri_error("format", "the URI is not valid UTF-8"))
+}
+
+/// Decodes RFC 4648 base32, ignoring case, spaces, dashes and padding as authenticator apps do.
+fn decode_base32(data: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::with_capacity(data.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for (i, c) in data.char_indices() {
+        let value = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u32 - 'A' as u32,
+            c @ '2'..='7' => c as u32 - '2' as u32 + 26,
+            ' ' | '-' | '=' => continue,
+            _ => return Err(format!("invalid base32: bad character at offset {i}")),
+        };
+        buffer = (buffer << 5 | value) & 0xfff;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+
+    if bytes.is_empty() {
+        return Err("the secret is empty".to_string());
+    }
+    Ok(bytes)
+}
+
+fn resolve_period(period: Option<u32>, otp: &Otp) -> Result<u32, CommandError> {
+    let period = period.or(otp.period).unwrap_or(DEFAULT_TOTP_PERIOD);
+    if period == 0 || period > MAX_TOTP_PERIOD {
+        return Err(uri_error(
+            "range",
+            format!("period must be between 1 and {MAX_TOTP_PERIOD}"),
+        ));
+    }
+    Ok(period)
+}
+
+fn check_period(period: Option<u32>, errors: &mut ValidationErrors) {
+    if period.is_some_and(|period| period == 0 || period > MAX_TOTP_PERIOD) {
+        range(errors, "period", 1, MAX_TOTP_PERIOD);
+    }
+}
+
+fn digits_in_range(digits: u32) -> bool {
+    (MIN_OTP_DIGITS..=MAX_OTP_DIGITS).contains(&digits)
+}
+
+fn range(errors: &mut ValidationErrors, field: &'static str, min: u32, max: u32) {
+    let mut err = ValidationError::new("range");
+    err.add_param("min".into(), &min);
+    err.add_param("max".into(), &max);
+    errors.add(field, err);
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+fn secret_error(field: &str, msg: String) -> CommandError {
+    CommandError::invalid_params(vec![FieldError {
+        field: field.to_string(),
+        code: "encoding".to_string(),
+        msg,
+    }])
+}
+
+fn uri_error(code: &str, msg: impl ToString) -> CommandError {
+    CommandError::invalid_params(vec![FieldError {
+        field: "otpauth_uri".to_string(),
+        code: code.to_string(),
+        msg: msg.to_string(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::field_errors;
+
+    /// Base32 of the ASCII seeds of the RFC 6238 test vectors
+    const SHA1_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+    const SHA256_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZA====";
+    const SHA512_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQGEZDGNA=";
+
+    fn params(secret: &str, algorithm: OtpAlgorithm, digits: u32) -> OtpParams {
+        OtpParams {
+            secret: Some(secret.to_string()),
+            otpauth_uri: None,
+            algorithm: Some(algorithm),
+            digits: Some(digits),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_totp_generate_rfc6238() {
+        let cases = [
+            (59, "94287082", "46119246", "90693936"),
+            (1111111109, "07081804", "68084774", "25091201"),
+            (1234567890, "89005924", "91819424", "93441116"),
+            (20000000000, "65353130", "77737706", "47863826"),
+        ];
+        for (timestamp, sha1, sha256, sha512) in cases {
+            for (secret, algorithm, expected) in [
+                (SHA1_SECRET, OtpAlgorithm::Sha1, sha1),
+                (SHA256_SECRET, OtpAlgorithm::Sha256, sha256),
+                (SHA512_SECRET, OtpAlgorithm::Sha512, sha512),
+            ] {
+                let input = TotpGenerateInput {
+                    params: params(secret, algorithm, 8),
+                    period: None,
+                    timestamp: Some(timestamp),
+                };
+                let result = totp_generate(&input).await.unwrap();
+                assert_eq!(result.code, expected, "{algorithm:?} at {timestamp}");
+                assert_eq!(result.counter, timestamp / 30);
+            }
+        }
+
+        let input = TotpGenerateInput {
+            params: params(SHA1_SECRET, OtpAlgorithm::Sha1, 8),
+            period: None,
+            timestamp: Some(59),
+        };
+        assert_eq!(totp_generate(&input).await.unwrap().valid_for_secs, 1);
+    }
+
+    #[tokio::test]
+    async fn test_hotp_generate_and_otpauth_uri() {
+        // RFC 4226 appendix D
+        let expected = ["755224", "287082", "359152", "969429", "338314", "254676"];
+        for (counter, code) in expected.into_iter().enumerate() {
+            let input = HotpGenerateInput {
+                params: params(&SHA1_SECRET.to_lowercase(), OtpAlgorithm::Sha1, 6),
+                counter: Some(counter as u64),
+            };
+            assert_eq!(hotp_generate(&input).await.unwrap().code, code);
+        }
+
+        let uri = "otpauth://hotp/ACME%20Co:alice%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=ACME%20Co&counter=3";
+        let input = HotpGenerateInput {
+            params: OtpParams {
+                otpauth_uri: Some(uri.to_string()),
+                ..Default::default()
+            },
+            counter: None,
+        };
+        let result = hotp_generate(&input).await.unwrap();
+        assert_eq!(result.code, "969429");
+        assert_eq!(result.issuer.as_deref(), Some("ACME Co"));
+        assert_eq!(result.account.as_deref(), Some("alice@example.com"));
+
+        let uri = "otpauth://totp/bob?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&algorithm=SHA256&digits=8&period=60";
+        let parsed = OtpAuthUri::parse(uri, OtpKind::Totp).unwrap();
+        assert_eq!(parsed.algorithm, Some(OtpAlgorithm::Sha256));
+        assert_eq!((parsed.digits, parsed.period), (Some(8), Some(60)));
+        assert_eq!(parsed.issuer, None);
+
+        let err = eyre::Report::from(OtpAuthUri::parse(uri, OtpKind::Hotp).unwrap_err());
+        let errors = field_errors(&err).unwrap();
+        assert_eq!(errors[0].field, "otpauth_uri");
+        assert_eq!(errors[0].code, "unsupported");
+    }
+
+    #[tokio::test]
+    async fn test_totp_verify_window() {
+        let verify = |code: &str, timestamp: u64, window: Option<u32>| TotpVerifyInput {
+            params: params(SHA1_SECRET, OtpAlgorithm::Sha1, 8),
+            code: code.to_string(),
+            period: None,
+            window,
+            timestamp: Some(timestamp),
+        };
+
+        let result = totp_verify(&verify("0708 1804", 1111111109, None))
+            .await
+            .unwrap();
+        assert!(result.valid);
+        assert_eq!(result.drift, Some(0));
+        assert_eq!(result.counter, Some(1111111109 / 30));
+
+        // The code of 1111111109 is one step old 30 seconds later and two steps old a minute later
+        let result = totp_verify(&verify("07081804", 1111111139, None))
+            .await
+            .unwrap();
+        assert_eq!((result.valid, result.drift), (true, Some(-1)));
+        let result = totp_verify(&verify("07081804", 1111111169, None))
+            .await
+            .unwrap();
+        assert!(!result.valid);
+        let result = totp_verify(&verify("07081804", 1111111169, Some(2)))
+            .await
+            .unwrap();
+        assert_eq!(result.drift, Some(-2));
+
+        let invalid = TotpVerifyInput {
+            params: OtpParams {
+                secret: Some(SHA1_SECRET.to_string()),
+                otpauth_uri: Some("otpauth://totp/x".to_string()),
+                algorithm: None,
+                digits: Some(4),
+            },
+            ..verify("123456", 0, Some(MAX_TOTP_WINDOW + 1))
+        };
+        let errors = invalid.validate().unwrap_err();
+        let mut fields: Vec<_> = errors.field_errors().into_keys().collect();
+        fields.sort();
+        assert_eq!(fields, ["digits", "otpauth_uri", "window"]);
+    }
+}
@@ -0,0 +1,582 @@
+use crate::error::{CommandError, FieldError};
+use argon2::password_hash::{
+    Error as PhcError, PasswordHash, PasswordHasher, PasswordVerifier, SaltString,
+};
+use argon2::{Argon2, Version};
+use eyre::Result;
+use rand::rngs::OsRng;
+use schemars::JsonSchema;
+use scrypt::Scrypt;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+/// Argon2id memory cost in KiB when none is given, the OWASP recommendation
+pub const DEFAULT_ARGON2_MEMORY_COST: u32 = 19 * 1024;
+/// Argon2id iterations when none are given, the OWASP recommendation
+pub const DEFAULT_ARGON2_TIME_COST: u32 = 2;
+/// scrypt CPU/memory cost exponent when none is given, the OWASP recommendation
+pub const DEFAULT_SCRYPT_LOG_N: u8 = 17;
+/// scrypt block size when none is given
+pub const DEFAULT_SCRYPT_BLOCK_SIZE: u32 = 8;
+/// Largest memory either algorithm may use in KiB, hashes above it are rejected as well
+pub const MAX_PASSWORD_MEMORY_COST: u32 = 256 * 1024;
+/// Largest Argon2id iteration count
+pub const MAX_ARGON2_TIME_COST: u32 = 16;
+/// Largest number of Argon2id lanes or scrypt parallel mixes
+pub const MAX_PASSWORD_PARALLELISM: u32 = 16;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub enum PasswordAlgorithm {
+    #[default]
+    #[serde(rename = "argon2id")]
+    Argon2id,
+    #[serde(rename = "scrypt")]
+    Scrypt,
+}
+
+/// Algorithm and costs new hashes are produced with, defaults apply to the ones left out.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct PasswordParams {
+    /// `argon2id` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub algorithm: Option<PasswordAlgorithm>,
+    /// Argon2id memory in KiB
+    #[serde(default)]
+    #[schemars(range(min = 8, max = MAX_PASSWORD_MEMORY_COST))]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub memory_cost: Option<u32>,
+    /// Argon2id iterations
+    #[serde(default)]
+    #[schemars(range(min = 1, max = MAX_ARGON2_TIME_COST))]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub time_cost: Option<u32>,
+    /// Argon2id lanes or scrypt parallel mixes, 1 by default
+    #[serde(default)]
+    #[schemars(range(min = 1, max = MAX_PASSWORD_PARALLELISM))]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub parallelism: Option<u32>,
+    /// scrypt cost exponent, N = 2^log_n
+    #[serde(default)]
+    #[schemars(range(min = 1, max = 63))]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub log_n: Option<u8>,
+    /// scrypt block size r
+    #[serde(default)]
+    #[schemars(range(min = 1))]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub block_size: Option<u32>,
+}
+
+/// Fully resolved costs of a hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Costs {
+    Argon2id {
+        memory_cost: u32,
+        time_cost: u32,
+        parallelism: u32,
+    },
+    Scrypt {
+        log_n: u8,
+        block_size: u32,
+        parallelism: u32,
+    },
+}
+
+impl Costs {
+    /// Memory used by the hash in KiB, saturated for absurd scrypt parameters
+    fn memory_kib(self) -> u64 {
+        match self {
+            Costs::Argon2id { memory_cost, .. } => memory_cost.into(),
+            Costs::Scrypt {
+                log_n, block_size, ..
+            } => 1u64
+                .checked_shl(log_n.into())
+                .and_then(|n| n.checked_mul(u64::from(block_size) * 128))
+                .map_or(u64::MAX, |bytes| bytes / 1024),
+        }
+    }
+
+    /// Whether any cost is lower than the target's, or the algorithm differs
+    fn weaker_than(self, target: Costs) -> bool {
+        match (self, target) {
+            (
+                Costs::Argon2id {
+                    memory_cost,
+                    time_cost,
+                    ..
+                },
+                Costs::Argon2id {
+                    memory_cost: target_memory,
+                    time_cost: target_time,
+                    ..
+                },
+            ) => memory_cost < target_memory || time_cost < target_time,
+            (
+                Costs::Scrypt {
+                    log_n, block_size, ..
+                },
+                Costs::Scrypt {
+                    log_n: target_log_n,
+                    block_size: target_block_size,
+                    ..
+                },
+            ) => log_n < target_log_n || block_size < target_block_size,
+            _ => true,
+        }
+    }
+
+    /// Reads the costs of a parsed PHC string
+    fn of_hash(hash: &PasswordHash) -> Result<Costs, CommandError> {
+        match hash.algorithm.as_str() {
+            "argon2id" => {
+                let params = argon2::Params::try_from(hash).map_err(hash_error)?;
+                if hash.version != Some(Version::V0x13.into()) {
+                    return Err(hash_error("unsupported Argon2 version"));
+                }
+                Ok(Costs::Argon2id {
+                    memory_cost: params.m_cost(),
+                    time_cost: params.t_cost(),
+                    parallelism: params.p_cost(),
+                })
+            }
+            "scrypt" => {
+                let params = scrypt::Params::try_from(hash).map_err(hash_error)?;
+                Ok(Costs::Scrypt {
+                    log_n: params.log_n(),
+                    block_size: params.r(),
+                    parallelism: params.p(),
+                })
+            }
+            algorithm => Err(hash_error(format!(
+                "unsupported algorithm {algorithm}, expected argon2id or scrypt"
+            ))),
+        }
+    }
+}
+
+impl PasswordParams {
+    fn costs(&self) -> Costs {
+        let parallelism = self.parallelism.unwrap_or(1);
+        match self.algorithm.unwrap_or_default() {
+            PasswordAlgorithm::Argon2id => Costs::Argon2id {
+                memory_cost: self.memory_cost.unwrap_or(DEFAULT_ARGON2_MEMORY_COST),
+                time_cost: self.time_cost.unwrap_or(DEFAULT_ARGON2_TIME_COST),
+                parallelism,
+            },
+            PasswordAlgorithm::Scrypt => Costs::Scrypt {
+                log_n: self.log_n.unwrap_or(DEFAULT_SCRYPT_LOG_N),
+                block_size: self.block_size.unwrap_or(DEFAULT_SCRYPT_BLOCK_SIZE),
+                parallelism,
+            },
+        }
+    }
+
+    fn check(&self, errors: &mut ValidationErrors) {
+        let algorithm = self.algorithm.unwrap_or_default();
+        let unsupported = |errors: &mut ValidationErrors, field: &'static str| {
+            errors.add(
+                field,
+                ValidationError::new("unsupported")
+                    .with_message(format!("not used by {algorithm:?}").into()),
+            );
+        };
+        let range = |errors: &mut ValidationErrors, field: &'static str, min: u64, max: u64| {
+            let mut err = ValidationError::new("range");
+            err.add_param("min".into(), &min);
+            err.add_param("max".into(), &max);
+            errors.add(field, err);
+        };
+
+        match algorithm {
+            PasswordAlgorithm::Argon2id => {
+                if self.log_n.is_some() {
+                    unsupported(errors, "log_n");
+                }
+                if self.block_size.is_some() {
+                    unsupported(errors, "block_size");
+                }
+                // Argon2 needs at least 8 KiB per lane
+                let min_memory = 8 * self.parallelism.unwrap_or(1);
+                if let Some(memory_cost) = self.memory_cost {
+                    if memory_cost < min_memory || memory_cost > MAX_PASSWORD_MEMORY_COST {
+                        let max = MAX_PASSWORD_MEMORY_COST.into();
+                        range(errors, "memory_cost", min_memory.into(), max);
+                    }
+                }
+                if self
+                    .time_cost
+                    .is_some_and(|time_cost| time_cost == 0 || time_cost > MAX_ARGON2_TIME_COST)
+                {
+                    range(errors, "time_cost", 1, MAX_ARGON2_TIME_COST.into());
+                }
+            }
+            PasswordAlgorithm::Scrypt => {
+                if self.memory_cost.is_some() {
+                    unsupported(errors, "memory_cost");
+                }
+                if self.time_cost.is_some() {
+                    unsupported(errors, "time_cost");
+                }
+                if self.log_n == Some(0) {
+                    range(errors, "log_n", 1, 63);
+                }
+                if self.block_size == Some(0) {
+                    errors.add("block_size", ValidationError::new("range"));
+                }
+                if self.costs().memory_kib() > MAX_PASSWORD_MEMORY_COST.into() {
+                    errors.add(
+                        "log_n",
+                        ValidationError::new("range").with_message(
+                            format!(
+                                "128 * block_size * 2^log_n bytes must be at most {} MiB",
+                                MAX_PASSWORD_MEMORY_COST / 1024
+                            )
+                            .into(),
+                        ),
+                    );
+                }
+            }
+        }
+        if self
+            .parallelism
+            .is_some_and(|parallelism| parallelism == 0 || parallelism > MAX_PASSWORD_PARALLELISM)
+        {
+            range(errors, "parallelism", 1, MAX_PASSWORD_PARALLELISM.into());
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct PasswordHashInput {
+    pub password: String,
+    #[serde(flatten)]
+    #[cfg_attr(feature = "ts-rs", ts(flatten))]
+    pub params: PasswordParams,
+}
+
+impl Validate for PasswordHashInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        self.params.check(&mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct PasswordHashResult {
+    /// PHC string holding the algorithm, costs, salt and hash
+    pub hash: String,
+    pub algorithm: PasswordAlgorithm,
+    pub computation_time_ms: u64,
+}
+
+pub async fn password_hash(input: &PasswordHashInput) -> Result<PasswordHashResult> {
+    let start = Instant::now();
+    let algorithm = input.params.algorithm.unwrap_or_default();
+    let costs = input.params.costs();
+    let password = input.password.clone();
+
+    // Memory-hard hashing takes tens of milliseconds at least, keep it off the async runtime
+    let hash = tokio::task::spawn_blocking(move || -> Result<String> {
+        let salt = SaltString::generate(&mut OsRng);
+        let hash = match costs {
+            Costs::Argon2id {
+                memory_cost,
+                time_cost,
+                parallelism,
+            } => {
+                let params = argon2::Params::new(memory_cost, time_cost, parallelism, None)
+                    .map_err(|err| eyre::eyre!("invalid Argon2 parameters: {err}"))?;
+                Argon2::new(argon2::Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password(password.as_bytes(), &salt)
+            }
+            Costs::Scrypt {
+                log_n,
+                block_size,
+                parallelism,
+            } => {
+                let params = scrypt::Params::new(
+                    log_n,
+                    block_size,
+                    parallelism,
+                    scrypt::Params::RECOMMENDED_LEN,
+                )
+                .map_err(|err| eyre::eyre!("invalid scrypt parameters: {err}"))?;
+                Scrypt.hash_password_customized(password.as_bytes(), None, None, params, &salt)
+            }
+        };
+        Ok(hash
+            .map_err(|err| eyre::eyre!("failed to hash the password: {err}"))?
+            .to_string())
+    })
+    .await??;
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(PasswordHashResult {
+        hash,
+        algorithm,
+        computation_time_ms,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct PasswordVerifyInput {
+    pub password: String,
+    /// PHC string produced by `password_hash`
+    pub hash: String,
+    /// Parameters `needs_rehash` is checked against
+    #[serde(flatten)]
+    #[cfg_attr(feature = "ts-rs", ts(flatten))]
+    pub params: PasswordParams,
+}
+
+impl Validate for PasswordVerifyInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        self.params.check(&mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct PasswordVerifyResult {
+    pub valid: bool,
+    /// Whether the hash should be replaced by a new one of the password, once verified
+    pub needs_rehash: bool,
+    pub computation_time_ms: u64,
+}
+
+pub async fn password_verify(input: &PasswordVerifyInput) -> Result<PasswordVerifyResult> {
+    let start = Instant::now();
+    let (costs, needs_rehash) = check_hash(&input.hash, &input.params)?;
+    let password = input.password.clone();
+    let hash = input.hash.clone();
+
+    let valid = tokio::task::spawn_blocking(move || -> Result<bool> {
+        let hash = PasswordHash::new(&hash).map_err(hash_error)?;
+        let res = match costs {
+            Costs::Argon2id { .. } => Argon2::default().verify_password(password.as_bytes(), &hash),
+            Costs::Scrypt { .. } => Scrypt.verify_password(password.as_bytes(), &hash),
+        };
+        match res {
+            Ok(()) => Ok(true),
+            Err(PhcError::Password) => Ok(false),
+            Err(err) => Err(eyre::eyre!("failed to verify the password: {err}")),
+        }
+    })
+    .await??;
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(PasswordVerifyResult {
+        valid,
+        needs_rehash,
+        computation_time_ms,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct NeedsRehashInput {
+    /// PHC string produced by `password_hash`
+    pub hash: String,
+    #[serde(flatten)]
+    #[cfg_attr(feature = "ts-rs", ts(flatten))]
+    pub params: PasswordParams,
+}
+
+impl Validate for NeedsRehashInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        self.params.check(&mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct NeedsRehashResult {
+    /// Whether the hash uses another algorithm or lower costs than the given parameters
+    pub needs_rehash: bool,
+    pub computation_time_ms: u64,
+}
+
+pub async fn needs_rehash(input: &NeedsRehashInput) -> Result<NeedsRehashResult> {
+    let start = Instant::now();
+    let (_, needs_rehash) = check_hash(&input.hash, &input.params)?;
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(NeedsRehashResult {
+        needs_rehash,
+        computation_time_ms,
+    })
+}
+
+/// Parses a PHC string and compares its costs to `params`, rejecting hashes too costly to verify.
+fn check_hash(hash: &str, params: &PasswordParams) -> Result<(Costs, bool), CommandError> {
+    let hash = PasswordHash::new(hash).map_err(hash_error)?;
+    let costs = Costs::of_hash(&hash)?;
+
+    let parallelism = match costs {
+        Costs::Argon2id { parallelism, .. } | Costs::Scrypt { parallelism, .. } => parallelism,
+    };
+    if costs.memory_kib() > MAX_PASSWORD_MEMORY_COST.into()
+        || parallelism > MAX_PASSWORD_PARALLELISM
+        || matches!(costs, Costs::Argon2id { time_cost, .. } if time_cost > MAX_ARGON2_TIME_COST)
+    {
+        return Err(hash_error("the hash costs exceed the supported limits"));
+    }
+
+    Ok((costs, costs.weaker_than(params.costs())))
+}
+
+fn hash_error(msg: impl ToString) -> CommandError {
+    CommandError::invalid_params(vec![FieldError {
+        field: "hash".to_string(),
+        code: "format".to_string(),
+        msg: msg.to_string(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(algorithm: PasswordAlgorithm) -> PasswordParams {
+        // Cheap costs to keep the tests fast
+        match algorithm {
+            PasswordAlgorithm::Argon2id => PasswordParams {
+                algorithm: Some(algorithm),
+                memory_cost: Some(64),
+                time_cost: Some(1),
+                ..Default::default()
+            },
+            PasswordAlgorithm::Scrypt => PasswordParams {
+                algorithm: Some(algorithm),
+                log_n: Some(4),
+                block_size: Some(8),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_password_round_trip() {
+        for algorithm in [PasswordAlgorithm::Argon2id, PasswordAlgorithm::Scrypt] {
+            let input = PasswordHashInput {
+                password: "correct horse".to_string(),
+                params: params(algorithm),
+            };
+            assert!(input.validate().is_ok());
+            let hash = password_hash(&input).await.unwrap().hash;
+            let prefix = match algorithm {
+                PasswordAlgorithm::Argon2id => "$argon2id$v=19$m=64,t=1,p=1$",
+                PasswordAlgorithm::Scrypt => "$scrypt$ln=4,r=8,p=1$",
+            };
+            assert!(hash.starts_with(prefix), "{hash}");
+
+            let mut verify = PasswordVerifyInput {
+                password: "correct horse".to_string(),
+                hash,
+                params: params(algorithm),
+            };
+            let result = password_verify(&verify).await.unwrap();
+            assert!(result.valid);
+            assert!(!result.needs_rehash);
+
+            verify.password = "battery staple".to_string();
+            assert!(!password_verify(&verify).await.unwrap().valid);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_needs_rehash() {
+        let hash = password_hash(&PasswordHashInput {
+            password: "1234".to_string(),
+            params: params(PasswordAlgorithm::Argon2id),
+        })
+        .await
+        .unwrap()
+        .hash;
+        let cases = [
+            (params(PasswordAlgorithm::Argon2id), false),
+            (PasswordParams::default(), true),
+            (params(PasswordAlgorithm::Scrypt), true),
+        ];
+        for (params, expected) in cases {
+            let input = NeedsRehashInput {
+                hash: hash.clone(),
+                params,
+            };
+            assert_eq!(needs_rehash(&input).await.unwrap().needs_rehash, expected);
+        }
+
+        let err = needs_rehash(&NeedsRehashInput {
+            hash: "$argon2id$v=19$m=4194304,t=1,p=1$c2FsdHNhbHQ$aGFzaGhhc2hoYXNoaGFzaA".to_string(),
+            params: PasswordParams::default(),
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(crate::error::field_errors(&err).unwrap()[0].field, "hash");
+    }
+
+    #[test]
+    fn test_password_params() {
+        let input = PasswordHashInput {
+            password: String::new(),
+            params: PasswordParams {
+                algorithm: Some(PasswordAlgorithm::Scrypt),
+                time_cost: Some(3),
+                log_n: Some(30),
+                ..Default::default()
+            },
+        };
+        let errors = input.validate().unwrap_err();
+        let mut fields: Vec<_> = errors
+            .errors()
+            .keys()
+            .map(|field| field.to_string())
+            .collect();
+        fields.sort();
+        assert_eq!(fields, ["log_n", "time_cost"]);
+    }
+
+    #[cfg(feature = "ts-rs")]
+    #[test]
+    fn test_enum_literals_match_typescript() {
+        crate::cmd::verify_enum_literals::<PasswordAlgorithm>().unwrap();
+    }
+}
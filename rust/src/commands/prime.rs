@@ -1,123 +1,355 @@
 use eyre::Result;
 use rayon::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
+use validator::{Validate, ValidationError, ValidationErrors};
 
 #[cfg(feature = "ts-rs")]
 use ts_rs::TS;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Largest `limit` accepted by the `find_primes` command, the largest integer JavaScript
+/// numbers represent exactly
+pub const MAX_PRIME_LIMIT: u64 = (1 << 53) - 1;
+/// Largest span from `start` to `limit` that is sieved at once
+pub const MAX_PRIME_RANGE: u64 = 10_000_000_000;
+/// Largest span whose primes are all listed, wider ranges need `page` or `count_only`
+pub const MAX_PRIME_LIST_RANGE: u64 = 100_000_000;
+/// Largest number of primes in a page
+pub const MAX_PRIME_PAGE: u32 = 1_000_000;
+/// Odd numbers sieved at once, 32 KiB of bits so that a segment stays in the CPU cache
+const SEGMENT_BITS: u64 = 32 * 1024 * 8;
+/// Fewest segments worth spreading over the rayon pool. A single segment has nothing to share.
+/// This hasn't been measured on multi-core hardware yet, `bench_parallel_cutoff` times both
+/// paths to tune it.
+const PARALLEL_MIN_SEGMENTS: usize = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
-pub struct FindPrimesInput {
+pub struct PrimePage {
+    /// Number of primes of the range to skip
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub offset: u64,
+    /// Largest number of primes returned
+    #[schemars(range(min = 1, max = MAX_PRIME_PAGE))]
     pub limit: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct FindPrimesInput {
+    /// Smallest number of the range, 0 by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional, type = "number"))]
+    pub start: Option<u64>,
+    /// Largest number of the range, inclusive
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[schemars(range(max = MAX_PRIME_LIMIT))]
+    pub limit: u64,
     #[serde(default = "default_use_parallel")]
     pub use_parallel: bool,
+    /// Only count the primes of the range, π(limit) - π(start - 1), without listing them
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub count_only: Option<bool>,
+    /// Window of the primes to list, all of them by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub page: Option<PrimePage>,
 }
 
 fn default_use_parallel() -> bool {
     true
 }
 
+impl Validate for FindPrimesInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let start = self.start.unwrap_or(0);
+        let listed = !self.count_only.unwrap_or(false) && self.page.is_none();
+
+        if self.limit > MAX_PRIME_LIMIT {
+            let mut err = ValidationError::new("range");
+            err.add_param("max".into(), &MAX_PRIME_LIMIT);
+            errors.add("limit", err);
+        } else if start > self.limit {
+            errors.add(
+                "start",
+                ValidationError::new("range").with_message("must be at most `limit`".into()),
+            );
+        } else if self.limit - start > MAX_PRIME_RANGE {
+            let mut err = ValidationError::new("range")
+                .with_message("the range is too wide to sieve at once".into());
+            err.add_param("max".into(), &MAX_PRIME_RANGE);
+            errors.add("limit", err);
+        } else if listed && self.limit - start > MAX_PRIME_LIST_RANGE {
+            let mut err = ValidationError::new("range")
+                .with_message("the range is too wide to list, use `page` or `count_only`".into());
+            err.add_param("max".into(), &MAX_PRIME_LIST_RANGE);
+            errors.add("limit", err);
+        }
+        if let Some(page) = &self.page {
+            if page.limit == 0 || page.limit > MAX_PRIME_PAGE {
+                let mut err = ValidationError::new("range");
+                err.add_param("min".into(), &1);
+                err.add_param("max".into(), &MAX_PRIME_PAGE);
+                errors.add("page.limit", err);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
 pub struct FindPrimesResult {
-    pub primes: Vec<u32>,
-    pub count: usize,
-    pub limit: u32,
+    /// Primes of the requested page in increasing order, empty with `count_only`
+    #[cfg_attr(feature = "ts-rs", ts(type = "Array<number>"))]
+    pub primes: Vec<u64>,
+    /// Number of primes in the whole range
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub count: u64,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub start: u64,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub limit: u64,
     pub computation_time_ms: u64,
     pub used_parallel: bool,
 }
 
 pub async fn find_primes(input: &FindPrimesInput) -> Result<FindPrimesResult> {
     let start = Instant::now();
-    
-    let primes = if input.use_parallel {
-        find_primes_parallel(input.limit)
-    } else {
-        find_primes_sequential(input.limit)
+    let range_start = input.start.unwrap_or(0);
+
+    let used_parallel = input.use_parallel
+        && rayon::current_num_threads() > 1
+        && segment_lows(range_start, input.limit).len() >= PARALLEL_MIN_SEGMENTS;
+    let (skip, take) = match (&input.page, input.count_only.unwrap_or(false)) {
+        (_, true) => (0, 0),
+        (Some(page), false) => (page.offset, u64::from(page.limit)),
+        (None, false) => (0, u64::MAX),
     };
-    
+    let (primes, count) = sieve(range_start, input.limit, skip, take, used_parallel);
+
     let computation_time_ms = start.elapsed().as_millis() as u64;
-    let count = primes.len();
-    
+
     Ok(FindPrimesResult {
         primes,
         count,
+        start: range_start,
         limit: input.limit,
         computation_time_ms,
-        used_parallel: input.use_parallel,
+        used_parallel,
     })
 }
 
-fn find_primes_sequential(limit: u32) -> Vec<u32> {
-    if limit < 2 {
-        return vec![];
+/// Counts the primes of `[start, limit]` and lists `take` of them after the first `skip`.
+///
+/// Odd numbers are sieved in segments of [`SEGMENT_BITS`] bits, with the odd primes up to
+/// √limit, so memory doesn't grow with the range. In parallel, segments are sieved
+/// concurrently and their primes concatenated in order.
+fn sieve(start: u64, limit: u64, skip: u64, take: u64, parallel: bool) -> (Vec<u64>, u64) {
+    let end = skip.saturating_add(take);
+    let mut primes = Vec::new();
+    let mut count = 0;
+
+    if (start..=limit).contains(&2) {
+        if skip == 0 && take > 0 {
+            primes.push(2);
+        }
+        count += 1;
     }
-    
-    let mut is_prime = vec![true; (limit + 1) as usize];
-    is_prime[0] = false;
-    is_prime[1] = false;
-    
-    for i in 2..=((limit as f64).sqrt() as u32) {
-        if is_prime[i as usize] {
-            let mut j = i * i;
-            while j <= limit {
-                is_prime[j as usize] = false;
-                j += i;
-            }
+
+    let lows = segment_lows(start, limit);
+    if lows.is_empty() {
+        return (primes, count);
+    }
+    let base = base_primes(limit.isqrt(), parallel);
+
+    if skip == 0 && take == u64::MAX {
+        // Everything is listed, in a single pass
+        let segments = map_segments(&lows, limit, &base, parallel, |_, low, words| {
+            segment_primes(low, words).collect::<Vec<_>>()
+        });
+        primes.reserve(segments.iter().map(Vec::len).sum());
+        for segment in segments {
+            count += segment.len() as u64;
+            primes.extend(segment);
         }
+        return (primes, count);
     }
-    
-    (2..=limit)
-        .filter(|&i| is_prime[i as usize])
+
+    // Counted first, to find the segments of the page and only list those
+    let counts = map_segments(&lows, limit, &base, parallel, |_, _, words| {
+        words
+            .iter()
+            .map(|word| u64::from(word.count_ones()))
+            .sum::<u64>()
+    });
+    let mut page = Vec::new();
+    for (&low, segment_count) in lows.iter().zip(counts) {
+        if count + segment_count > skip && count < end {
+            let from = skip.saturating_sub(count) as usize;
+            let to = (end - count).min(segment_count) as usize;
+            page.push((low, from, to));
+        }
+        count += segment_count;
+    }
+
+    let page_lows: Vec<u64> = page.iter().map(|&(low, _, _)| low).collect();
+    let segments = map_segments(&page_lows, limit, &base, parallel, |i, low, words| {
+        let (_, from, to) = page[i];
+        segment_primes(low, words)
+            .skip(from)
+            .take(to - from)
+            .collect::<Vec<_>>()
+    });
+    primes.extend(segments.into_iter().flatten());
+
+    (primes, count)
+}
+
+/// First odd number of every segment of `[start, limit]`.
+fn segment_lows(start: u64, limit: u64) -> Vec<u64> {
+    let first_odd = start.max(1) | 1;
+    if first_odd > limit {
+        return vec![];
+    }
+    (first_odd..=limit)
+        .step_by(2 * SEGMENT_BITS as usize)
         .collect()
 }
 
-fn find_primes_parallel(limit: u32) -> Vec<u32> {
-    if limit < 2 {
+/// Sieves the segments starting at `lows` and maps each with `f(index, low, words)`, in order.
+///
+/// Every rayon worker reuses its own segment buffer.
+fn map_segments<T: Send>(
+    lows: &[u64],
+    limit: u64,
+    base: &[u32],
+    parallel: bool,
+    f: impl Fn(usize, u64, &[u64]) -> T + Sync + Send,
+) -> Vec<T> {
+    let sieve = |words: &mut Vec<u64>, (i, &low): (usize, &u64)| {
+        let high = limit.min(low + 2 * (SEGMENT_BITS - 1));
+        sieve_segment(low, high, base, words);
+        f(i, low, words)
+    };
+    if parallel {
+        lows.par_iter()
+            .enumerate()
+            .map_init(Vec::new, sieve)
+            .collect()
+    } else {
+        let mut words = Vec::new();
+        lows.iter()
+            .enumerate()
+            .map(|segment| sieve(&mut words, segment))
+            .collect()
+    }
+}
+
+/// Odd primes up to `limit`, which is at most √[`MAX_PRIME_LIMIT`].
+///
+/// They are sieved like any range, with the odd primes up to √limit found the same way.
+fn base_primes(limit: u64, parallel: bool) -> Vec<u32> {
+    if limit < 3 {
         return vec![];
     }
-    
-    // For small limits, sequential is faster due to overhead
-    if limit < 1000 {
-        return find_primes_sequential(limit);
-    }
-    
-    let mut is_prime = vec![true; (limit + 1) as usize];
-    is_prime[0] = false;
-    is_prime[1] = false;
-    
-    // Sequential sieve for small primes
-    for i in 2..=((limit as f64).sqrt() as u32) {
-        if is_prime[i as usize] {
-            let mut j = i * i;
-            while j <= limit {
-                is_prime[j as usize] = false;
-                j += i;
-            }
+    let base = base_primes(limit.isqrt(), parallel);
+    let segments = map_segments(
+        &segment_lows(1, limit),
+        limit,
+        &base,
+        parallel,
+        |_, low, words| {
+            segment_primes(low, words)
+                .map(|prime| prime as u32)
+                .collect::<Vec<_>>()
+        },
+    );
+    segments.concat()
+}
+
+/// Sieves the odd numbers of `[low, high]`, with `low` odd, into `words`.
+///
+/// Bit `i` stands for `low + 2i` and is set when that number is prime.
+fn sieve_segment(low: u64, high: u64, base: &[u32], words: &mut Vec<u64>) {
+    let bits = ((high - low) / 2 + 1) as usize;
+    words.clear();
+    words.resize(bits.div_ceil(64), u64::MAX);
+    let tail = bits % 64;
+    if tail != 0 {
+        words[bits / 64] = (1 << tail) - 1;
+    }
+    if low == 1 {
+        words[0] &= !1;
+    }
+
+    for &prime in base {
+        let prime = u64::from(prime);
+        if prime * prime > high {
+            break;
+        }
+        // Smaller multiples were crossed out by smaller primes, and even ones aren't stored
+        let mut multiple = (prime * prime).max(low.div_ceil(prime) * prime);
+        if multiple % 2 == 0 {
+            multiple += prime;
+        }
+        let mut bit = ((multiple - low) / 2) as usize;
+        while bit < bits {
+            words[bit / 64] &= !(1 << (bit % 64));
+            bit += prime as usize;
         }
     }
-    
-    // Parallel collection of results
-    (2..=limit)
-        .into_par_iter()
-        .filter(|&i| is_prime[i as usize])
-        .collect()
+}
+
+/// Primes of a sieved segment starting at `low`, in increasing order.
+fn segment_primes(low: u64, words: &[u64]) -> impl Iterator<Item = u64> + '_ {
+    words
+        .iter()
+        .enumerate()
+        .flat_map(move |(i, &word)| word_primes(low + 128 * i as u64, word))
+}
+
+/// Primes of a segment word whose first bit stands for `first`.
+fn word_primes(first: u64, mut word: u64) -> impl Iterator<Item = u64> {
+    std::iter::from_fn(move || {
+        if word == 0 {
+            return None;
+        }
+        let bit = word.trailing_zeros();
+        word &= word - 1;
+        Some(first + 2 * u64::from(bit))
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn input(limit: u64, use_parallel: bool) -> FindPrimesInput {
+        FindPrimesInput {
+            start: None,
+            limit,
+            use_parallel,
+            count_only: None,
+            page: None,
+        }
+    }
+
     #[tokio::test]
     async fn test_find_primes() {
-        let input = FindPrimesInput {
-            limit: 20,
-            use_parallel: false,
-        };
-        
+        let input = input(20, false);
+
         let result = find_primes(&input).await.unwrap();
         assert_eq!(result.primes, vec![2, 3, 5, 7, 11, 13, 17, 19]);
         assert_eq!(result.count, 8);
@@ -125,11 +357,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_find_primes_parallel() {
-        let input = FindPrimesInput {
-            limit: 20,
-            use_parallel: true,
-        };
-        
+        let input = input(20, true);
+
         let result = find_primes(&input).await.unwrap();
         let mut expected = vec![2, 3, 5, 7, 11, 13, 17, 19];
         let mut actual = result.primes.clone();
@@ -142,15 +371,119 @@ mod tests {
     #[tokio::test]
     async fn test_find_primes_edge_cases() {
         // Test limit = 0
-        let result = find_primes(&FindPrimesInput { limit: 0, use_parallel: false }).await.unwrap();
-        assert_eq!(result.primes, Vec::<u32>::new());
-        
+        let result = find_primes(&input(0, false)).await.unwrap();
+        assert_eq!(result.primes, Vec::<u64>::new());
+
         // Test limit = 1
-        let result = find_primes(&FindPrimesInput { limit: 1, use_parallel: false }).await.unwrap();
-        assert_eq!(result.primes, Vec::<u32>::new());
-        
+        let result = find_primes(&input(1, false)).await.unwrap();
+        assert_eq!(result.primes, Vec::<u64>::new());
+
         // Test limit = 2
-        let result = find_primes(&FindPrimesInput { limit: 2, use_parallel: false }).await.unwrap();
+        let result = find_primes(&input(2, false)).await.unwrap();
         assert_eq!(result.primes, vec![2]);
     }
+
+    #[tokio::test]
+    async fn test_find_primes_ranges_and_pages() {
+        // π(10^7), across many segments
+        let mut counted = input(10_000_000, true);
+        counted.count_only = Some(true);
+        let result = find_primes(&counted).await.unwrap();
+        assert_eq!(result.count, 664_579);
+        assert!(result.primes.is_empty());
+
+        // Far past u32, checked against trial division
+        let start = 1_000_000_000_000;
+        let mut range = input(start + 2_000, false);
+        range.start = Some(start);
+        let is_prime = |n: u64| (2..=n.isqrt()).all(|d| !n.is_multiple_of(d));
+        let expected: Vec<u64> = (start..=start + 2_000).filter(|&n| is_prime(n)).collect();
+        let result = find_primes(&range).await.unwrap();
+        assert_eq!(result.primes[0], 1_000_000_000_039);
+        assert_eq!(result.primes, expected);
+
+        // Pages crossing segment boundaries add up to the whole list
+        for use_parallel in [false, true] {
+            let all = find_primes(&input(2_000_000, use_parallel)).await.unwrap();
+            let mut paged = input(2_000_000, use_parallel);
+            let mut primes = Vec::new();
+            for offset in (0..all.count + 1).step_by(40_000) {
+                paged.page = Some(PrimePage {
+                    offset,
+                    limit: 40_000,
+                });
+                let result = find_primes(&paged).await.unwrap();
+                assert_eq!(result.count, all.count);
+                primes.extend(result.primes);
+            }
+            assert_eq!(primes, all.primes);
+        }
+    }
+
+    #[test]
+    fn test_sieve_parallel_matches_sequential() {
+        // Called directly, since `find_primes` stays sequential on a single thread pool
+        for (start, limit) in [(0, 3_000_000), (1_000_000_000_000, 1_000_002_000_000)] {
+            for (skip, take) in [(0, u64::MAX), (0, 0), (30_000, 50_000)] {
+                let sequential = sieve(start, limit, skip, take, false);
+                assert_eq!(sieve(start, limit, skip, take, true), sequential);
+            }
+        }
+        assert_eq!(base_primes(1000, true), base_primes(1000, false));
+        assert_eq!(base_primes(1000, false).len(), 167);
+    }
+
+    /// Times the sequential and parallel sieves by number of segments, to tune
+    /// [`PARALLEL_MIN_SEGMENTS`]. Run it in release mode, on the hardware to tune for:
+    /// `cargo test --release --lib bench_parallel_cutoff -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_parallel_cutoff() {
+        let threads = std::thread::available_parallelism().map_or(2, |n| n.get().max(2));
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        let median_ms = |limit: u64, parallel: bool| {
+            let mut times: Vec<f64> = (0..51)
+                .map(|_| {
+                    let start = Instant::now();
+                    std::hint::black_box(pool.install(|| sieve(0, limit, 0, 0, parallel)));
+                    start.elapsed().as_secs_f64() * 1e3
+                })
+                .collect();
+            times.sort_by(f64::total_cmp);
+            times[times.len() / 2]
+        };
+
+        println!("{threads} threads, count only, median of 51 runs");
+        println!("segments      limit  sequential ms  parallel ms");
+        for segments in [1, 2, 3, 4, 6, 8, 16, 32, 64] {
+            let limit = segments * 2 * SEGMENT_BITS - 1;
+            let sequential = median_ms(limit, false);
+            let parallel = median_ms(limit, true);
+            println!("{segments:>8} {limit:>10} {sequential:>14.3} {parallel:>12.3}");
+        }
+    }
+
+    #[test]
+    fn test_find_primes_validation() {
+        let mut input = input(MAX_PRIME_LIST_RANGE + 1, true);
+        let fields = |input: &FindPrimesInput| {
+            let errors = input.validate().unwrap_err();
+            let mut fields: Vec<_> = errors.field_errors().into_keys().collect();
+            fields.sort();
+            fields
+        };
+        assert_eq!(fields(&input), ["limit"]);
+
+        input.count_only = Some(true);
+        assert!(input.validate().is_ok());
+        input.start = Some(MAX_PRIME_LIST_RANGE + 2);
+        input.page = Some(PrimePage {
+            offset: 0,
+            limit: 0,
+        });
+        assert_eq!(fields(&input), ["page.limit", "start"]);
+    }
 }
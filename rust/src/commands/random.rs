@@ -0,0 +1,467 @@
+use crate::commands::hash::{encode_output, OutputEncoding};
+use crate::error::{CommandError, FieldError};
+use eyre::Result;
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+use rand_chacha::ChaCha20Rng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use validator::{Validate, ValidationError, ValidationErrors};
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+/// Largest number of values generated at once
+pub const MAX_RANDOM_COUNT: u32 = 10_000;
+/// Largest number of bytes per value in `bytes` mode
+pub const MAX_RANDOM_BYTES: u32 = 64 * 1024;
+/// Largest number of bytes generated at once in `bytes` mode, across all values
+pub const MAX_RANDOM_TOTAL_BYTES: u64 = 4 * 1024 * 1024;
+/// Largest nanoid length
+pub const MAX_NANOID_LENGTH: u32 = 256;
+/// Largest number of items to choose from or shuffle
+pub const MAX_RANDOM_ITEMS: u64 = 1_000_000;
+
+/// URL-safe alphabet of nanoid
+const NANOID_ALPHABET: &str = "_-0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// Crockford's base32 alphabet of ULIDs
+const ULID_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum UuidVersion {
+    /// Fully random
+    #[default]
+    V4,
+    /// Prefixed with the Unix time in milliseconds, so that they sort by creation time
+    V7,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RandomMode {
+    /// Random bytes, `base64` encoded by default
+    Bytes {
+        #[schemars(range(min = 1, max = MAX_RANDOM_BYTES))]
+        length: u32,
+        #[serde(default)]
+        #[cfg_attr(feature = "ts-rs", ts(optional))]
+        output_encoding: Option<OutputEncoding>,
+    },
+    /// Uniform integers in `[min, max]`
+    Int {
+        #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+        min: i64,
+        #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+        max: i64,
+    },
+    /// Uniform floats in `[min, max)`, `[0, 1)` by default
+    Float {
+        #[serde(default)]
+        #[cfg_attr(feature = "ts-rs", ts(optional))]
+        min: Option<f64>,
+        #[serde(default)]
+        #[cfg_attr(feature = "ts-rs", ts(optional))]
+        max: Option<f64>,
+    },
+    Uuid {
+        #[serde(default)]
+        #[cfg_attr(feature = "ts-rs", ts(optional))]
+        version: Option<UuidVersion>,
+    },
+    /// 26-character ULIDs, sortable by creation time
+    Ulid,
+    /// Nanoids, 21 URL-safe characters by default
+    Nanoid {
+        #[serde(default)]
+        #[schemars(range(min = 1, max = MAX_NANOID_LENGTH))]
+        #[cfg_attr(feature = "ts-rs", ts(optional))]
+        length: Option<u32>,
+        #[serde(default)]
+        #[cfg_attr(feature = "ts-rs", ts(optional))]
+        alphabet: Option<String>,
+    },
+    /// Items picked with replacement, with probabilities proportional to `weights` if given
+    Choice {
+        #[schemars(length(min = 1, max = MAX_RANDOM_ITEMS))]
+        #[cfg_attr(feature = "ts-rs", ts(type = "Array<any>"))]
+        items: Vec<Value>,
+        #[serde(default)]
+        #[cfg_attr(feature = "ts-rs", ts(optional))]
+        weights: Option<Vec<f64>>,
+    },
+    /// `items` in a uniformly random order (Fisher-Yates)
+    Shuffle {
+        #[schemars(length(max = MAX_RANDOM_ITEMS))]
+        #[cfg_attr(feature = "ts-rs", ts(type = "Array<any>"))]
+        items: Vec<Value>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct RandomInput {
+    pub mode: RandomMode,
+    /// Number of values, ignored by `shuffle`
+    #[serde(default = "default_count")]
+    #[schemars(range(min = 1, max = MAX_RANDOM_COUNT))]
+    pub count: u32,
+    /// Makes the output reproducible, for tests only since it is then predictable
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional, type = "number"))]
+    pub seed: Option<u64>,
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct RandomResult {
+    /// `count` values, or the shuffled items
+    #[cfg_attr(feature = "ts-rs", ts(type = "Array<any>"))]
+    pub values: Vec<Value>,
+    pub seeded: bool,
+    pub computation_time_ms: u64,
+}
+
+pub async fn random(input: &RandomInput) -> Result<RandomResult> {
+    let start = Instant::now();
+    // ChaCha20 seeded from the OS is a CSPRNG, and stays reproducible across platforms when seeded
+    let mut rng = match input.seed {
+        Some(seed) => ChaCha20Rng::seed_from_u64(seed),
+        None => ChaCha20Rng::from_entropy(),
+    };
+    let count = input.count as usize;
+
+    let values = match &input.mode {
+        RandomMode::Bytes {
+            length,
+            output_encoding,
+        } => {
+            let encoding = output_encoding.unwrap_or(OutputEncoding::Base64);
+            generate(count, || {
+                let mut bytes = vec![0; *length as usize];
+                rng.fill_bytes(&mut bytes);
+                match encode_output(bytes, encoding) {
+                    (_, Some(bytes)) => bytes.into(),
+                    (text, None) => text.into(),
+                }
+            })
+        }
+        RandomMode::Int { min, max } => generate(count, || rng.gen_range(*min..=*max).into()),
+        RandomMode::Float { min, max } => {
+            let (min, max) = (min.unwrap_or(0.0), max.unwrap_or(1.0));
+            generate(count, || rng.gen_range(min..max).into())
+        }
+        RandomMode::Uuid { version } => {
+            let version = version.unwrap_or_default();
+            generate(count, || uuid(&mut rng, version).into())
+        }
+        RandomMode::Ulid => generate(count, || ulid(&mut rng).into()),
+        RandomMode::Nanoid { length, alphabet } => {
+            let alphabet: Vec<char> = alphabet
+                .as_deref()
+                .unwrap_or(NANOID_ALPHABET)
+                .chars()
+                .collect();
+            let length = length.unwrap_or(21);
+            generate(count, || {
+                let id: String = (0..length)
+                    .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                    .collect();
+                id.into()
+            })
+        }
+        RandomMode::Choice { items, weights } => match weights {
+            Some(weights) => {
+                let index = WeightedIndex::new(weights).map_err(|err| {
+                    CommandError::invalid_params(vec![FieldError {
+                        field: "mode.weights".to_string(),
+                        code: "range".to_string(),
+                        msg: err.to_string(),
+                    }])
+                })?;
+                generate(count, || items[index.sample(&mut rng)].clone())
+            }
+            None => generate(count, || {
+                items.choose(&mut rng).cloned().unwrap_or_default()
+            }),
+        },
+        RandomMode::Shuffle { items } => {
+            let mut items = items.clone();
+            items.shuffle(&mut rng);
+            items
+        }
+    };
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(RandomResult {
+        values,
+        seeded: input.seed.is_some(),
+        computation_time_ms,
+    })
+}
+
+fn generate(count: usize, f: impl FnMut() -> Value) -> Vec<Value> {
+    std::iter::repeat_with(f).take(count).collect()
+}
+
+fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+fn uuid(rng: &mut impl RngCore, version: UuidVersion) -> String {
+    let mut bytes = [0u8; 16];
+    rng.fill_bytes(&mut bytes);
+    if version == UuidVersion::V7 {
+        bytes[..6].copy_from_slice(&unix_time_ms().to_be_bytes()[2..]);
+    }
+    let version = match version {
+        UuidVersion::V4 => 0x40,
+        UuidVersion::V7 => 0x70,
+    };
+    bytes[6] = (bytes[6] & 0x0f) | version;
+    // RFC 9562 variant
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex = crate::commands::hash::encode_hex(&bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+fn ulid(rng: &mut impl RngCore) -> String {
+    let mut random = [0u8; 16];
+    rng.fill_bytes(&mut random[6..]);
+    let value = (u128::from(unix_time_ms()) << 80) | u128::from_be_bytes(random);
+
+    // 26 base32 digits hold 130 bits, the top two are always zero
+    (0..26)
+        .rev()
+        .map(|digit| ULID_ALPHABET[(value >> (digit * 5)) as usize & 0x1f] as char)
+        .collect()
+}
+
+// Implemented by hand since the limits depend on the mode
+impl Validate for RandomInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let range = |errors: &mut ValidationErrors, field: &'static str, min: u64, max: u64| {
+            let mut err = ValidationError::new("range");
+            err.add_param("min".into(), &min);
+            err.add_param("max".into(), &max);
+            errors.add(field, err);
+        };
+        let invalid = |errors: &mut ValidationErrors, field: &'static str, msg: &'static str| {
+            errors.add(
+                field,
+                ValidationError::new("range").with_message(msg.into()),
+            );
+        };
+
+        if self.count == 0 || self.count > MAX_RANDOM_COUNT {
+            range(&mut errors, "count", 1, MAX_RANDOM_COUNT.into());
+        }
+
+        match &self.mode {
+            RandomMode::Bytes { length, .. } => {
+                if *length == 0 || *length > MAX_RANDOM_BYTES {
+                    range(&mut errors, "mode.length", 1, MAX_RANDOM_BYTES.into());
+                } else if u64::from(self.count) * u64::from(*length) > MAX_RANDOM_TOTAL_BYTES {
+                    let mut err = ValidationError::new("range")
+                        .with_message("count * length exceeds the total byte limit".into());
+                    err.add_param("max".into(), &MAX_RANDOM_TOTAL_BYTES);
+                    errors.add("mode.length", err);
+                }
+            }
+            RandomMode::Int { min, max } => {
+                if min > max {
+                    invalid(&mut errors, "mode.max", "must be at least min");
+                }
+            }
+            RandomMode::Float { min, max } => {
+                let (min, max) = (min.unwrap_or(0.0), max.unwrap_or(1.0));
+                // The span must be finite too, `{min: -1e308, max: 1e308}` overflows it
+                if !(max - min).is_finite() || min >= max {
+                    invalid(
+                        &mut errors,
+                        "mode.max",
+                        "must be finite and greater than min",
+                    );
+                }
+            }
+            RandomMode::Uuid { .. } | RandomMode::Ulid => {}
+            RandomMode::Nanoid { length, alphabet } => {
+                if length.is_some_and(|length| length == 0 || length > MAX_NANOID_LENGTH) {
+                    range(&mut errors, "mode.length", 1, MAX_NANOID_LENGTH.into());
+                }
+                if let Some(alphabet) = alphabet {
+                    let mut chars: Vec<char> = alphabet.chars().collect();
+                    chars.sort_unstable();
+                    chars.dedup();
+                    if chars.len() < 2 || chars.len() != alphabet.chars().count() {
+                        let msg = "needs at least two distinct characters, without repeats";
+                        invalid(&mut errors, "mode.alphabet", msg);
+                    }
+                }
+            }
+            RandomMode::Choice { items, weights } => {
+                if items.is_empty() || items.len() as u64 > MAX_RANDOM_ITEMS {
+                    range(&mut errors, "mode.items", 1, MAX_RANDOM_ITEMS);
+                }
+                if weights
+                    .as_ref()
+                    .is_some_and(|weights| weights.len() != items.len())
+                {
+                    invalid(
+                        &mut errors,
+                        "mode.weights",
+                        "one weight is required per item",
+                    );
+                }
+            }
+            RandomMode::Shuffle { items } => {
+                if items.len() as u64 > MAX_RANDOM_ITEMS {
+                    range(&mut errors, "mode.items", 0, MAX_RANDOM_ITEMS);
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(mode: RandomMode, count: u32) -> RandomInput {
+        RandomInput {
+            mode,
+            count,
+            seed: Some(42),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_random_seeded() {
+        let dice = input(RandomMode::Int { min: 1, max: 6 }, 100);
+        let first = random(&dice).await.unwrap().values;
+        assert_eq!(first, random(&dice).await.unwrap().values);
+        assert!(first
+            .iter()
+            .all(|value| (1..=6).contains(&value.as_i64().unwrap())));
+
+        let items: Vec<Value> = (0..10).map(Value::from).collect();
+        let shuffled = random(&input(
+            RandomMode::Shuffle {
+                items: items.clone(),
+            },
+            1,
+        ))
+        .await
+        .unwrap()
+        .values;
+        let mut sorted = shuffled.clone();
+        sorted.sort_by_key(|value| value.as_i64());
+        assert_eq!(sorted, items);
+        assert_ne!(shuffled, items);
+
+        let weighted = RandomMode::Choice {
+            items: vec!["never".into(), "always".into()],
+            weights: Some(vec![0.0, 1.0]),
+        };
+        let values = random(&input(weighted, 20)).await.unwrap().values;
+        assert!(values.iter().all(|value| value == "always"));
+    }
+
+    #[tokio::test]
+    async fn test_random_identifiers() {
+        let uuid = RandomMode::Uuid {
+            version: Some(UuidVersion::V7),
+        };
+        let values = random(&input(uuid, 1)).await.unwrap().values;
+        let uuid = values[0].as_str().unwrap();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "7");
+        assert!(matches!(&uuid[19..20], "8" | "9" | "a" | "b"));
+
+        let values = random(&input(RandomMode::Ulid, 1)).await.unwrap().values;
+        let ulid = values[0].as_str().unwrap();
+        assert_eq!(ulid.len(), 26);
+        // The timestamp prefix decodes to the current time
+        let time = ulid[..10].bytes().fold(0u64, |time, c| {
+            let digit = ULID_ALPHABET.iter().position(|&d| d == c).unwrap() as u64;
+            time * 32 + digit
+        });
+        assert!(unix_time_ms() - time < 60_000);
+
+        let nanoid = RandomMode::Nanoid {
+            length: Some(8),
+            alphabet: Some("ab".to_string()),
+        };
+        let values = random(&input(nanoid, 1)).await.unwrap().values;
+        let id = values[0].as_str().unwrap();
+        assert!(id.len() == 8 && id.chars().all(|c| c == 'a' || c == 'b'));
+    }
+
+    #[test]
+    fn test_random_params() {
+        let errors = input(RandomMode::Int { min: 5, max: 1 }, 0)
+            .validate()
+            .unwrap_err();
+        let mut fields: Vec<_> = errors
+            .errors()
+            .keys()
+            .map(|field| field.to_string())
+            .collect();
+        fields.sort();
+        assert_eq!(fields, ["count", "mode.max"]);
+
+        let choice = RandomMode::Choice {
+            items: vec![1.into(), 2.into()],
+            weights: Some(vec![1.0]),
+        };
+        assert!(input(choice, 1).validate().is_err());
+
+        let float = |min, max| RandomMode::Float {
+            min: Some(min),
+            max: Some(max),
+        };
+        assert!(input(float(-1e308, 1e308), 1).validate().is_err());
+        assert!(input(float(f64::NEG_INFINITY, 0.0), 1).validate().is_err());
+        assert!(input(float(-1e307, 1e307), 1).validate().is_ok());
+
+        let bytes = |length| RandomMode::Bytes {
+            length,
+            output_encoding: None,
+        };
+        assert!(input(bytes(MAX_RANDOM_BYTES), 64).validate().is_ok());
+        let errors = input(bytes(MAX_RANDOM_BYTES), MAX_RANDOM_COUNT)
+            .validate()
+            .unwrap_err();
+        assert!(errors.errors().contains_key("mode.length"));
+    }
+}
@@ -0,0 +1,172 @@
+use crate::cmd::Command;
+use eyre::{eyre, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::time::Instant;
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SchemaInput {
+    /// Command whose `params` schema to return, the whole `Command` schema when omitted
+    #[serde(default)]
+    pub cmd: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SchemaResult {
+    pub cmd: Option<String>,
+    #[cfg_attr(feature = "ts-rs", ts(type = "Record<string, any>"))]
+    pub schema: Value,
+    pub computation_time_ms: u64,
+}
+
+pub async fn schema(input: &SchemaInput) -> Result<SchemaResult> {
+    let start = Instant::now();
+
+    let schema = match &input.cmd {
+        Some(cmd) => params_schema(cmd).ok_or_else(|| eyre!("unknown command `{cmd}`"))?,
+        None => command_schema(),
+    };
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(SchemaResult {
+        cmd: input.cmd.clone(),
+        schema,
+        computation_time_ms,
+    })
+}
+
+/// JSON Schema of the whole `Command` envelope, with every input type under `$defs`.
+pub fn command_schema() -> Value {
+    let mut root = schemars::schema_for!(Command).to_value();
+
+    // Nested commands refer to the document root, which is only `Command` in this document.
+    // Point them at a definition instead so that sub-schemas can stand on their own.
+    let mut command = root.clone();
+    if let Some(command) = command.as_object_mut() {
+        command.remove("$schema");
+        command.remove("$defs");
+    }
+    rewrite_root_refs(&mut root);
+    rewrite_root_refs(&mut command);
+    if let Some(defs) = root["$defs"].as_object_mut() {
+        defs.insert("Command".to_string(), command);
+    }
+
+    root
+}
+
+fn rewrite_root_refs(schema: &mut Value) {
+    match schema {
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                if name == "$ref" && field == "#" {
+                    *field = Value::String("#/$defs/Command".to_string());
+                } else {
+                    rewrite_root_refs(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(rewrite_root_refs),
+        _ => {}
+    }
+}
+
+/// Standalone JSON Schema of the `params` of a single command.
+///
+/// Commands without parameters get a schema that only accepts `null`.
+pub fn params_schema(cmd: &str) -> Option<Value> {
+    let root = command_schema();
+    let variant = root["oneOf"].as_array()?.iter().find(|variant| {
+        let tag = &variant["properties"]["cmd"];
+        tag["const"] == cmd || tag["enum"][0] == cmd
+    })?;
+
+    let params = match variant["properties"].get("params") {
+        // Inline the referenced input type so its properties are at the top level
+        Some(params) => match params["$ref"].as_str() {
+            Some(reference) => {
+                let name = reference.trim_start_matches("#/$defs/");
+                root["$defs"][name].clone()
+            }
+            None => params.clone(),
+        },
+        None => serde_json::json!({ "type": "null" }),
+    };
+    Some(standalone(cmd, params, &root))
+}
+
+/// Standalone JSON Schema of every type referenced by `Command`, keyed by type name.
+pub fn type_schemas() -> Map<String, Value> {
+    let root = command_schema();
+    let Some(defs) = root["$defs"].as_object() else {
+        return Map::new();
+    };
+
+    defs.iter()
+        .map(|(name, schema)| (name.clone(), standalone(name, schema.clone(), &root)))
+        .collect()
+}
+
+/// Turns a sub-schema of `root` into a document of its own, carrying over the definitions.
+fn standalone(title: &str, schema: Value, root: &Value) -> Value {
+    let mut out = Map::new();
+    out.insert("$schema".to_string(), root["$schema"].clone());
+    out.insert("title".to_string(), Value::String(title.to_string()));
+
+    match schema {
+        Value::Object(fields) => out.extend(fields),
+        // `true` / `false` schemas, or a bare `$ref`
+        other => {
+            out.insert("allOf".to_string(), Value::Array(vec![other]));
+        }
+    }
+    if root["$defs"].is_object() {
+        out.insert("$defs".to_string(), root["$defs"].clone());
+    }
+
+    Value::Object(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_params_schema_limits() {
+        let result = schema(&SchemaInput {
+            cmd: Some("sort_numbers".to_string()),
+        })
+        .await
+        .unwrap();
+
+        let numbers = &result.schema["properties"]["numbers"];
+        assert_eq!(numbers["type"], "array");
+        assert_eq!(
+            numbers["maxItems"],
+            crate::commands::sort::MAX_SORT_LEN as i64
+        );
+        assert!(result.schema["$defs"]["SortAlgorithm"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_command_schema() {
+        let input = SchemaInput {
+            cmd: Some("nope".to_string()),
+        };
+        assert!(schema(&input).await.is_err());
+    }
+
+    #[test]
+    fn test_params_schema_without_params() {
+        assert_eq!(params_schema("logs").unwrap()["type"], "null");
+    }
+}
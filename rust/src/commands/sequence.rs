@@ -0,0 +1,409 @@
+use crate::math::{
+    self, check_modulus, linear_recurrence, lucas, Arithmetic, ArithmeticMode, Checked, Exact,
+    Modular,
+};
+use eyre::{eyre, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+/// Largest number of coefficients of a linear recurrence
+pub const MAX_SEQUENCE_ORDER: u64 = 32;
+/// Largest number of terms returned at once
+pub const MAX_SEQUENCE_COUNT: u32 = 10_000;
+/// Largest term index of a linear recurrence in `big_int` mode
+pub const MAX_SEQUENCE_BIG_N: u64 = 1_000_000;
+/// Largest term index of the Catalan numbers and factorials, which are computed term by term
+pub const MAX_SEQUENCE_DIRECT_N: u64 = 100_000;
+/// Largest total number of digits returned at once
+pub const MAX_SEQUENCE_OUTPUT_LEN: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(tag = "family", rename_all = "snake_case")]
+pub enum Sequence {
+    /// a(n) = c1 a(n-1) + ... + ck a(n-k) with `initial` holding a(0)..a(k-1)
+    Linear {
+        #[schemars(length(min = 1, max = MAX_SEQUENCE_ORDER))]
+        #[cfg_attr(feature = "ts-rs", ts(type = "Array<number>"))]
+        coefficients: Vec<i64>,
+        #[cfg_attr(feature = "ts-rs", ts(type = "Array<number>"))]
+        initial: Vec<i64>,
+    },
+    /// 0, 1, 1, 2, 3, 5, ...
+    Fibonacci,
+    /// 2, 1, 3, 4, 7, 11, ...
+    Lucas,
+    /// 0, 0, 1, 1, 2, 4, 7, ...
+    Tribonacci,
+    /// 1, 1, 2, 5, 14, 42, ...
+    Catalan,
+    /// 1, 1, 2, 6, 24, 120, ...
+    Factorial,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SequenceInput {
+    pub sequence: Sequence,
+    /// Index of the first term
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub n: u64,
+    /// Number of consecutive terms, only a(n) by default
+    #[serde(default = "default_count")]
+    #[schemars(range(min = 1, max = MAX_SEQUENCE_COUNT))]
+    pub count: u32,
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub mode: Option<ArithmeticMode>,
+    /// Required in `modular` mode
+    #[serde(default)]
+    #[schemars(range(min = 1))]
+    #[cfg_attr(feature = "ts-rs", ts(optional, type = "number"))]
+    pub modulus: Option<u64>,
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SequenceResult {
+    /// Decimal digits of a(n)..a(n + count - 1), fewer than `count` when `overflowed`
+    pub terms: Vec<String>,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub n: u64,
+    pub mode: ArithmeticMode,
+    /// Whether a term didn't fit in an `i64` in `fixed` mode, `terms` stop right before it
+    pub overflowed: bool,
+    pub computation_time_ms: u64,
+}
+
+pub async fn sequence(input: &SequenceInput) -> Result<SequenceResult> {
+    let start = Instant::now();
+    let mode = input.mode.unwrap_or_default();
+    let count = input.count as usize;
+
+    let terms = match mode {
+        ArithmeticMode::Fixed => evaluate(&Checked, &input.sequence, input.n, count)?,
+        ArithmeticMode::BigInt => evaluate(&Exact, &input.sequence, input.n, count)?,
+        ArithmeticMode::Modular => {
+            let modular = Modular(input.modulus.unwrap_or(1));
+            evaluate(&modular, &input.sequence, input.n, count)?
+        }
+    };
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(SequenceResult {
+        overflowed: terms.len() < count,
+        terms,
+        n: input.n,
+        mode,
+        computation_time_ms,
+    })
+}
+
+fn evaluate<A: Arithmetic>(
+    ar: &A,
+    sequence: &Sequence,
+    n: u64,
+    count: usize,
+) -> Result<Vec<String>> {
+    let terms = match sequence {
+        Sequence::Linear {
+            coefficients,
+            initial,
+        } => linear_recurrence(ar, coefficients, initial, n, count),
+        Sequence::Fibonacci => math::fibonacci(ar, n, count),
+        Sequence::Lucas => lucas(ar, n, count),
+        Sequence::Tribonacci => linear_recurrence(ar, &[1, 1, 1], &[0, 0, 1], n, count),
+        Sequence::Catalan => ar.catalan(n, count),
+        Sequence::Factorial => ar.factorials(n, count),
+    };
+
+    // `validate` bounds the digits up front, this only guards callers that skip it
+    let output_len: usize = terms.iter().map(|term| ar.decimal_len(term)).sum();
+    if output_len > MAX_SEQUENCE_OUTPUT_LEN {
+        return Err(eyre!(
+            "sequence output would exceed {MAX_SEQUENCE_OUTPUT_LEN} digits, request fewer terms"
+        ));
+    }
+
+    Ok(terms.iter().map(|term| ar.to_decimal(term)).collect())
+}
+
+/// Upper bound of the number of decimal digits of the term `k`.
+fn term_digits(sequence: &Sequence, k: u64) -> usize {
+    let k = k as f64;
+    let phi_log10 = ((1.0 + 5f64.sqrt()) / 2.0).log10();
+    let log10 = match sequence {
+        // |a(k)| <= max(|a(i)|) s^k with s the sum of |c(i)|, by induction on k
+        Sequence::Linear {
+            coefficients,
+            initial,
+        } => {
+            let sum: f64 = coefficients.iter().map(|&c| c.unsigned_abs() as f64).sum();
+            let largest = initial.iter().map(|&a| a.unsigned_abs()).max().unwrap_or(0);
+            (largest.max(1) as f64).log10() + k * sum.max(1.0).log10()
+        }
+        // F(k) < φ^k, L(k) < φ^(k+2) and T(k) < 1.84^k
+        Sequence::Fibonacci => k * phi_log10,
+        Sequence::Lucas => (k + 2.0) * phi_log10,
+        Sequence::Tribonacci => k * 1.84f64.log10(),
+        // C(k) <= 4^k / (sqrt(π) k^1.5)
+        Sequence::Catalan if k >= 1.0 => {
+            k * 4f64.log10() - 1.5 * k.log10() - 0.5 * std::f64::consts::PI.log10()
+        }
+        // Stirling's formula with its first correction term bounds k! from above
+        Sequence::Factorial if k >= 2.0 => {
+            k * (k / std::f64::consts::E).log10()
+                + 0.5 * (2.0 * std::f64::consts::PI * k).log10()
+                + 1.0 / (12.0 * k * std::f64::consts::LN_10)
+        }
+        Sequence::Catalan | Sequence::Factorial => 0.0,
+    };
+    // One more digit than floor(log10), and one for rounding errors
+    log10.max(0.0) as usize + 2
+}
+
+// Implemented by hand since the limits depend on the sequence and the mode
+impl Validate for SequenceInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mode = self.mode.unwrap_or_default();
+        let mut errors = ValidationErrors::new();
+
+        if self.count == 0 || self.count > MAX_SEQUENCE_COUNT {
+            let mut err = ValidationError::new("range");
+            err.add_param("min".into(), &1);
+            err.add_param("max".into(), &MAX_SEQUENCE_COUNT);
+            errors.add("count", err);
+        }
+
+        if let Sequence::Linear {
+            coefficients,
+            initial,
+        } = &self.sequence
+        {
+            if coefficients.is_empty() || coefficients.len() as u64 > MAX_SEQUENCE_ORDER {
+                let mut err = ValidationError::new("length");
+                err.add_param("min".into(), &1);
+                err.add_param("max".into(), &MAX_SEQUENCE_ORDER);
+                errors.add("sequence.coefficients", err);
+            }
+            if initial.len() != coefficients.len() {
+                errors.add(
+                    "sequence.initial",
+                    ValidationError::new("length")
+                        .with_message("one initial term is required per coefficient".into()),
+                );
+            }
+        }
+
+        let max_n = match (&self.sequence, mode) {
+            (Sequence::Catalan | Sequence::Factorial, _) => Some(MAX_SEQUENCE_DIRECT_N),
+            (_, ArithmeticMode::BigInt) => Some(MAX_SEQUENCE_BIG_N),
+            _ => None,
+        };
+        let last = self.n.saturating_add(u64::from(self.count.max(1)) - 1);
+        if let Some(max_n) = max_n.filter(|&max_n| last > max_n) {
+            let mut err = ValidationError::new("range")
+                .with_message(format!("the last term index must be at most {max_n}").into());
+            err.add_param("max".into(), &max_n);
+            errors.add("n", err);
+        }
+
+        // Only exact terms grow past 20 digits
+        if mode == ArithmeticMode::BigInt && (1..=MAX_SEQUENCE_COUNT).contains(&self.count) {
+            let output_len = (0..u64::from(self.count))
+                .map(|i| term_digits(&self.sequence, self.n.saturating_add(i)))
+                .fold(0usize, usize::saturating_add);
+            if output_len > MAX_SEQUENCE_OUTPUT_LEN {
+                let msg = format!(
+                    "the terms would exceed {MAX_SEQUENCE_OUTPUT_LEN} digits, request fewer terms"
+                );
+                errors.add(
+                    "count",
+                    ValidationError::new("range").with_message(msg.into()),
+                );
+            }
+        }
+
+        check_modulus(mode, self.modulus, &mut errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(sequence: Sequence, n: u64, count: u32, mode: ArithmeticMode) -> SequenceInput {
+        SequenceInput {
+            sequence,
+            n,
+            count,
+            mode: Some(mode),
+            modulus: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sequence_families() {
+        let result = sequence(&input(Sequence::Tribonacci, 0, 8, ArithmeticMode::Fixed))
+            .await
+            .unwrap();
+        assert_eq!(result.terms, ["0", "0", "1", "1", "2", "4", "7", "13"]);
+
+        let result = sequence(&input(Sequence::Factorial, 18, 4, ArithmeticMode::Fixed))
+            .await
+            .unwrap();
+        // 21! no longer fits in an i64
+        assert_eq!(result.terms.len(), 3);
+        assert!(result.overflowed);
+
+        let result = sequence(&input(Sequence::Factorial, 25, 1, ArithmeticMode::BigInt))
+            .await
+            .unwrap();
+        assert_eq!(result.terms, ["15511210043330985984000000"]);
+        assert!(!result.overflowed);
+    }
+
+    #[tokio::test]
+    async fn test_sequence_modular() {
+        let mut input = input(
+            Sequence::Linear {
+                coefficients: vec![1, 1],
+                initial: vec![0, 1],
+            },
+            1_000_000_000_000,
+            2,
+            ArithmeticMode::Modular,
+        );
+        input.modulus = Some(1_000_000_007);
+
+        let result = sequence(&input).await.unwrap();
+        let (a, b) = math::fibonacci_pair_mod(1_000_000_000_000, 1_000_000_007);
+        assert_eq!(result.terms, [a.to_string(), b.to_string()]);
+    }
+
+    #[test]
+    fn test_sequence_params() {
+        let linear = |coefficients: Vec<i64>, initial: Vec<i64>| Sequence::Linear {
+            coefficients,
+            initial,
+        };
+
+        let errors = input(linear(vec![1, 1], vec![0]), 0, 0, ArithmeticMode::Fixed)
+            .validate()
+            .unwrap_err();
+        let mut fields: Vec<_> = errors
+            .errors()
+            .keys()
+            .map(|field| field.to_string())
+            .collect();
+        fields.sort();
+        assert_eq!(fields, ["count", "sequence.initial"]);
+
+        let too_far = input(
+            Sequence::Catalan,
+            MAX_SEQUENCE_DIRECT_N,
+            2,
+            ArithmeticMode::Fixed,
+        );
+        assert!(too_far.validate().is_err());
+        let too_far = input(
+            Sequence::Lucas,
+            MAX_SEQUENCE_BIG_N + 1,
+            1,
+            ArithmeticMode::BigInt,
+        );
+        assert!(too_far.validate().is_err());
+        let huge = input(Sequence::Lucas, u64::MAX - 1, 2, ArithmeticMode::Fixed);
+        assert!(huge.validate().is_ok());
+
+        // 100000! alone has 456574 digits
+        let too_long = |sequence, n, count| input(sequence, n, count, ArithmeticMode::BigInt);
+        assert!(too_long(Sequence::Factorial, 99_000, 18).validate().is_ok());
+        let errors = too_long(Sequence::Factorial, 99_000, 20)
+            .validate()
+            .unwrap_err();
+        assert!(errors.errors().contains_key("count"));
+        assert!(too_long(Sequence::Catalan, 90_000, 200).validate().is_err());
+        assert!(too_long(Sequence::Fibonacci, 900_000, 100)
+            .validate()
+            .is_err());
+        let wide = Sequence::Linear {
+            coefficients: vec![i64::MAX; MAX_SEQUENCE_ORDER as usize],
+            initial: vec![i64::MAX; MAX_SEQUENCE_ORDER as usize],
+        };
+        let errors = too_long(wide, 500_000, 1).validate().unwrap_err();
+        assert!(errors.errors().contains_key("count"));
+    }
+
+    #[test]
+    fn test_term_digits_are_upper_bounds() {
+        for sequence in [
+            Sequence::Linear {
+                coefficients: vec![2, -1, 3],
+                initial: vec![5, -7, 1],
+            },
+            Sequence::Fibonacci,
+            Sequence::Lucas,
+            Sequence::Tribonacci,
+            Sequence::Catalan,
+            Sequence::Factorial,
+        ] {
+            let terms = evaluate(&Exact, &sequence, 0, 500).unwrap();
+            for (k, term) in terms.iter().enumerate() {
+                let digits = term_digits(&sequence, k as u64);
+                let len = term.trim_start_matches('-').len();
+                assert!(len <= digits, "{sequence:?} {k}");
+                if !matches!(sequence, Sequence::Linear { .. }) {
+                    assert!(digits <= len + 2, "{sequence:?} {k}");
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sequence_direct_without_big_ints() {
+        let result = sequence(&input(
+            Sequence::Factorial,
+            MAX_SEQUENCE_DIRECT_N - 1,
+            2,
+            ArithmeticMode::Fixed,
+        ))
+        .await
+        .unwrap();
+        assert!(result.terms.is_empty());
+        assert!(result.overflowed);
+
+        let mut input = input(
+            Sequence::Catalan,
+            MAX_SEQUENCE_DIRECT_N - 1,
+            2,
+            ArithmeticMode::Modular,
+        );
+        input.modulus = Some(1_000_000_007);
+        let result = sequence(&input).await.unwrap();
+        let expected: Vec<String> = math::catalan(MAX_SEQUENCE_DIRECT_N - 1, 2)
+            .iter()
+            .map(|term| (term % dashu_int::UBig::from(1_000_000_007u64)).to_string())
+            .collect();
+        assert_eq!(result.terms, expected);
+    }
+}
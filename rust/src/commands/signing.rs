@@ -0,0 +1,684 @@
+//! Digital signatures with Ed25519 and ECDSA P-256.
+//!
+//! Keys are passed in and out of Rust in one of three formats: the raw key bytes (the 32-byte
+//! seed or scalar, and the 32-byte Ed25519 or 65-byte uncompressed SEC1 public key), DER encoded
+//! PKCS#8 and SubjectPublicKeyInfo documents, or JSON Web Keys.
+
+use crate::commands::crypto::MAX_CRYPTO_DATA_LEN;
+use crate::commands::hash::{decode_input, encode_output, DataEncoding, OutputEncoding};
+use crate::error::{CommandError, FieldError};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+use ed25519_dalek::{Signer, Verifier};
+use eyre::Result;
+use rand::rngs::OsRng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Instant;
+use validator::Validate;
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub enum SignatureAlgorithm {
+    #[serde(rename = "ed25519")]
+    Ed25519,
+    /// ECDSA over P-256 with SHA-256 and deterministic nonces (RFC 6979)
+    #[serde(rename = "ecdsa_p256")]
+    EcdsaP256,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub enum KeyFormat {
+    /// The bare key bytes
+    #[serde(rename = "raw")]
+    Raw,
+    /// PKCS#8 for private keys and SubjectPublicKeyInfo for public keys, DER encoded
+    #[default]
+    #[serde(rename = "pkcs8")]
+    Pkcs8,
+    /// JSON Web Key, always passed as JSON text
+    #[serde(rename = "jwk")]
+    Jwk,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum KeyKind {
+    Private,
+    Public,
+}
+
+enum PrivateKey {
+    Ed25519(ed25519_dalek::SigningKey),
+    P256(p256::ecdsa::SigningKey),
+}
+
+enum PublicKey {
+    Ed25519(ed25519_dalek::VerifyingKey),
+    P256(p256::ecdsa::VerifyingKey),
+}
+
+impl PrivateKey {
+    fn generate(algorithm: SignatureAlgorithm) -> Self {
+        match algorithm {
+            SignatureAlgorithm::Ed25519 => {
+                Self::Ed25519(ed25519_dalek::SigningKey::generate(&mut OsRng))
+            }
+            SignatureAlgorithm::EcdsaP256 => {
+                Self::P256(p256::ecdsa::SigningKey::random(&mut OsRng))
+            }
+        }
+    }
+
+    fn public_key(&self) -> PublicKey {
+        match self {
+            Self::Ed25519(key) => PublicKey::Ed25519(key.verifying_key()),
+            Self::P256(key) => PublicKey::P256(*key.verifying_key()),
+        }
+    }
+
+    fn sign(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Ed25519(key) => key.sign(data).to_vec(),
+            Self::P256(key) => {
+                let signature: p256::ecdsa::Signature = key.sign(data);
+                signature.to_vec()
+            }
+        }
+    }
+
+    fn decode(
+        field: &str,
+        algorithm: SignatureAlgorithm,
+        format: KeyFormat,
+        key: &[u8],
+    ) -> Result<Self, CommandError> {
+        let invalid = || key_error(field, format!("not a valid {algorithm:?} {format:?} key"));
+        match (algorithm, format) {
+            (SignatureAlgorithm::Ed25519, KeyFormat::Raw) => {
+                let seed = key.try_into().map_err(|_| invalid())?;
+                Ok(Self::Ed25519(ed25519_dalek::SigningKey::from_bytes(seed)))
+            }
+            (SignatureAlgorithm::Ed25519, KeyFormat::Pkcs8) => {
+                let key = ed25519_dalek::SigningKey::from_pkcs8_der(key).map_err(|_| invalid())?;
+                Ok(Self::Ed25519(key))
+            }
+            (SignatureAlgorithm::EcdsaP256, KeyFormat::Raw) => {
+                let key = p256::ecdsa::SigningKey::from_slice(key).map_err(|_| invalid())?;
+                Ok(Self::P256(key))
+            }
+            (SignatureAlgorithm::EcdsaP256, KeyFormat::Pkcs8) => {
+                let key = p256::ecdsa::SigningKey::from_pkcs8_der(key).map_err(|_| invalid())?;
+                Ok(Self::P256(key))
+            }
+            (_, KeyFormat::Jwk) => {
+                let jwk = Jwk::parse(field, algorithm, key)?;
+                let d = jwk.member(field, "d")?;
+                Self::decode(field, algorithm, KeyFormat::Raw, &d)
+            }
+        }
+    }
+
+    fn encode(&self, format: KeyFormat) -> Vec<u8> {
+        match (self, format) {
+            (Self::Ed25519(key), KeyFormat::Raw) => key.to_bytes().to_vec(),
+            (Self::Ed25519(key), KeyFormat::Pkcs8) => key
+                .to_pkcs8_der()
+                .expect("Ed25519 keys are encodable")
+                .as_bytes()
+                .to_vec(),
+            (Self::P256(key), KeyFormat::Raw) => key.to_bytes().to_vec(),
+            (Self::P256(key), KeyFormat::Pkcs8) => key
+                .to_pkcs8_der()
+                .expect("P-256 keys are encodable")
+                .as_bytes()
+                .to_vec(),
+            (_, KeyFormat::Jwk) => {
+                let mut jwk = self.public_key().jwk();
+                jwk["d"] = URL_SAFE_NO_PAD.encode(self.encode(KeyFormat::Raw)).into();
+                jwk.to_string().into_bytes()
+            }
+        }
+    }
+}
+
+impl PublicKey {
+    fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        match self {
+            Self::Ed25519(key) => ed25519_dalek::Signature::from_slice(signature)
+                .is_ok_and(|signature| key.verify(data, &signature).is_ok()),
+            Self::P256(key) => {
+                // Both the fixed size r || s form and ASN.1 DER are accepted
+                let signature = p256::ecdsa::Signature::from_slice(signature)
+                    .or_else(|_| p256::ecdsa::Signature::from_der(signature));
+                signature.is_ok_and(|signature| key.verify(data, &signature).is_ok())
+            }
+        }
+    }
+
+    fn decode(
+        field: &str,
+        algorithm: SignatureAlgorithm,
+        format: KeyFormat,
+        key: &[u8],
+    ) -> Result<Self, CommandError> {
+        let invalid = || key_error(field, format!("not a valid {algorithm:?} {format:?} key"));
+        match (algorithm, format) {
+            (SignatureAlgorithm::Ed25519, KeyFormat::Raw) => {
+                let key = key.try_into().map_err(|_| invalid())?;
+                let key = ed25519_dalek::VerifyingKey::from_bytes(key).map_err(|_| invalid())?;
+                Ok(Self::Ed25519(key))
+            }
+            (SignatureAlgorithm::Ed25519, KeyFormat::Pkcs8) => {
+                let key =
+                    ed25519_dalek::VerifyingKey::from_public_key_der(key).map_err(|_| invalid())?;
+                Ok(Self::Ed25519(key))
+            }
+            (SignatureAlgorithm::EcdsaP256, KeyFormat::Raw) => {
+                let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(key).map_err(|_| invalid())?;
+                Ok(Self::P256(key))
+            }
+            (SignatureAlgorithm::EcdsaP256, KeyFormat::Pkcs8) => {
+                let key =
+                    p256::ecdsa::VerifyingKey::from_public_key_der(key).map_err(|_| invalid())?;
+                Ok(Self::P256(key))
+            }
+            (SignatureAlgorithm::Ed25519, KeyFormat::Jwk) => {
+                let x = Jwk::parse(field, algorithm, key)?.member(field, "x")?;
+                Self::decode(field, algorithm, KeyFormat::Raw, &x)
+            }
+            (SignatureAlgorithm::EcdsaP256, KeyFormat::Jwk) => {
+                let jwk = Jwk::parse(field, algorithm, key)?;
+                let point = [vec![0x04], jwk.member(field, "x")?, jwk.member(field, "y")?].concat();
+                Self::decode(field, algorithm, KeyFormat::Raw, &point)
+            }
+        }
+    }
+
+    fn encode(&self, format: KeyFormat) -> Vec<u8> {
+        match (self, format) {
+            (Self::Ed25519(key), KeyFormat::Raw) => key.to_bytes().to_vec(),
+            (Self::Ed25519(key), KeyFormat::Pkcs8) => key
+                .to_public_key_der()
+                .expect("Ed25519 keys are encodable")
+                .into_vec(),
+            (Self::P256(key), KeyFormat::Raw) => key.to_encoded_point(false).as_bytes().to_vec(),
+            (Self::P256(key), KeyFormat::Pkcs8) => key
+                .to_public_key_der()
+                .expect("P-256 keys are encodable")
+                .into_vec(),
+            (_, KeyFormat::Jwk) => self.jwk().to_string().into_bytes(),
+        }
+    }
+
+    fn jwk(&self) -> Value {
+        match self {
+            Self::Ed25519(key) => json!({
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "x": URL_SAFE_NO_PAD.encode(key.to_bytes()),
+            }),
+            Self::P256(key) => {
+                let point = key.to_encoded_point(false);
+                json!({
+                    "kty": "EC",
+                    "crv": "P-256",
+                    "x": URL_SAFE_NO_PAD.encode(point.x().expect("the point is uncompressed")),
+                    "y": URL_SAFE_NO_PAD.encode(point.y().expect("the point is uncompressed")),
+                })
+            }
+        }
+    }
+}
+
+/// A JSON Web Key checked to be of the expected key type and curve.
+struct Jwk(serde_json::Map<String, Value>);
+
+impl Jwk {
+    fn parse(field: &str, algorithm: SignatureAlgorithm, key: &[u8]) -> Result<Self, CommandError> {
+        let Ok(Value::Object(jwk)) = serde_json::from_slice(key) else {
+            return Err(key_error(field, "not a JSON Web Key object"));
+        };
+        let (kty, crv) = match algorithm {
+            SignatureAlgorithm::Ed25519 => ("OKP", "Ed25519"),
+            SignatureAlgorithm::EcdsaP256 => ("EC", "P-256"),
+        };
+        if jwk.get("kty").and_then(Value::as_str) != Some(kty)
+            || jwk.get("crv").and_then(Value::as_str) != Some(crv)
+        {
+            return Err(key_error(field, format!("expected a {kty} key on {crv}")));
+        }
+        Ok(Self(jwk))
+    }
+
+    fn member(&self, field: &str, name: &str) -> Result<Vec<u8>, CommandError> {
+        self.0
+            .get(name)
+            .and_then(Value::as_str)
+            .and_then(|value| URL_SAFE_NO_PAD.decode(value).ok())
+            .ok_or_else(|| key_error(field, format!("missing or invalid \"{name}\" member")))
+    }
+}
+
+fn key_error(field: &str, msg: impl Into<String>) -> CommandError {
+    CommandError::invalid_params(vec![FieldError {
+        field: field.to_string(),
+        code: "format".to_string(),
+        msg: msg.into(),
+    }])
+}
+
+/// Decodes a key parameter, JWKs are JSON text unless an encoding is given.
+fn decode_key(
+    field: &str,
+    key: &str,
+    format: KeyFormat,
+    encoding: Option<DataEncoding>,
+) -> Result<Vec<u8>, CommandError> {
+    let encoding = encoding.unwrap_or(match format {
+        KeyFormat::Jwk => DataEncoding::Utf8,
+        KeyFormat::Raw | KeyFormat::Pkcs8 => DataEncoding::Base64,
+    });
+    Ok(decode_input(field, key, encoding)?.to_vec())
+}
+
+/// Encodes an exported key, JWKs are always returned as JSON text.
+fn encode_key(
+    key: Vec<u8>,
+    format: KeyFormat,
+    encoding: OutputEncoding,
+) -> (String, Option<Vec<u8>>) {
+    match format {
+        KeyFormat::Jwk => (String::from_utf8(key).expect("JWKs are JSON text"), None),
+        KeyFormat::Raw | KeyFormat::Pkcs8 => encode_output(key, encoding),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct GenerateKeypairInput {
+    pub algorithm: SignatureAlgorithm,
+    /// `pkcs8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub format: Option<KeyFormat>,
+    /// How binary keys are returned, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output_encoding: Option<OutputEncoding>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct KeyPairResult {
+    /// Encoded private key, only set when a private key was generated or given
+    pub private_key: Option<String>,
+    /// Private key bytes, only set with the `bytes` encoding
+    pub private_key_bytes: Option<Vec<u8>>,
+    /// Encoded public key, empty with the `bytes` encoding
+    pub public_key: String,
+    /// Public key bytes, only set with the `bytes` encoding
+    pub public_key_bytes: Option<Vec<u8>>,
+    pub algorithm: SignatureAlgorithm,
+    pub format: KeyFormat,
+    pub output_encoding: OutputEncoding,
+    pub computation_time_ms: u64,
+}
+
+impl KeyPairResult {
+    fn new(
+        algorithm: SignatureAlgorithm,
+        private_key: Option<&PrivateKey>,
+        public_key: &PublicKey,
+        format: KeyFormat,
+        output_encoding: OutputEncoding,
+        start: Instant,
+    ) -> Self {
+        let (private_key, private_key_bytes) = match private_key {
+            Some(key) => {
+                let (key, bytes) = encode_key(key.encode(format), format, output_encoding);
+                (Some(key), bytes)
+            }
+            None => (None, None),
+        };
+        let (public_key, public_key_bytes) =
+            encode_key(public_key.encode(format), format, output_encoding);
+
+        Self {
+            private_key,
+            private_key_bytes,
+            public_key,
+            public_key_bytes,
+            algorithm,
+            format,
+            output_encoding,
+            computation_time_ms: start.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+pub async fn generate_keypair(input: &GenerateKeypairInput) -> Result<KeyPairResult> {
+    let start = Instant::now();
+    let key = PrivateKey::generate(input.algorithm);
+
+    Ok(KeyPairResult::new(
+        input.algorithm,
+        Some(&key),
+        &key.public_key(),
+        input.format.unwrap_or_default(),
+        input.output_encoding.unwrap_or(OutputEncoding::Base64),
+        start,
+    ))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct ConvertKeyInput {
+    pub algorithm: SignatureAlgorithm,
+    pub key: String,
+    /// Whether `key` is a private or a public key, the public key is derived from private ones
+    pub kind: KeyKind,
+    /// Format of `key`, `pkcs8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub format: Option<KeyFormat>,
+    /// How `key` is decoded, `base64` by default and `utf8` for JWKs
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub key_encoding: Option<DataEncoding>,
+    /// Format the key is exported to
+    pub to_format: KeyFormat,
+    /// How binary keys are returned, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output_encoding: Option<OutputEncoding>,
+}
+
+pub async fn convert_key(input: &ConvertKeyInput) -> Result<KeyPairResult> {
+    let start = Instant::now();
+    let format = input.format.unwrap_or_default();
+    let key = decode_key("key", &input.key, format, input.key_encoding)?;
+
+    let (private_key, public_key) = match input.kind {
+        KeyKind::Private => {
+            let key = PrivateKey::decode("key", input.algorithm, format, &key)?;
+            let public_key = key.public_key();
+            (Some(key), public_key)
+        }
+        KeyKind::Public => (
+            None,
+            PublicKey::decode("key", input.algorithm, format, &key)?,
+        ),
+    };
+
+    Ok(KeyPairResult::new(
+        input.algorithm,
+        private_key.as_ref(),
+        &public_key,
+        input.to_format,
+        input.output_encoding.unwrap_or(OutputEncoding::Base64),
+        start,
+    ))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SignInput {
+    pub algorithm: SignatureAlgorithm,
+    #[validate(length(max = MAX_CRYPTO_DATA_LEN))]
+    pub data: String,
+    /// How `data` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub encoding: Option<DataEncoding>,
+    pub private_key: String,
+    /// Format of `private_key`, `pkcs8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub key_format: Option<KeyFormat>,
+    /// How `private_key` is decoded, `base64` by default and `utf8` for JWKs
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub key_encoding: Option<DataEncoding>,
+    /// How the signature is returned, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output_encoding: Option<OutputEncoding>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SignResult {
+    /// Encoded signature, 64 bytes for both algorithms (r || s for ECDSA)
+    pub signature: String,
+    /// Signature bytes, only set with the `bytes` encoding
+    pub bytes: Option<Vec<u8>>,
+    pub algorithm: SignatureAlgorithm,
+    pub output_encoding: OutputEncoding,
+    pub computation_time_ms: u64,
+}
+
+pub async fn sign(input: &SignInput) -> Result<SignResult> {
+    let start = Instant::now();
+    let output_encoding = input.output_encoding.unwrap_or(OutputEncoding::Base64);
+    let format = input.key_format.unwrap_or_default();
+
+    let data = decode_input("data", &input.data, input.encoding.unwrap_or_default())?;
+    let key = decode_key(
+        "private_key",
+        &input.private_key,
+        format,
+        input.key_encoding,
+    )?;
+    let key = PrivateKey::decode("private_key", input.algorithm, format, &key)?;
+    let (signature, bytes) = encode_output(key.sign(&data), output_encoding);
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(SignResult {
+        signature,
+        bytes,
+        algorithm: input.algorithm,
+        output_encoding,
+        computation_time_ms,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct VerifyInput {
+    pub algorithm: SignatureAlgorithm,
+    #[validate(length(max = MAX_CRYPTO_DATA_LEN))]
+    pub data: String,
+    /// How `data` is decoded, `utf8` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub encoding: Option<DataEncoding>,
+    pub public_key: String,
+    /// Format of `public_key`, `pkcs8` (SubjectPublicKeyInfo) by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub key_format: Option<KeyFormat>,
+    /// How `public_key` is decoded, `base64` by default and `utf8` for JWKs
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub key_encoding: Option<DataEncoding>,
+    /// ECDSA signatures may be either r || s or DER encoded
+    pub signature: String,
+    /// How `signature` is decoded, `base64` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub signature_encoding: Option<DataEncoding>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct VerifyResult {
+    /// Whether the signature is valid, false for malformed signatures as well
+    pub valid: bool,
+    pub algorithm: SignatureAlgorithm,
+    pub computation_time_ms: u64,
+}
+
+pub async fn verify(input: &VerifyInput) -> Result<VerifyResult> {
+    let start = Instant::now();
+    let format = input.key_format.unwrap_or_default();
+
+    let data = decode_input("data", &input.data, input.encoding.unwrap_or_default())?;
+    let key = decode_key("public_key", &input.public_key, format, input.key_encoding)?;
+    let key = PublicKey::decode("public_key", input.algorithm, format, &key)?;
+    let signature_encoding = input.signature_encoding.unwrap_or(DataEncoding::Base64);
+    let signature = decode_input("signature", &input.signature, signature_encoding)?;
+    let valid = key.verify(&data, &signature);
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(VerifyResult {
+        valid,
+        algorithm: input.algorithm,
+        computation_time_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign_input(
+        algorithm: SignatureAlgorithm,
+        private_key: String,
+        format: KeyFormat,
+    ) -> SignInput {
+        SignInput {
+            algorithm,
+            data: "payload".to_string(),
+            encoding: None,
+            private_key,
+            key_format: Some(format),
+            key_encoding: None,
+            output_encoding: None,
+        }
+    }
+
+    fn verify_input(
+        algorithm: SignatureAlgorithm,
+        public_key: String,
+        format: KeyFormat,
+        signature: String,
+    ) -> VerifyInput {
+        VerifyInput {
+            algorithm,
+            data: "payload".to_string(),
+            encoding: None,
+            public_key,
+            key_format: Some(format),
+            key_encoding: None,
+            signature,
+            signature_encoding: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_and_verify() {
+        for algorithm in [SignatureAlgorithm::Ed25519, SignatureAlgorithm::EcdsaP256] {
+            for format in [KeyFormat::Raw, KeyFormat::Pkcs8, KeyFormat::Jwk] {
+                let keypair = generate_keypair(&GenerateKeypairInput {
+                    algorithm,
+                    format: Some(format),
+                    output_encoding: None,
+                })
+                .await
+                .unwrap();
+
+                let private_key = keypair.private_key.unwrap();
+                let result = sign(&sign_input(algorithm, private_key, format))
+                    .await
+                    .unwrap();
+                let mut input =
+                    verify_input(algorithm, keypair.public_key, format, result.signature);
+                assert!(
+                    verify(&input).await.unwrap().valid,
+                    "{algorithm:?} {format:?}"
+                );
+
+                input.data = "tampered".to_string();
+                assert!(
+                    !verify(&input).await.unwrap().valid,
+                    "{algorithm:?} {format:?}"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ed25519_vector() {
+        // RFC 8032, test 1
+        let private_key = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+        let mut input = sign_input(
+            SignatureAlgorithm::Ed25519,
+            private_key.to_string(),
+            KeyFormat::Raw,
+        );
+        input.data = String::new();
+        input.key_encoding = Some(DataEncoding::Hex);
+        input.output_encoding = Some(OutputEncoding::Hex);
+        let result = sign(&input).await.unwrap();
+        assert!(result
+            .signature
+            .starts_with("e5564300c360ac729086e2cc806e828a"));
+
+        let converted = convert_key(&ConvertKeyInput {
+            algorithm: SignatureAlgorithm::Ed25519,
+            key: private_key.to_string(),
+            kind: KeyKind::Private,
+            format: Some(KeyFormat::Raw),
+            key_encoding: Some(DataEncoding::Hex),
+            to_format: KeyFormat::Jwk,
+            output_encoding: None,
+        })
+        .await
+        .unwrap();
+        let jwk: Value = serde_json::from_str(&converted.public_key).unwrap();
+        assert_eq!(jwk["x"], "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo");
+    }
+
+    #[tokio::test]
+    async fn test_key_errors() {
+        let err = convert_key(&ConvertKeyInput {
+            algorithm: SignatureAlgorithm::EcdsaP256,
+            key: r#"{"kty": "OKP", "crv": "Ed25519", "x": "AA"}"#.to_string(),
+            kind: KeyKind::Public,
+            format: Some(KeyFormat::Jwk),
+            key_encoding: None,
+            to_format: KeyFormat::Raw,
+            output_encoding: None,
+        })
+        .await
+        .unwrap_err();
+        let field_errors = crate::error::field_errors(&err).unwrap();
+        assert_eq!(field_errors[0].field, "key");
+        assert_eq!(field_errors[0].code, "format");
+    }
+
+    #[cfg(feature = "ts-rs")]
+    #[test]
+    fn test_enum_literals_match_typescript() {
+        crate::cmd::verify_enum_literals::<KeyFormat>().unwrap();
+    }
+}
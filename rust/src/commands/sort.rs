@@ -1,21 +1,102 @@
 use eyre::Result;
 use rayon::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::time::Instant;
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
 
 #[cfg(feature = "ts-rs")]
 use ts_rs::TS;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Largest `numbers` array accepted by the `sort_numbers` command
+pub const MAX_SORT_LEN: u64 = 10_000_000;
+/// Largest `records` array accepted by the `sort_records` command
+pub const MAX_SORT_RECORDS: u64 = 1_000_000;
+/// Largest number of keys records are sorted by
+pub const MAX_SORT_KEYS: u64 = 16;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
 pub struct SortNumbersInput {
+    #[schemars(length(max = MAX_SORT_LEN))]
     pub numbers: Vec<i32>,
     #[serde(default = "default_algorithm")]
     pub algorithm: SortAlgorithm,
+    /// `asc` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub direction: Option<SortDirection>,
+    /// Keep equal numbers in input order, which only shows in `argsort` indices
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub stable: Option<bool>,
+    /// Return the sorting permutation in `indices` instead of the numbers
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub argsort: Option<bool>,
+    /// Collapse equal numbers, returning how often each occurred in `counts`
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub dedup: Option<bool>,
+    /// Only keep the k largest numbers, selected before sorting them in `direction`
+    #[serde(default)]
+    #[schemars(range(min = 1))]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub top_k: Option<u32>,
+    /// Only keep the k smallest numbers, selected before sorting them in `direction`
+    #[serde(default)]
+    #[schemars(range(min = 1))]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub bottom_k: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Validate for SortNumbersInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let unsupported = |errors: &mut ValidationErrors, field: &'static str, msg: &str| {
+            errors.add(
+                field,
+                ValidationError::new("unsupported").with_message(msg.to_string().into()),
+            );
+        };
+
+        if self.numbers.len() as u64 > MAX_SORT_LEN {
+            let mut err = ValidationError::new("length");
+            err.add_param("max".into(), &MAX_SORT_LEN);
+            errors.add("numbers", err);
+        }
+        for (field, k) in [("top_k", self.top_k), ("bottom_k", self.bottom_k)] {
+            if k == Some(0) {
+                let mut err = ValidationError::new("range");
+                err.add_param("min".into(), &1);
+                errors.add(field, err);
+            }
+        }
+        if self.top_k.is_some() && self.bottom_k.is_some() {
+            unsupported(&mut errors, "bottom_k", "can't be combined with top_k");
+        }
+        if self.dedup == Some(true) {
+            if self.argsort == Some(true) {
+                unsupported(&mut errors, "dedup", "can't be combined with argsort");
+            } else if self.top_k.is_some() || self.bottom_k.is_some() {
+                unsupported(&mut errors, "dedup", "can't be combined with a selection");
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
 pub enum SortAlgorithm {
@@ -33,7 +114,12 @@ fn default_algorithm() -> SortAlgorithm {
 #[cfg_attr(feature = "ts-rs", derive(TS))]
 #[cfg_attr(feature = "ts-rs", ts(export))]
 pub struct SortNumbersResult {
+    /// Sorted numbers, empty with `argsort`
     pub sorted_numbers: Vec<i32>,
+    /// Indices into `numbers` in sorted order, only set with `argsort`
+    pub indices: Option<Vec<usize>>,
+    /// Occurrences of each of `sorted_numbers`, only set with `dedup`
+    pub counts: Option<Vec<u32>>,
     pub algorithm: SortAlgorithm,
     pub input_length: usize,
     pub computation_time_ms: u64,
@@ -41,38 +127,650 @@ pub struct SortNumbersResult {
 
 pub async fn sort_numbers(input: &SortNumbersInput) -> Result<SortNumbersResult> {
     let start = Instant::now();
-    let mut numbers = input.numbers.clone();
-    
-    match input.algorithm {
-        SortAlgorithm::Parallel => {
-            numbers.par_sort_unstable();
+    let numbers = &input.numbers;
+    let stable = input.stable.unwrap_or(false);
+    let descending = input.direction == Some(SortDirection::Desc);
+    // The k largest are the k first in descending order, whatever order they are returned in
+    let selection = match (input.top_k, input.bottom_k) {
+        (Some(k), _) => Some((k as usize, true)),
+        (None, Some(k)) => Some((k as usize, false)),
+        (None, None) => None,
+    };
+
+    let (sorted_numbers, indices, counts) = if input.argsort == Some(true) {
+        let mut indices: Vec<usize> = (0..numbers.len()).collect();
+        // Ties are broken by index when stable, so the selection can't reorder them either
+        let by_value = |&a: &usize, &b: &usize, descending: bool| {
+            let ordering = in_direction(numbers[a].cmp(&numbers[b]), descending);
+            if stable {
+                ordering.then(a.cmp(&b))
+            } else {
+                ordering
+            }
+        };
+        if let Some((k, largest)) = selection {
+            select_first(&mut indices, k, |a, b| by_value(a, b, largest));
         }
-        SortAlgorithm::Sequential => {
-            numbers.sort_unstable();
+        sort_by_stability(&mut indices, &input.algorithm, stable, |a, b| {
+            by_value(a, b, descending)
+        });
+        (Vec::new(), Some(indices), None)
+    } else {
+        let mut sorted = numbers.clone();
+        if let Some((k, largest)) = selection {
+            select_first(&mut sorted, k, |a, b| in_direction(a.cmp(b), largest));
         }
-    }
-    
+        sort_by_stability(&mut sorted, &input.algorithm, stable, |a, b| {
+            in_direction(a.cmp(b), descending)
+        });
+
+        if input.dedup == Some(true) {
+            let (mut unique, mut counts) = (Vec::new(), Vec::new());
+            for number in sorted {
+                match (unique.last(), counts.last_mut()) {
+                    (Some(&last), Some(count)) if last == number => *count += 1,
+                    _ => {
+                        unique.push(number);
+                        counts.push(1);
+                    }
+                }
+            }
+            (unique, None, Some(counts))
+        } else {
+            (sorted, None, None)
+        }
+    };
+
     let computation_time_ms = start.elapsed().as_millis() as u64;
     
     Ok(SortNumbersResult {
-        sorted_numbers: numbers,
+        sorted_numbers,
+        indices,
+        counts,
         algorithm: input.algorithm.clone(),
         input_length: input.numbers.len(),
         computation_time_ms,
     })
 }
 
+fn in_direction(ordering: Ordering, descending: bool) -> Ordering {
+    if descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+/// Keeps the `k` first items in `compare` order, in no particular order, in linear time.
+fn select_first<T>(items: &mut Vec<T>, k: usize, compare: impl FnMut(&T, &T) -> Ordering) {
+    if k < items.len() {
+        items.select_nth_unstable_by(k, compare);
+        items.truncate(k);
+    }
+}
+
+fn sort_by_stability<T: Send>(
+    items: &mut [T],
+    algorithm: &SortAlgorithm,
+    stable: bool,
+    compare: impl Fn(&T, &T) -> Ordering + Sync,
+) {
+    match (algorithm, stable) {
+        (SortAlgorithm::Parallel, true) => items.par_sort_by(compare),
+        (SortAlgorithm::Sequential, true) => items.sort_by(compare),
+        (_, false) => sort_by(items, algorithm, compare),
+    }
+}
+
+/// Values of one type to sort with `sort_values`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SortValues {
+    I64 {
+        #[cfg_attr(feature = "ts-rs", ts(type = "Array<number>"))]
+        items: Vec<i64>,
+    },
+    U64 {
+        #[cfg_attr(feature = "ts-rs", ts(type = "Array<number>"))]
+        items: Vec<u64>,
+    },
+    /// Ordered by IEEE 754 total order, so -0 sorts before +0. NaN has no JSON form and is
+    /// carried as `null`, which is also what `JSON.stringify` turns NaN and infinities into.
+    F64 {
+        items: Vec<Option<f64>>,
+    },
+    String {
+        items: Vec<String>,
+    },
+}
+
+impl SortValues {
+    fn len(&self) -> usize {
+        match self {
+            SortValues::I64 { items } => items.len(),
+            SortValues::U64 { items } => items.len(),
+            SortValues::F64 { items } => items.len(),
+            SortValues::String { items } => items.len(),
+        }
+    }
+}
+
+/// Where NaN (`null`) sorts among floats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum NanPlacement {
+    First,
+    #[default]
+    Last,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum StringOrder {
+    /// Byte order of the UTF-8 encoding, which is also code point order
+    #[default]
+    Bytes,
+    /// Order of the lowercased strings, ties broken by byte order
+    CaseInsensitive,
+    /// Runs of digits compare by their numeric value, so "file9" sorts before "file10"
+    Natural,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SortValuesInput {
+    pub values: SortValues,
+    #[serde(default = "default_algorithm")]
+    pub algorithm: SortAlgorithm,
+    /// Floats only, `last` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub nan_placement: Option<NanPlacement>,
+    /// Strings only, `bytes` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub string_order: Option<StringOrder>,
+}
+
+// Implemented by hand since the options that apply depend on the type of the values
+impl Validate for SortValuesInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        let unsupported = |errors: &mut ValidationErrors, field: &'static str, msg: &str| {
+            errors.add(
+                field,
+                ValidationError::new("unsupported").with_message(msg.to_string().into()),
+            );
+        };
+
+        if self.values.len() as u64 > MAX_SORT_LEN {
+            let mut err = ValidationError::new("length");
+            err.add_param("max".into(), &MAX_SORT_LEN);
+            errors.add("values.items", err);
+        }
+        if self.nan_placement.is_some() && !matches!(self.values, SortValues::F64 { .. }) {
+            unsupported(&mut errors, "nan_placement", "only applies to f64 values");
+        }
+        if self.string_order.is_some() && !matches!(self.values, SortValues::String { .. }) {
+            unsupported(&mut errors, "string_order", "only applies to string values");
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SortValuesResult {
+    /// The sorted values, of the same type as the input
+    pub values: SortValues,
+    pub algorithm: SortAlgorithm,
+    pub input_length: usize,
+    pub computation_time_ms: u64,
+}
+
+pub async fn sort_values(input: &SortValuesInput) -> Result<SortValuesResult> {
+    let start = Instant::now();
+    let mut values = input.values.clone();
+    let algorithm = &input.algorithm;
+
+    match &mut values {
+        SortValues::I64 { items } => sort_by(items, algorithm, Ord::cmp),
+        SortValues::U64 { items } => sort_by(items, algorithm, Ord::cmp),
+        SortValues::F64 { items } => {
+            let nan_placement = input.nan_placement.unwrap_or_default();
+            sort_by(items, algorithm, |a, b| match (a, b) {
+                (Some(a), Some(b)) => a.total_cmp(b),
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) if nan_placement == NanPlacement::First => Ordering::Less,
+                (Some(_), None) if nan_placement == NanPlacement::First => Ordering::Greater,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+            })
+        }
+        SortValues::String { items } => {
+            let string_order = input.string_order.unwrap_or_default();
+            sort_by(items, algorithm, |a, b| string_cmp(a, b, string_order))
+        }
+    }
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(SortValuesResult {
+        values,
+        algorithm: input.algorithm.clone(),
+        input_length: input.values.len(),
+        computation_time_ms,
+    })
+}
+
+fn sort_by<T: Send>(
+    items: &mut [T],
+    algorithm: &SortAlgorithm,
+    compare: impl Fn(&T, &T) -> Ordering + Sync,
+) {
+    match algorithm {
+        SortAlgorithm::Parallel => items.par_sort_unstable_by(compare),
+        SortAlgorithm::Sequential => items.sort_unstable_by(compare),
+    }
+}
+
+fn string_cmp(a: &str, b: &str, order: StringOrder) -> Ordering {
+    match order {
+        StringOrder::Bytes => a.cmp(b),
+        StringOrder::CaseInsensitive => {
+            let lower_b = b.chars().flat_map(char::to_lowercase);
+            (a.chars().flat_map(char::to_lowercase))
+                .cmp(lower_b)
+                .then_with(|| a.cmp(b))
+        }
+        StringOrder::Natural => natural_cmp(a, b),
+    }
+}
+
+/// Compares digit runs by numeric value and everything else by code point, then by byte order.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut x, mut y) = (a.as_bytes(), b.as_bytes());
+    loop {
+        match (x.first(), y.first()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(c), Some(d)) if c.is_ascii_digit() && d.is_ascii_digit() => {
+                let (run_x, rest_x) = split_digits(x);
+                let (run_y, rest_y) = split_digits(y);
+                // Without leading zeros, a longer run is a larger number
+                let (value_x, value_y) = (trim_zeros(run_x), trim_zeros(run_y));
+                let ordering = value_x
+                    .len()
+                    .cmp(&value_y.len())
+                    .then_with(|| value_x.cmp(value_y));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                (x, y) = (rest_x, rest_y);
+            }
+            (Some(c), Some(d)) => {
+                // UTF-8 byte order is code point order, so bytes can be compared one at a time
+                if c != d {
+                    return c.cmp(d);
+                }
+                (x, y) = (&x[1..], &y[1..]);
+            }
+        }
+    }
+}
+
+fn split_digits(bytes: &[u8]) -> (&[u8], &[u8]) {
+    let len = bytes
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count();
+    bytes.split_at(len)
+}
+
+fn trim_zeros(digits: &[u8]) -> &[u8] {
+    let zeros = digits.iter().take_while(|&&digit| digit == b'0').count();
+    &digits[zeros..]
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Where null and missing keys sort, regardless of the direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum NullPlacement {
+    First,
+    #[default]
+    Last,
+}
+
+/// How the value at a key path is interpreted before comparing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum KeyCoercion {
+    /// Values keep their JSON type, and booleans sort before numbers, strings, then arrays
+    /// and objects
+    #[default]
+    Auto,
+    /// Numeric strings are parsed and booleans count as 0 and 1, anything else is null
+    Number,
+    /// Numbers and booleans are compared by their JSON text
+    String,
+    /// `"true"` and `"false"` are parsed and numbers are true unless 0, anything else is null
+    Boolean,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SortKey {
+    /// JSON pointer (RFC 6901) to the value in each record, such as `/address/city`
+    pub path: String,
+    /// `asc` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub direction: Option<SortDirection>,
+    /// `last` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub nulls: Option<NullPlacement>,
+    /// `auto` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub coerce: Option<KeyCoercion>,
+    /// How strings are compared, `bytes` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub string_order: Option<StringOrder>,
+}
+
+impl Validate for SortKey {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if !self.path.is_empty() && !self.path.starts_with('/') {
+            errors.add(
+                "path",
+                ValidationError::new("format")
+                    .with_message("a JSON pointer is empty or starts with `/`".into()),
+            );
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum RecordsOutput {
+    /// The records themselves, in sorted order
+    #[default]
+    Records,
+    /// Indices into the input, so that `records[indices[0]]` comes first
+    Indices,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SortRecordsInput {
+    #[cfg_attr(feature = "ts-rs", ts(type = "Array<any>"))]
+    pub records: Vec<Value>,
+    /// Keys in order of priority, later keys only break ties of the earlier ones
+    pub keys: Vec<SortKey>,
+    /// `records` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub output: Option<RecordsOutput>,
+    #[serde(default = "default_algorithm")]
+    pub algorithm: SortAlgorithm,
+}
+
+impl Validate for SortRecordsInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.records.len() as u64 > MAX_SORT_RECORDS {
+            let mut err = ValidationError::new("length");
+            err.add_param("max".into(), &MAX_SORT_RECORDS);
+            errors.add("records", err);
+        }
+        if self.keys.is_empty() || self.keys.len() as u64 > MAX_SORT_KEYS {
+            let mut err = ValidationError::new("length");
+            err.add_param("min".into(), &1);
+            err.add_param("max".into(), &MAX_SORT_KEYS);
+            errors.add("keys", err);
+        }
+
+        // Listed like derived nested errors, so that they are reported as `keys[i].path`
+        let keys: BTreeMap<_, _> = self
+            .keys
+            .iter()
+            .enumerate()
+            .filter_map(|(i, key)| key.validate().err().map(|err| (i, Box::new(err))))
+            .collect();
+        if !keys.is_empty() {
+            errors
+                .errors_mut()
+                .insert("keys".into(), ValidationErrorsKind::List(keys));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SortRecordsResult {
+    /// Sorted records, only set with the `records` output
+    #[cfg_attr(feature = "ts-rs", ts(type = "Array<any> | null"))]
+    pub records: Option<Vec<Value>>,
+    /// Sorting permutation, only set with the `indices` output
+    pub indices: Option<Vec<usize>>,
+    pub algorithm: SortAlgorithm,
+    pub input_length: usize,
+    pub computation_time_ms: u64,
+}
+
+/// Sorts records stably, so records with equal keys keep their relative order.
+pub async fn sort_records(input: &SortRecordsInput) -> Result<SortRecordsResult> {
+    let start = Instant::now();
+
+    // Keys are read once per record rather than on every comparison
+    let table: Vec<Vec<KeyValue>> = input
+        .records
+        .par_iter()
+        .map(|record| {
+            input
+                .keys
+                .iter()
+                .map(|key| KeyValue::read(record, key))
+                .collect()
+        })
+        .collect();
+    let compare = |&a: &usize, &b: &usize| {
+        input
+            .keys
+            .iter()
+            .zip(table[a].iter().zip(&table[b]))
+            .map(|(key, (a, b))| KeyValue::compare(a, b, key))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    };
+
+    let mut indices: Vec<usize> = (0..input.records.len()).collect();
+    match input.algorithm {
+        SortAlgorithm::Parallel => indices.par_sort_by(compare),
+        SortAlgorithm::Sequential => indices.sort_by(compare),
+    }
+
+    let (records, indices) = match input.output.unwrap_or_default() {
+        RecordsOutput::Records => {
+            let records = indices
+                .par_iter()
+                .map(|&i| input.records[i].clone())
+                .collect();
+            (Some(records), None)
+        }
+        RecordsOutput::Indices => (None, Some(indices)),
+    };
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(SortRecordsResult {
+        records,
+        indices,
+        algorithm: input.algorithm.clone(),
+        input_length: input.records.len(),
+        computation_time_ms,
+    })
+}
+
+/// Value of a sort key in one record, after coercion.
+#[derive(Debug, PartialEq)]
+pub(crate) enum KeyValue<'a> {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(Cow<'a, str>),
+    /// Arrays and objects, which are equal to each other
+    Composite,
+}
+
+impl<'a> KeyValue<'a> {
+    pub(crate) fn read(record: &'a Value, key: &SortKey) -> Self {
+        let Some(value) = record.pointer(&key.path) else {
+            return KeyValue::Null;
+        };
+        let number = |n: Option<f64>| {
+            n.filter(|n| !n.is_nan())
+                .map_or(KeyValue::Null, KeyValue::Number)
+        };
+
+        match (key.coerce.unwrap_or_default(), value) {
+            (_, Value::Null) => KeyValue::Null,
+            (KeyCoercion::Auto, Value::Bool(b)) => KeyValue::Bool(*b),
+            (KeyCoercion::Auto, Value::Number(n)) => number(n.as_f64()),
+            (KeyCoercion::Auto, Value::String(s)) => KeyValue::String(Cow::Borrowed(s)),
+            (KeyCoercion::Auto, _) => KeyValue::Composite,
+            (KeyCoercion::Number, Value::Number(n)) => number(n.as_f64()),
+            (KeyCoercion::Number, Value::String(s)) => number(s.trim().parse().ok()),
+            (KeyCoercion::Number, Value::Bool(b)) => KeyValue::Number(f64::from(u8::from(*b))),
+            (KeyCoercion::Number, _) => KeyValue::Null,
+            (KeyCoercion::String, Value::String(s)) => KeyValue::String(Cow::Borrowed(s)),
+            (KeyCoercion::String, value) => KeyValue::String(Cow::Owned(value.to_string())),
+            (KeyCoercion::Boolean, Value::Bool(b)) => KeyValue::Bool(*b),
+            (KeyCoercion::Boolean, Value::Number(n)) => KeyValue::Bool(n.as_f64() != Some(0.0)),
+            (KeyCoercion::Boolean, Value::String(s)) => match s.trim() {
+                "true" => KeyValue::Bool(true),
+                "false" => KeyValue::Bool(false),
+                _ => KeyValue::Null,
+            },
+            (KeyCoercion::Boolean, _) => KeyValue::Null,
+        }
+    }
+
+    /// Detaches the value from the record it was read from.
+    pub(crate) fn into_owned(self) -> KeyValue<'static> {
+        match self {
+            KeyValue::Null => KeyValue::Null,
+            KeyValue::Bool(b) => KeyValue::Bool(b),
+            KeyValue::Number(n) => KeyValue::Number(n),
+            KeyValue::String(s) => KeyValue::String(Cow::Owned(s.into_owned())),
+            KeyValue::Composite => KeyValue::Composite,
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            KeyValue::Null => 0,
+            KeyValue::Bool(_) => 1,
+            KeyValue::Number(_) => 2,
+            KeyValue::String(_) => 3,
+            KeyValue::Composite => 4,
+        }
+    }
+
+    pub(crate) fn compare(a: &Self, b: &Self, key: &SortKey) -> Ordering {
+        let nulls_first = key.nulls.unwrap_or_default() == NullPlacement::First;
+        let ordering = match (a, b) {
+            (KeyValue::Null, KeyValue::Null) => return Ordering::Equal,
+            (KeyValue::Null, _) if nulls_first => return Ordering::Less,
+            (KeyValue::Null, _) => return Ordering::Greater,
+            (_, KeyValue::Null) if nulls_first => return Ordering::Greater,
+            (_, KeyValue::Null) => return Ordering::Less,
+            (KeyValue::Bool(a), KeyValue::Bool(b)) => a.cmp(b),
+            (KeyValue::Number(a), KeyValue::Number(b)) => a.total_cmp(b),
+            (KeyValue::String(a), KeyValue::String(b)) => {
+                string_cmp(a, b, key.string_order.unwrap_or_default())
+            }
+            (a, b) => a.rank().cmp(&b.rank()),
+        };
+
+        match key.direction.unwrap_or_default() {
+            SortDirection::Asc => ordering,
+            SortDirection::Desc => ordering.reverse(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn numbers_input(numbers: Vec<i32>, algorithm: SortAlgorithm) -> SortNumbersInput {
+        SortNumbersInput {
+            numbers,
+            algorithm,
+            direction: None,
+            stable: None,
+            argsort: None,
+            dedup: None,
+            top_k: None,
+            bottom_k: None,
+        }
+    }
+
     #[tokio::test]
     async fn test_sort_numbers() {
-        let input = SortNumbersInput {
-            numbers: vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5],
-            algorithm: SortAlgorithm::Parallel,
-        };
-        
+        let input = numbers_input(
+            vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5],
+            SortAlgorithm::Parallel,
+        );
+
         let result = sort_numbers(&input).await.unwrap();
         assert_eq!(result.sorted_numbers, vec![1, 1, 2, 3, 3, 4, 5, 5, 5, 6, 9]);
         assert_eq!(result.input_length, 11);
@@ -80,13 +778,278 @@ mod tests {
 
     #[tokio::test]
     async fn test_sort_empty() {
-        let input = SortNumbersInput {
-            numbers: vec![],
-            algorithm: SortAlgorithm::Sequential,
-        };
-        
+        let input = numbers_input(vec![], SortAlgorithm::Sequential);
+
         let result = sort_numbers(&input).await.unwrap();
         assert_eq!(result.sorted_numbers, Vec::<i32>::new());
         assert_eq!(result.input_length, 0);
     }
+
+    #[tokio::test]
+    async fn test_sort_numbers_options() {
+        let numbers = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        for algorithm in [SortAlgorithm::Parallel, SortAlgorithm::Sequential] {
+            let mut input = numbers_input(numbers.clone(), algorithm);
+            input.direction = Some(SortDirection::Desc);
+            input.stable = Some(true);
+            input.argsort = Some(true);
+            let result = sort_numbers(&input).await.unwrap();
+            // The three 5s keep their input order
+            assert_eq!(result.indices.unwrap(), [5, 7, 4, 8, 10, 2, 0, 9, 6, 1, 3]);
+            assert!(result.sorted_numbers.is_empty());
+
+            input.top_k = Some(3);
+            let result = sort_numbers(&input).await.unwrap();
+            assert_eq!(result.indices.unwrap(), [5, 7, 4]);
+        }
+
+        let mut input = numbers_input(numbers.clone(), SortAlgorithm::Parallel);
+        input.dedup = Some(true);
+        let result = sort_numbers(&input).await.unwrap();
+        assert_eq!(result.sorted_numbers, [1, 2, 3, 4, 5, 6, 9]);
+        assert_eq!(result.counts.unwrap(), [2, 1, 2, 1, 3, 1, 1]);
+
+        // Selections are returned in `direction`, and k may exceed the input
+        let mut input = numbers_input(numbers.clone(), SortAlgorithm::Sequential);
+        input.top_k = Some(4);
+        assert_eq!(
+            sort_numbers(&input).await.unwrap().sorted_numbers,
+            [5, 5, 6, 9]
+        );
+        input.top_k = None;
+        input.bottom_k = Some(20);
+        input.direction = Some(SortDirection::Desc);
+        let result = sort_numbers(&input).await.unwrap();
+        assert_eq!(result.sorted_numbers, [9, 6, 5, 5, 5, 4, 3, 3, 2, 1, 1]);
+
+        let mut invalid = numbers_input(numbers, SortAlgorithm::Parallel);
+        invalid.top_k = Some(0);
+        invalid.bottom_k = Some(2);
+        invalid.dedup = Some(true);
+        invalid.argsort = Some(true);
+        let errors = invalid.validate().unwrap_err();
+        let mut fields: Vec<_> = errors.field_errors().into_keys().collect();
+        fields.sort();
+        assert_eq!(fields, ["bottom_k", "dedup", "top_k"]);
+    }
+
+    fn values_input(values: SortValues, algorithm: SortAlgorithm) -> SortValuesInput {
+        SortValuesInput {
+            values,
+            algorithm,
+            nan_placement: None,
+            string_order: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sort_values_numbers() {
+        let input = values_input(
+            SortValues::I64 {
+                items: vec![1_700_000_000_000, -5, i64::MAX, 0, i64::MIN],
+            },
+            SortAlgorithm::Parallel,
+        );
+        let result = sort_values(&input).await.unwrap();
+        let SortValues::I64 { items } = result.values else {
+            panic!("expected i64 values");
+        };
+        assert_eq!(items, [i64::MIN, -5, 0, 1_700_000_000_000, i64::MAX]);
+
+        let floats = vec![
+            Some(2.5),
+            None,
+            Some(0.0),
+            Some(-0.0),
+            Some(f64::NEG_INFINITY),
+            None,
+        ];
+        for (nan_placement, expected) in [
+            (
+                None,
+                [
+                    Some(f64::NEG_INFINITY),
+                    Some(-0.0),
+                    Some(0.0),
+                    Some(2.5),
+                    None,
+                    None,
+                ],
+            ),
+            (
+                Some(NanPlacement::First),
+                [
+                    None,
+                    None,
+                    Some(f64::NEG_INFINITY),
+                    Some(-0.0),
+                    Some(0.0),
+                    Some(2.5),
+                ],
+            ),
+        ] {
+            for algorithm in [SortAlgorithm::Parallel, SortAlgorithm::Sequential] {
+                let mut input = values_input(
+                    SortValues::F64 {
+                        items: floats.clone(),
+                    },
+                    algorithm,
+                );
+                input.nan_placement = nan_placement;
+                let SortValues::F64 { items } = sort_values(&input).await.unwrap().values else {
+                    panic!("expected f64 values");
+                };
+                // -0.0 == 0.0, so compare the bits to check the sign of zero
+                let bits = |items: &[Option<f64>]| {
+                    items
+                        .iter()
+                        .map(|v| v.map(f64::to_bits))
+                        .collect::<Vec<_>>()
+                };
+                assert_eq!(bits(&items), bits(&expected));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sort_values_strings() {
+        let items: Vec<String> = ["file10", "File2", "file9", "file02", "apple", "Banana"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        for (string_order, expected) in [
+            (
+                None,
+                ["Banana", "File2", "apple", "file02", "file10", "file9"],
+            ),
+            (
+                Some(StringOrder::CaseInsensitive),
+                ["apple", "Banana", "file02", "file10", "File2", "file9"],
+            ),
+            (
+                Some(StringOrder::Natural),
+                ["Banana", "File2", "apple", "file02", "file9", "file10"],
+            ),
+        ] {
+            let mut input = values_input(
+                SortValues::String {
+                    items: items.clone(),
+                },
+                SortAlgorithm::Sequential,
+            );
+            input.string_order = string_order;
+            let SortValues::String { items } = sort_values(&input).await.unwrap().values else {
+                panic!("expected strings");
+            };
+            assert_eq!(items, expected, "{string_order:?}");
+        }
+
+        let mut input = values_input(SortValues::U64 { items: vec![] }, SortAlgorithm::Parallel);
+        input.string_order = Some(StringOrder::Natural);
+        input.nan_placement = Some(NanPlacement::First);
+        let errors = input.validate().unwrap_err();
+        let mut fields: Vec<_> = errors.field_errors().into_keys().collect();
+        fields.sort();
+        assert_eq!(fields, ["nan_placement", "string_order"]);
+    }
+
+    fn key(path: &str) -> SortKey {
+        SortKey {
+            path: path.to_string(),
+            direction: None,
+            nulls: None,
+            coerce: None,
+            string_order: None,
+        }
+    }
+
+    fn records_input(records: Value, keys: Vec<SortKey>) -> SortRecordsInput {
+        SortRecordsInput {
+            records: serde_json::from_value(records).unwrap(),
+            keys,
+            output: Some(RecordsOutput::Indices),
+            algorithm: SortAlgorithm::Parallel,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sort_records_multiple_keys() {
+        let records = serde_json::json!([
+            { "name": "Eve", "address": { "city": "Oslo" }, "age": 31 },
+            { "name": "bob", "address": { "city": "Bergen" }, "age": 25 },
+            { "name": "Alice", "address": { "city": "Oslo" }, "age": 31 },
+            { "name": "Dan", "age": 40 },
+            { "name": "Carol", "address": { "city": "Bergen" }, "age": null },
+        ]);
+        let mut by_city = key("/address/city");
+        by_city.nulls = Some(NullPlacement::First);
+        let mut by_age = key("/age");
+        by_age.direction = Some(SortDirection::Desc);
+
+        // Missing and null keys go first or last whatever the direction
+        let input = records_input(records.clone(), vec![by_city, by_age, key("/name")]);
+        let result = sort_records(&input).await.unwrap();
+        assert_eq!(result.indices.unwrap(), [3, 1, 4, 2, 0]);
+        assert!(result.records.is_none());
+
+        let mut by_name = key("/name");
+        by_name.string_order = Some(StringOrder::CaseInsensitive);
+        let mut input = records_input(records, vec![by_name]);
+        input.output = None;
+        let result = sort_records(&input).await.unwrap();
+        let names: Vec<_> = result
+            .records
+            .unwrap()
+            .iter()
+            .map(|r| r["name"].clone())
+            .collect();
+        assert_eq!(names, ["Alice", "bob", "Carol", "Dan", "Eve"]);
+    }
+
+    #[tokio::test]
+    async fn test_sort_records_coercion_and_stability() {
+        let records = serde_json::json!([
+            { "id": "a", "price": "10.5" },
+            { "id": "b", "price": 9 },
+            { "id": "c", "price": "n/a" },
+            { "id": "d", "price": "9" },
+            { "id": "e", "price": true },
+        ]);
+
+        // Byte order of the JSON types in `auto` mode: booleans, numbers, then strings
+        let input = records_input(records.clone(), vec![key("/price")]);
+        let result = sort_records(&input).await.unwrap();
+        assert_eq!(result.indices.unwrap(), [4, 1, 0, 3, 2]);
+
+        // Equal prices keep their input order, and unparseable ones are null
+        let mut by_price = key("/price");
+        by_price.coerce = Some(KeyCoercion::Number);
+        for algorithm in [SortAlgorithm::Parallel, SortAlgorithm::Sequential] {
+            let mut input = records_input(records.clone(), vec![by_price.clone()]);
+            input.algorithm = algorithm;
+            let result = sort_records(&input).await.unwrap();
+            assert_eq!(result.indices.unwrap(), [4, 1, 3, 0, 2]);
+        }
+    }
+
+    #[test]
+    fn test_sort_records_validation() {
+        let input = records_input(
+            serde_json::json!([]),
+            vec![key("/ok"), key("missing/slash")],
+        );
+        let errors = input.validate().unwrap_err();
+        let mut fields = Vec::new();
+        crate::error::push_field_errors(&errors, "", &mut fields);
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].field, "keys[1].path");
+        assert_eq!(fields[0].code, "format");
+
+        let input = records_input(serde_json::json!([]), vec![]);
+        assert!(input
+            .validate()
+            .unwrap_err()
+            .field_errors()
+            .contains_key("keys"));
+    }
 }
@@ -0,0 +1,614 @@
+use super::sort::{KeyValue, SortDirection, SortKey, MAX_SORT_KEYS};
+use crate::data_dir;
+use crate::progress::ProgressHandle;
+use eyre::{eyre, Result, WrapErr};
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicU64};
+use std::time::Instant;
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+/// Memory used to sort lines when no `memory_budget` is given
+pub const DEFAULT_SORT_FILE_MEMORY: u64 = 64 * 1024 * 1024;
+/// Smallest `memory_budget` accepted by the `sort_file` command
+pub const MIN_SORT_FILE_MEMORY: u64 = 64 * 1024;
+/// Largest `memory_budget` accepted by the `sort_file` command
+pub const MAX_SORT_FILE_MEMORY: u64 = 1024 * 1024 * 1024;
+/// Largest number of runs merged at once, more runs are merged in several passes
+const MAX_MERGE_FAN_IN: usize = 64;
+/// Smallest read buffer of a run while merging
+const MIN_MERGE_BUFFER_LEN: usize = 4 * 1024;
+
+/// What the lines of a file hold, and how they compare.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LineFormat {
+    /// One number per line
+    Numbers {
+        /// `asc` by default
+        #[serde(default)]
+        #[cfg_attr(feature = "ts-rs", ts(optional))]
+        direction: Option<SortDirection>,
+    },
+    /// One JSON value per line (NDJSON), compared by keys like `sort_records`
+    Records { keys: Vec<SortKey> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SortFileInput {
+    /// Path relative to the data directory
+    pub path: String,
+    /// Path the sorted lines are written to, relative to the data directory, which may be `path`
+    pub output_path: String,
+    pub format: LineFormat,
+    /// Bytes of lines sorted in memory at once, 64 MiB by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional, type = "number"))]
+    #[schemars(range(min = MIN_SORT_FILE_MEMORY, max = MAX_SORT_FILE_MEMORY))]
+    pub memory_budget: Option<u64>,
+    /// Id to poll the number of bytes read then written with the `progress` command
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub progress_id: Option<String>,
+}
+
+impl Validate for SortFileInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        for (field, path) in [("path", &self.path), ("output_path", &self.output_path)] {
+            if path.is_empty() {
+                let mut err = ValidationError::new("length");
+                err.add_param("min".into(), &1);
+                errors.add(field, err);
+            }
+        }
+        if self
+            .memory_budget
+            .is_some_and(|budget| !(MIN_SORT_FILE_MEMORY..=MAX_SORT_FILE_MEMORY).contains(&budget))
+        {
+            let mut err = ValidationError::new("range");
+            err.add_param("min".into(), &MIN_SORT_FILE_MEMORY);
+            err.add_param("max".into(), &MAX_SORT_FILE_MEMORY);
+            errors.add("memory_budget", err);
+        }
+
+        if let LineFormat::Records { keys } = &self.format {
+            if keys.is_empty() || keys.len() as u64 > MAX_SORT_KEYS {
+                let mut err = ValidationError::new("length");
+                err.add_param("min".into(), &1);
+                err.add_param("max".into(), &MAX_SORT_KEYS);
+                errors.add("format.keys", err);
+            }
+            let keys: BTreeMap<_, _> = keys
+                .iter()
+                .enumerate()
+                .filter_map(|(i, key)| key.validate().err().map(|err| (i, Box::new(err))))
+                .collect();
+            if !keys.is_empty() {
+                errors
+                    .errors_mut()
+                    .insert("format.keys".into(), ValidationErrorsKind::List(keys));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SortFileResult {
+    /// Sorted lines written, blank lines are dropped
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub line_count: u64,
+    /// Sorted runs spilled to temporary files, 0 when the file fit in memory
+    pub runs: usize,
+    /// Merges of runs into fewer runs before the final merge
+    pub merge_passes: u32,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub file_size: u64,
+    pub computation_time_ms: u64,
+}
+
+/// Sorts the lines of a file that may not fit in memory.
+///
+/// Lines are sorted in chunks of `memory_budget` bytes, spilled as sorted runs next to the
+/// output, and the runs are merged into the output. Records sort stably, numbers don't.
+pub async fn sort_file(input: &SortFileInput) -> Result<SortFileResult> {
+    let start = Instant::now();
+    let path = data_dir::resolve("path", &input.path)?;
+    let output_path = data_dir::resolve("output_path", &input.output_path)?;
+
+    let file = File::open(&path).wrap_err_with(|| format!("failed to open {}", input.path))?;
+    let file_size = file
+        .metadata()
+        .wrap_err_with(|| format!("failed to read the metadata of {}", input.path))?
+        .len();
+
+    let format = input.format.clone();
+    let budget = input.memory_budget.unwrap_or(DEFAULT_SORT_FILE_MEMORY) as usize;
+    let progress_id = input.progress_id.clone();
+    let (line_count, runs, merge_passes) =
+        tokio::task::spawn_blocking(move || -> Result<(u64, usize, u32)> {
+            // Every byte is read once, then written once
+            let progress = ProgressHandle::start(progress_id.as_deref(), file_size * 2);
+            let mut temp = TempFiles::new(&output_path)?;
+
+            let mut reader = BufReader::new(file);
+            let mut chunk: Vec<Line> = Vec::new();
+            let mut chunk_len = 0;
+            let mut runs = Vec::new();
+            let mut text = String::new();
+            let mut line_number = 0;
+            loop {
+                text.clear();
+                let read = reader.read_line(&mut text)?;
+                if read == 0 {
+                    break;
+                }
+                progress.advance(read as u64);
+                line_number += 1;
+
+                let text = text.trim_end_matches(['\n', '\r']);
+                if text.trim().is_empty() {
+                    continue;
+                }
+                let key = format.key(text).ok_or_else(|| match format {
+                    LineFormat::Numbers { .. } => eyre!("line {line_number} is not a number"),
+                    LineFormat::Records { .. } => eyre!("line {line_number} is not valid JSON"),
+                })?;
+                chunk_len += text.len() + key.heap_len() + std::mem::size_of::<Line>();
+                chunk.push(Line {
+                    key,
+                    text: text.to_string(),
+                });
+
+                if chunk_len >= budget {
+                    runs.push(spill(&mut chunk, &format, temp.create())?);
+                    chunk_len = 0;
+                }
+            }
+
+            let sorting = temp.create();
+            let mut spilled = 0;
+            let mut merge_passes = 0;
+            let line_count = if runs.is_empty() {
+                format.sort(&mut chunk);
+                write_lines(chunk.into_iter(), &sorting, |len| progress.advance(len))?
+            } else {
+                if !chunk.is_empty() {
+                    runs.push(spill(&mut chunk, &format, temp.create())?);
+                }
+                drop(chunk);
+                spilled = runs.len();
+
+                let buffer_len = (budget / (MAX_MERGE_FAN_IN + 1)).max(MIN_MERGE_BUFFER_LEN);
+                while runs.len() > MAX_MERGE_FAN_IN {
+                    let mut merged = Vec::new();
+                    for group in runs.chunks(MAX_MERGE_FAN_IN) {
+                        let run = temp.create();
+                        merge(group, &run, &format, buffer_len, |_| {})?;
+                        merged.push(run);
+                    }
+                    // Frees the disk space of merged runs right away
+                    runs.iter().for_each(|run| drop(fs::remove_file(run)));
+                    runs = merged;
+                    merge_passes += 1;
+                }
+                merge(&runs, &sorting, &format, buffer_len, |len| {
+                    progress.advance(len)
+                })?
+            };
+
+            // Renamed last, so that the output is never partially written
+            fs::rename(&sorting, &output_path).wrap_err("failed to write the output")?;
+            Ok((line_count, spilled, merge_passes))
+        })
+        .await?
+        .wrap_err_with(|| format!("failed to sort {}", input.path))?;
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(SortFileResult {
+        line_count,
+        runs,
+        merge_passes,
+        file_size,
+        computation_time_ms,
+    })
+}
+
+/// A line of the file along with its parsed sort key.
+struct Line {
+    key: LineKey,
+    text: String,
+}
+
+enum LineKey {
+    Number(f64),
+    Record(Vec<KeyValue<'static>>),
+}
+
+impl LineKey {
+    /// Bytes allocated by the key, counted against the memory budget
+    fn heap_len(&self) -> usize {
+        match self {
+            LineKey::Number(_) => 0,
+            LineKey::Record(values) => {
+                let strings: usize = values
+                    .iter()
+                    .map(|value| match value {
+                        KeyValue::String(s) => s.len(),
+                        _ => 0,
+                    })
+                    .sum();
+                values.capacity() * std::mem::size_of::<KeyValue>() + strings
+            }
+        }
+    }
+}
+
+impl LineFormat {
+    fn key(&self, text: &str) -> Option<LineKey> {
+        match self {
+            LineFormat::Numbers { .. } => text
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|n| !n.is_nan())
+                .map(LineKey::Number),
+            LineFormat::Records { keys } => {
+                let record: Value = serde_json::from_str(text).ok()?;
+                let values = keys
+                    .iter()
+                    .map(|key| KeyValue::read(&record, key).into_owned())
+                    .collect();
+                Some(LineKey::Record(values))
+            }
+        }
+    }
+
+    fn compare(&self, a: &Line, b: &Line) -> Ordering {
+        match (self, &a.key, &b.key) {
+            (LineFormat::Numbers { direction }, LineKey::Number(a), LineKey::Number(b)) => {
+                match direction.unwrap_or_default() {
+                    SortDirection::Asc => a.total_cmp(b),
+                    SortDirection::Desc => b.total_cmp(a),
+                }
+            }
+            (LineFormat::Records { keys }, LineKey::Record(a), LineKey::Record(b)) => keys
+                .iter()
+                .zip(a.iter().zip(b))
+                .map(|(key, (a, b))| KeyValue::compare(a, b, key))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal),
+            _ => Ordering::Equal,
+        }
+    }
+
+    fn sort(&self, lines: &mut [Line]) {
+        match self {
+            LineFormat::Numbers { .. } => lines.par_sort_unstable_by(|a, b| self.compare(a, b)),
+            LineFormat::Records { .. } => lines.par_sort_by(|a, b| self.compare(a, b)),
+        }
+    }
+}
+
+/// Temporary files next to the output, removed when dropped.
+/// Distinguishes the temporary files of concurrent sorts with the same output
+static NEXT_SORT_ID: AtomicU64 = AtomicU64::new(0);
+
+struct TempFiles {
+    dir: PathBuf,
+    prefix: String,
+    paths: Vec<PathBuf>,
+}
+
+impl TempFiles {
+    fn new(output_path: &Path) -> Result<Self> {
+        let dir = output_path
+            .parent()
+            .ok_or_else(|| eyre!("the output has no parent directory"))?;
+        let name = output_path
+            .file_name()
+            .ok_or_else(|| eyre!("the output has no file name"))?;
+        Ok(TempFiles {
+            dir: dir.to_path_buf(),
+            prefix: format!(
+                ".{}.{}.{}",
+                name.to_string_lossy(),
+                std::process::id(),
+                NEXT_SORT_ID.fetch_add(1, atomic::Ordering::Relaxed)
+            ),
+            paths: Vec::new(),
+        })
+    }
+
+    fn create(&mut self) -> PathBuf {
+        let path = self
+            .dir
+            .join(format!("{}.{}.tmp", self.prefix, self.paths.len()));
+        self.paths.push(path.clone());
+        path
+    }
+}
+
+impl Drop for TempFiles {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Sorts the lines of a chunk into a run file, leaving the chunk empty.
+fn spill(chunk: &mut Vec<Line>, format: &LineFormat, run: PathBuf) -> Result<PathBuf> {
+    format.sort(chunk);
+    write_lines(chunk.drain(..), &run, |_| {})?;
+    Ok(run)
+}
+
+fn write_lines(
+    lines: impl Iterator<Item = Line>,
+    path: &Path,
+    mut on_write: impl FnMut(u64),
+) -> Result<u64> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let mut count = 0;
+    for line in lines {
+        writer.write_all(line.text.as_bytes())?;
+        writer.write_all(b"\n")?;
+        on_write(line.text.len() as u64 + 1);
+        count += 1;
+    }
+    writer.flush()?;
+    Ok(count)
+}
+
+/// Next line of a run, in the heap of a k-way merge.
+struct Head<'a> {
+    line: Line,
+    run: usize,
+    format: &'a LineFormat,
+}
+
+impl Ord for Head<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed for the max-heap to pop the smallest line, from the earliest run on ties
+        self.format
+            .compare(&other.line, &self.line)
+            .then(other.run.cmp(&self.run))
+    }
+}
+
+impl PartialOrd for Head<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Head<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Head<'_> {}
+
+fn merge(
+    runs: &[PathBuf],
+    path: &Path,
+    format: &LineFormat,
+    buffer_len: usize,
+    on_write: impl FnMut(u64),
+) -> Result<u64> {
+    let mut readers = runs
+        .iter()
+        .map(|run| Ok(BufReader::with_capacity(buffer_len, File::open(run)?).lines()))
+        .collect::<Result<Vec<_>>>()?;
+    let next = |reader: &mut Lines<BufReader<File>>| -> Result<Option<Line>> {
+        let Some(text) = reader.next().transpose()? else {
+            return Ok(None);
+        };
+        let key = format
+            .key(&text)
+            .ok_or_else(|| eyre!("a temporary run was modified while sorting"))?;
+        Ok(Some(Line { key, text }))
+    };
+
+    let mut heap = BinaryHeap::with_capacity(runs.len());
+    for (run, reader) in readers.iter_mut().enumerate() {
+        if let Some(line) = next(reader)? {
+            heap.push(Head { line, run, format });
+        }
+    }
+    let lines = std::iter::from_fn(|| {
+        let Head { line, run, .. } = heap.pop()?;
+        match next(&mut readers[run]) {
+            Ok(Some(line)) => heap.push(Head { line, run, format }),
+            Ok(None) => {}
+            Err(err) => return Some(Err(err)),
+        }
+        Some(Ok(line))
+    });
+
+    let mut error = None;
+    let count = write_lines(
+        lines.map_while(|line| line.map_err(|err| error = Some(err)).ok()),
+        path,
+        on_write,
+    )?;
+    match error {
+        Some(err) => Err(err),
+        None => Ok(count),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir();
+        crate::data_dir::set_data_dir(&crate::data_dir::SetDataDirInput {
+            path: dir.display().to_string(),
+        })
+        .unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_sort_file_numbers() {
+        let dir = temp_dir();
+        let name = format!("test_sort_file_numbers_{}.txt", std::process::id());
+        // Pseudo-random order, enough lines to need several merge passes with the minimum budget
+        let numbers: Vec<i64> = (0..100_000)
+            .map(|i| (i * 7919) % 100_003 - 50_000)
+            .collect();
+        let text: String = numbers.iter().map(|n| format!("{n}\n")).collect();
+        fs::write(dir.join(&name), format!("{text}\n   \n")).unwrap();
+
+        let mut input = SortFileInput {
+            path: name.clone(),
+            output_path: name.clone(),
+            format: LineFormat::Numbers {
+                direction: Some(SortDirection::Desc),
+            },
+            memory_budget: Some(MIN_SORT_FILE_MEMORY),
+            progress_id: Some(name.clone()),
+        };
+        let result = sort_file(&input).await.unwrap();
+        assert_eq!(result.line_count, 100_000);
+        assert!(result.runs > MAX_MERGE_FAN_IN);
+        assert_eq!(result.merge_passes, 1);
+
+        let mut expected = numbers.clone();
+        expected.sort_unstable_by(|a, b| b.cmp(a));
+        let sorted: Vec<i64> = fs::read_to_string(dir.join(&name))
+            .unwrap()
+            .lines()
+            .map(|line| line.parse().unwrap())
+            .collect();
+        assert_eq!(sorted, expected);
+
+        let progress =
+            crate::progress::progress(&crate::progress::ProgressInput { id: name.clone() })
+                .unwrap();
+        assert!(progress.done);
+        // Blank lines aren't written back
+        assert_eq!(progress.processed, progress.total - 5);
+
+        // The run files are gone
+        let prefix = format!(".{name}.");
+        assert!(!fs::read_dir(&dir).unwrap().any(|entry| entry
+            .unwrap()
+            .file_name()
+            .to_string_lossy()
+            .starts_with(&prefix)));
+
+        fs::write(dir.join(&name), "1\n2\nthree\n").unwrap();
+        input.memory_budget = None;
+        let err = sort_file(&input).await.unwrap_err();
+        assert!(format!("{err:#}").contains("line 3 is not a number"));
+
+        fs::remove_file(dir.join(&name)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sort_file_records_stable() {
+        let dir = temp_dir();
+        let name = format!("test_sort_file_records_{}.ndjson", std::process::id());
+        let output = format!("{name}.sorted");
+        let text: String = (0..5_000)
+            .map(|i| format!("{{\"group\":{},\"id\":{i}}}\n", i % 7))
+            .collect();
+        fs::write(dir.join(&name), text).unwrap();
+
+        let input = SortFileInput {
+            path: name.clone(),
+            output_path: output.clone(),
+            format: LineFormat::Records {
+                keys: vec![SortKey {
+                    path: "/group".to_string(),
+                    direction: Some(SortDirection::Desc),
+                    nulls: None,
+                    coerce: None,
+                    string_order: None,
+                }],
+            },
+            memory_budget: Some(MIN_SORT_FILE_MEMORY),
+            progress_id: None,
+        };
+        let result = sort_file(&input).await.unwrap();
+        assert!(result.runs > 1);
+
+        let records: Vec<(u64, u64)> = fs::read_to_string(dir.join(&output))
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let record: Value = serde_json::from_str(line).unwrap();
+                (
+                    record["group"].as_u64().unwrap(),
+                    record["id"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        let mut expected: Vec<(u64, u64)> = (0..5_000).map(|i| (i % 7, i)).collect();
+        expected.sort_by_key(|&(group, _)| std::cmp::Reverse(group));
+        assert_eq!(records, expected);
+
+        fs::remove_file(dir.join(&name)).unwrap();
+        fs::remove_file(dir.join(&output)).unwrap();
+    }
+
+    #[test]
+    fn test_temp_files_are_unique_per_sort() {
+        let output = std::env::temp_dir().join("sorted.txt");
+        let mut first = TempFiles::new(&output).unwrap();
+        let mut second = TempFiles::new(&output).unwrap();
+        assert_ne!(first.create(), second.create());
+    }
+
+    #[test]
+    fn test_sort_file_validation() {
+        let input = SortFileInput {
+            path: String::new(),
+            output_path: "out.txt".to_string(),
+            format: LineFormat::Records {
+                keys: vec![SortKey {
+                    path: "group".to_string(),
+                    direction: None,
+                    nulls: None,
+                    coerce: None,
+                    string_order: None,
+                }],
+            },
+            memory_budget: Some(1024),
+            progress_id: None,
+        };
+        let mut fields = Vec::new();
+        crate::error::push_field_errors(&input.validate().unwrap_err(), "", &mut fields);
+        let mut fields: Vec<_> = fields.into_iter().map(|error| error.field).collect();
+        fields.sort();
+        assert_eq!(fields, ["format.keys[0].path", "memory_budget", "path"]);
+    }
+}
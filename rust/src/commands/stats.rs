@@ -0,0 +1,617 @@
+use crate::error::{CommandError, FieldError};
+use eyre::Result;
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+/// Largest `values` array accepted by the `stats` command
+pub const MAX_STATS_LEN: u64 = 10_000_000;
+/// Largest number of quantiles computed at once
+pub const MAX_QUANTILES: u64 = 1_000;
+/// Largest number of histogram bins
+pub const MAX_HISTOGRAM_BINS: u32 = 10_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum VarianceKind {
+    /// Divides by n - 1, to estimate the variance of the population the values are drawn from
+    #[default]
+    Sample,
+    /// Divides by n, when the values are the whole population
+    Population,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct HistogramOptions {
+    /// Number of bins of equal width
+    #[schemars(range(min = 1, max = MAX_HISTOGRAM_BINS))]
+    pub bins: u32,
+    /// Lower edge of the first bin, the smallest value by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub min: Option<f64>,
+    /// Upper edge of the last bin, the largest value by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub max: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct StatsInput {
+    #[schemars(length(max = MAX_STATS_LEN))]
+    pub values: Vec<f64>,
+    /// `sample` by default
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub variance: Option<VarianceKind>,
+    /// Quantiles to compute, between 0 and 1, interpolated linearly between values
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub quantiles: Option<Vec<f64>>,
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub histogram: Option<HistogramOptions>,
+    /// Second series of the same length, to correlate `values` with
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-rs", ts(optional))]
+    pub paired: Option<Vec<f64>>,
+}
+
+impl Validate for StatsInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+
+        if self.values.len() as u64 > MAX_STATS_LEN {
+            let mut err = ValidationError::new("length");
+            err.add_param("max".into(), &MAX_STATS_LEN);
+            errors.add("values", err);
+        }
+        if let Some(quantiles) = &self.quantiles {
+            if quantiles.len() as u64 > MAX_QUANTILES {
+                let mut err = ValidationError::new("length");
+                err.add_param("max".into(), &MAX_QUANTILES);
+                errors.add("quantiles", err);
+            } else if quantiles.iter().any(|q| !(0.0..=1.0).contains(q)) {
+                let mut err = ValidationError::new("range");
+                err.add_param("min".into(), &0);
+                err.add_param("max".into(), &1);
+                errors.add("quantiles", err);
+            }
+        }
+        if let Some(histogram) = &self.histogram {
+            if histogram.bins == 0 || histogram.bins > MAX_HISTOGRAM_BINS {
+                let mut err = ValidationError::new("range");
+                err.add_param("min".into(), &1);
+                err.add_param("max".into(), &MAX_HISTOGRAM_BINS);
+                errors.add("histogram.bins", err);
+            }
+            if let (Some(min), Some(max)) = (histogram.min, histogram.max) {
+                // The span must be finite too, `{min: -1e308, max: 1e308}` overflows it
+                if !(max - min).is_finite() || min >= max {
+                    errors.add(
+                        "histogram.max",
+                        ValidationError::new("range")
+                            .with_message("must be finite and greater than min".into()),
+                    );
+                }
+            }
+        }
+        if self
+            .paired
+            .as_ref()
+            .is_some_and(|paired| paired.len() != self.values.len())
+        {
+            errors.add(
+                "paired",
+                ValidationError::new("length")
+                    .with_message("must have as many values as `values`".into()),
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct Histogram {
+    /// `bins + 1` edges, every bin includes its lower edge and the last one its upper edge too
+    pub edges: Vec<f64>,
+    #[cfg_attr(feature = "ts-rs", ts(type = "Array<number>"))]
+    pub counts: Vec<u64>,
+    /// Values outside of the edges
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub outside: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct Correlation {
+    /// Pearson correlation coefficient, null when either series is constant
+    pub pearson: Option<f64>,
+    /// Covariance, with the same denominator as the variance
+    pub covariance: Option<f64>,
+}
+
+/// Statistics of `values`; the ones undefined for too few values are null.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct StatsResult {
+    pub count: usize,
+    pub sum: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+    pub variance: Option<f64>,
+    pub std_dev: Option<f64>,
+    pub median: Option<f64>,
+    /// Values of the requested quantiles, in the same order
+    pub quantiles: Option<Vec<f64>>,
+    /// Smallest of the most frequent values
+    pub mode: Option<f64>,
+    /// Occurrences of `mode`
+    pub mode_count: usize,
+    pub histogram: Option<Histogram>,
+    /// Only set when `paired` is given
+    pub correlation: Option<Correlation>,
+    pub computation_time_ms: u64,
+}
+
+pub async fn stats(input: &StatsInput) -> Result<StatsResult> {
+    let start = Instant::now();
+    let values = &input.values;
+    let variance_kind = input.variance.unwrap_or_default();
+
+    let moments = values
+        .par_iter()
+        .fold(Moments::default, |moments, &x| moments.push(x))
+        .reduce(Moments::default, Moments::merge);
+    let variance = moments.variance(variance_kind);
+
+    // Order statistics all come from a single sort
+    let mut sorted = values.clone();
+    sorted.par_sort_unstable_by(f64::total_cmp);
+    let quantiles = input.quantiles.as_ref().map(|quantiles| {
+        quantiles
+            .iter()
+            .map(|&q| quantile(&sorted, q).unwrap_or(f64::NAN))
+            .collect()
+    });
+    let (mode, mode_count) = mode(&sorted);
+
+    let histogram = input
+        .histogram
+        .as_ref()
+        .map(|options| histogram(values, options, &moments))
+        .transpose()?;
+
+    let correlation = input.paired.as_ref().map(|paired| {
+        let co_moments = values
+            .par_iter()
+            .zip(paired.par_iter())
+            .fold(CoMoments::default, |co_moments, (&x, &y)| {
+                co_moments.push(x, y)
+            })
+            .reduce(CoMoments::default, CoMoments::merge);
+        co_moments.correlation(variance_kind)
+    });
+
+    let computation_time_ms = start.elapsed().as_millis() as u64;
+
+    Ok(StatsResult {
+        count: values.len(),
+        sum: moments.sum + moments.compensation,
+        min: (moments.n > 0).then_some(moments.min),
+        max: (moments.n > 0).then_some(moments.max),
+        mean: (moments.n > 0).then_some(moments.mean),
+        variance,
+        std_dev: variance.map(f64::sqrt),
+        median: quantile(&sorted, 0.5),
+        quantiles,
+        mode,
+        mode_count,
+        histogram,
+        correlation,
+        computation_time_ms,
+    })
+}
+
+/// Running count, mean and sum of squared deviations (Welford), mergeable across threads
+/// (Chan et al.), along with the extremes and a compensated sum (Neumaier).
+#[derive(Debug, Clone, Copy)]
+struct Moments {
+    n: u64,
+    mean: f64,
+    m2: f64,
+    min: f64,
+    max: f64,
+    sum: f64,
+    compensation: f64,
+}
+
+impl Default for Moments {
+    fn default() -> Self {
+        Moments {
+            n: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0.0,
+            compensation: 0.0,
+        }
+    }
+}
+
+impl Moments {
+    fn push(mut self, x: f64) -> Self {
+        self.n += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.n as f64;
+        self.m2 += delta * (x - self.mean);
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        self.add_to_sum(x);
+        self
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        if other.n == 0 {
+            return self;
+        }
+        if self.n == 0 {
+            return other;
+        }
+        let n = self.n + other.n;
+        let delta = other.mean - self.mean;
+        let weight = self.n as f64 * other.n as f64 / n as f64;
+        self.mean += delta * other.n as f64 / n as f64;
+        self.m2 += other.m2 + delta * delta * weight;
+        self.n = n;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.add_to_sum(other.sum);
+        self.compensation += other.compensation;
+        self
+    }
+
+    fn add_to_sum(&mut self, x: f64) {
+        let sum = self.sum + x;
+        // Recovers the low-order bits lost by whichever operand is smaller
+        self.compensation += if self.sum.abs() >= x.abs() {
+            (self.sum - sum) + x
+        } else {
+            (x - sum) + self.sum
+        };
+        self.sum = sum;
+    }
+
+    fn variance(&self, kind: VarianceKind) -> Option<f64> {
+        let denominator = denominator(self.n, kind)?;
+        Some(self.m2 / denominator)
+    }
+}
+
+/// Running means and co-moments of two series, merged like [`Moments`].
+#[derive(Debug, Default, Clone, Copy)]
+struct CoMoments {
+    n: u64,
+    mean_x: f64,
+    mean_y: f64,
+    m2_x: f64,
+    m2_y: f64,
+    c_xy: f64,
+}
+
+impl CoMoments {
+    fn push(mut self, x: f64, y: f64) -> Self {
+        self.n += 1;
+        let n = self.n as f64;
+        let delta_x = x - self.mean_x;
+        let delta_y = y - self.mean_y;
+        self.mean_x += delta_x / n;
+        self.mean_y += delta_y / n;
+        self.m2_x += delta_x * (x - self.mean_x);
+        self.m2_y += delta_y * (y - self.mean_y);
+        self.c_xy += delta_x * (y - self.mean_y);
+        self
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        if other.n == 0 {
+            return self;
+        }
+        if self.n == 0 {
+            return other;
+        }
+        let n = self.n + other.n;
+        let delta_x = other.mean_x - self.mean_x;
+        let delta_y = other.mean_y - self.mean_y;
+        let weight = self.n as f64 * other.n as f64 / n as f64;
+        self.mean_x += delta_x * other.n as f64 / n as f64;
+        self.mean_y += delta_y * other.n as f64 / n as f64;
+        self.m2_x += other.m2_x + delta_x * delta_x * weight;
+        self.m2_y += other.m2_y + delta_y * delta_y * weight;
+        self.c_xy += other.c_xy + delta_x * delta_y * weight;
+        self.n = n;
+        self
+    }
+
+    fn correlation(&self, kind: VarianceKind) -> Correlation {
+        let spread = (self.m2_x * self.m2_y).sqrt();
+        Correlation {
+            pearson: (spread > 0.0).then(|| (self.c_xy / spread).clamp(-1.0, 1.0)),
+            covariance: denominator(self.n, kind).map(|denominator| self.c_xy / denominator),
+        }
+    }
+}
+
+fn denominator(n: u64, kind: VarianceKind) -> Option<f64> {
+    match kind {
+        VarianceKind::Sample if n >= 2 => Some((n - 1) as f64),
+        VarianceKind::Population if n >= 1 => Some(n as f64),
+        _ => None,
+    }
+}
+
+/// Linear interpolation between the closest ranks, as in NumPy and R's default (type 7).
+fn quantile(sorted: &[f64], q: f64) -> Option<f64> {
+    let last = sorted.len().checked_sub(1)?;
+    let position = q * last as f64;
+    let lower = position.floor() as usize;
+    let upper = (lower + 1).min(last);
+    let fraction = position - lower as f64;
+    Some(sorted[lower] + (sorted[upper] - sorted[lower]) * fraction)
+}
+
+fn mode(sorted: &[f64]) -> (Option<f64>, usize) {
+    let mut best: (Option<f64>, usize) = (None, 0);
+    for run in sorted.chunk_by(|a, b| a == b) {
+        // Strictly longer runs only, so ties keep the smallest value
+        if run.len() > best.1 {
+            best = (Some(run[0]), run.len());
+        }
+    }
+    best
+}
+
+fn histogram(
+    values: &[f64],
+    options: &HistogramOptions,
+    moments: &Moments,
+) -> Result<Histogram, CommandError> {
+    let bins = options.bins as usize;
+    let min = options
+        .min
+        .unwrap_or(if moments.n > 0 { moments.min } else { 0.0 });
+    let max = options
+        .max
+        .unwrap_or(if moments.n > 0 { moments.max } else { 0.0 });
+    // Both bounds given are checked by `validate`, a single one only against the data
+    let invalid = if min > max {
+        if options.max.is_some() {
+            Some(("histogram.max", "must be at least the smallest value"))
+        } else {
+            Some(("histogram.min", "must be at most the largest value"))
+        }
+    } else if !(max - min).is_finite() {
+        if options.max.is_some() {
+            Some(("histogram.max", "must be finite and greater than min"))
+        } else if options.min.is_some() {
+            Some(("histogram.min", "must be finite and less than max"))
+        } else {
+            Some((
+                "histogram",
+                "the values span more than the largest finite number",
+            ))
+        }
+    } else {
+        None
+    };
+    if let Some((field, msg)) = invalid {
+        return Err(CommandError::invalid_params(vec![FieldError {
+            field: field.to_string(),
+            code: "range".to_string(),
+            msg: msg.to_string(),
+        }]));
+    }
+    let width = (max - min) / bins as f64;
+
+    let edges = (0..=bins)
+        .map(|i| {
+            if i == bins {
+                max
+            } else {
+                min + width * i as f64
+            }
+        })
+        .collect();
+    let bin_of = |x: f64| {
+        if !(min..=max).contains(&x) {
+            None
+        } else if width > 0.0 {
+            // The upper edge belongs to the last bin, rounding may also land there
+            Some((((x - min) / width) as usize).min(bins - 1))
+        } else {
+            Some(0)
+        }
+    };
+
+    // Each thread counts into its own bins, the last slot holds the values outside of them
+    let counts = values
+        .par_iter()
+        .fold(
+            || vec![0u64; bins + 1],
+            |mut counts, &x| {
+                counts[bin_of(x).unwrap_or(bins)] += 1;
+                counts
+            },
+        )
+        .reduce(
+            || vec![0u64; bins + 1],
+            |mut a, b| {
+                a.iter_mut().zip(b).for_each(|(a, b)| *a += b);
+                a
+            },
+        );
+    let (counts, outside) = counts.split_at(bins);
+
+    Ok(Histogram {
+        edges,
+        counts: counts.to_vec(),
+        outside: outside[0],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(values: Vec<f64>) -> StatsInput {
+        StatsInput {
+            values,
+            variance: None,
+            quantiles: None,
+            histogram: None,
+            paired: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stats_summary() {
+        let mut input = input(vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        let result = stats(&input).await.unwrap();
+        assert_eq!(result.count, 8);
+        assert_eq!(result.sum, 40.0);
+        assert_eq!((result.min, result.max), (Some(2.0), Some(9.0)));
+        assert_eq!(result.mean, Some(5.0));
+        assert_eq!(result.variance, Some(32.0 / 7.0));
+        assert_eq!(result.median, Some(4.5));
+        assert_eq!((result.mode, result.mode_count), (Some(4.0), 3));
+        assert!(result.correlation.is_none());
+
+        input.variance = Some(VarianceKind::Population);
+        let result = stats(&input).await.unwrap();
+        assert_eq!((result.variance, result.std_dev), (Some(4.0), Some(2.0)));
+
+        let result = stats(&super::tests::input(vec![])).await.unwrap();
+        assert_eq!((result.count, result.sum), (0, 0.0));
+        assert_eq!(
+            (result.mean, result.median, result.variance),
+            (None, None, None)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stats_stability_and_correlation() {
+        // Naive sums of squares lose every digit of variance with such an offset
+        let offset = 1e9;
+        let values: Vec<f64> = (0..100_000).map(|i| offset + (i % 10) as f64).collect();
+        let mut input = input(values.clone());
+        input.variance = Some(VarianceKind::Population);
+        input.paired = Some(values.iter().map(|x| 3.0 - 2.0 * x).collect());
+        let result = stats(&input).await.unwrap();
+        assert!((result.variance.unwrap() - 8.25).abs() < 1e-6);
+        assert_eq!(result.sum, offset * 100_000.0 + 450_000.0);
+        let correlation = result.correlation.unwrap();
+        assert!((correlation.pearson.unwrap() + 1.0).abs() < 1e-9);
+        assert!((correlation.covariance.unwrap() + 16.5).abs() < 1e-3);
+    }
+
+    #[tokio::test]
+    async fn test_stats_quantiles_and_histogram() {
+        let mut input = input((1..=10).map(f64::from).collect());
+        input.quantiles = Some(vec![0.0, 0.25, 0.9, 1.0]);
+        input.histogram = Some(HistogramOptions {
+            bins: 4,
+            min: None,
+            max: Some(8.0),
+        });
+        let result = stats(&input).await.unwrap();
+        assert_eq!(result.quantiles.unwrap(), [1.0, 3.25, 9.1, 10.0]);
+        let histogram = result.histogram.unwrap();
+        assert_eq!(histogram.edges, [1.0, 2.75, 4.5, 6.25, 8.0]);
+        assert_eq!(histogram.counts, [2, 2, 2, 2]);
+        assert_eq!(histogram.outside, 2);
+
+        input.quantiles = Some(vec![1.5]);
+        input.histogram = Some(HistogramOptions {
+            bins: 0,
+            min: Some(1.0),
+            max: Some(1.0),
+        });
+        input.paired = Some(vec![1.0]);
+        let errors = input.validate().unwrap_err();
+        let mut fields: Vec<_> = errors.field_errors().into_keys().collect();
+        fields.sort();
+        assert_eq!(
+            fields,
+            ["histogram.bins", "histogram.max", "paired", "quantiles"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stats_histogram_bounds_outside_data() {
+        let mut input = input(vec![1.0, 2.0, 3.0]);
+        for (min, max, field) in [
+            (None, Some(0.5), "histogram.max"),
+            (Some(3.5), None, "histogram.min"),
+        ] {
+            input.histogram = Some(HistogramOptions { bins: 2, min, max });
+            let err = stats(&input).await.unwrap_err();
+            let field_errors = crate::error::field_errors(&err).unwrap();
+            assert_eq!(field_errors[0].field, field);
+        }
+
+        // A single bound on the edge of the data leaves one bin holding it
+        input.histogram = Some(HistogramOptions {
+            bins: 2,
+            min: Some(3.0),
+            max: None,
+        });
+        let histogram = stats(&input).await.unwrap().histogram.unwrap();
+        assert_eq!(histogram.counts, [1, 0]);
+        assert_eq!(histogram.outside, 2);
+    }
+
+    #[tokio::test]
+    async fn test_stats_histogram_span_overflow() {
+        let mut input = input(vec![-1e308, 1e308]);
+        input.histogram = Some(HistogramOptions {
+            bins: 2,
+            min: Some(-1e308),
+            max: Some(1e308),
+        });
+        let errors = input.validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("histogram.max"));
+
+        for (min, max, field) in [
+            (None, Some(1e308), "histogram.max"),
+            (Some(-1e308), None, "histogram.min"),
+            (None, None, "histogram"),
+        ] {
+            input.histogram = Some(HistogramOptions { bins: 2, min, max });
+            let err = stats(&input).await.unwrap_err();
+            let field_errors = crate::error::field_errors(&err).unwrap();
+            assert_eq!(field_errors[0].field, field);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! The app data directory, which bounds the files that commands may access.
+//!
+//! Commands take paths relative to it, so a command can never reach outside the directory
+//! the host configured with `set_data_dir`.
+
+use crate::error::{CommandError, ErrorCode, FieldError};
+use eyre::{eyre, Result, WrapErr};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use tracing::info;
+use validator::Validate;
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+static DATA_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct SetDataDirInput {
+    /// Absolute path of an existing directory
+    #[validate(length(min = 1))]
+    pub path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct DataDirStatus {
+    pub path: String,
+}
+
+pub fn set_data_dir(input: &SetDataDirInput) -> Result<DataDirStatus> {
+    let path = Path::new(&input.path);
+    if !path.is_absolute() {
+        return Err(invalid_path("path", "the data directory must be an absolute path").into());
+    }
+
+    let path = path
+        .canonicalize()
+        .wrap_err_with(|| format!("failed to open data directory {}", input.path))?;
+    if !path.is_dir() {
+        return Err(eyre!("{} is not a directory", path.display()));
+    }
+
+    info!("data directory set to {}", path.display());
+    let status = DataDirStatus {
+        path: path.display().to_string(),
+    };
+    *DATA_DIR
+        .lock()
+        .map_err(|_| eyre!("data directory lock poisoned"))? = Some(path);
+    Ok(status)
+}
+
+/// Resolves the path given in the parameter `field` against the data directory.
+///
+/// The path must be relative and stay inside the directory, symbolic links included.
+pub fn resolve(field: &str, relative: &str) -> Result<PathBuf, CommandError> {
+    let root = DATA_DIR
+        .lock()
+        .ok()
+        .and_then(|dir| dir.clone())
+        .ok_or_else(|| {
+            CommandError::new(
+                ErrorCode::ExecutionFailed,
+                "the data directory isn't configured, send `set_data_dir` first",
+            )
+        })?;
+
+    let relative_path = Path::new(relative);
+    let is_contained = relative_path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if relative.is_empty() || !is_contained {
+        return Err(invalid_path(
+            field,
+            "must be a relative path inside the data directory",
+        ));
+    }
+
+    let path = root.join(relative_path);
+    // Any existing component may be a link pointing elsewhere, including the parent of a file
+    // that is about to be created, so check where the deepest existing one actually is
+    let existing = path
+        .ancestors()
+        .find(|ancestor| ancestor.symlink_metadata().is_ok())
+        .unwrap_or(&root);
+    let is_inside = existing
+        .canonicalize()
+        .is_ok_and(|canonical| canonical.starts_with(&root));
+    if !is_inside {
+        return Err(invalid_path(
+            field,
+            "resolves outside of the data directory",
+        ));
+    }
+    Ok(path)
+}
+
+fn invalid_path(field: &str, msg: &str) -> CommandError {
+    CommandError::invalid_params(vec![FieldError {
+        field: field.to_string(),
+        code: "path".to_string(),
+        msg: msg.to_string(),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_rejects_escapes() {
+        let dir = std::env::temp_dir();
+        set_data_dir(&SetDataDirInput {
+            path: dir.display().to_string(),
+        })
+        .unwrap();
+
+        assert!(resolve("path", "downloads/file.bin").is_ok());
+        for path in ["", "../etc/passwd", "/etc/passwd", "a/../../b"] {
+            let err = resolve("path", path).unwrap_err();
+            assert_eq!(err.code, ErrorCode::InvalidParams, "{path}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_rejects_links_outside() {
+        let dir = std::env::temp_dir();
+        set_data_dir(&SetDataDirInput {
+            path: dir.display().to_string(),
+        })
+        .unwrap();
+        let outside = Path::new("/");
+        let link = format!("test_resolve_link_{}", std::process::id());
+        let dangling = format!("test_resolve_dangling_{}", std::process::id());
+        let _ = std::fs::remove_file(dir.join(&link));
+        let _ = std::fs::remove_file(dir.join(&dangling));
+        std::os::unix::fs::symlink(outside, dir.join(&link)).unwrap();
+        std::os::unix::fs::symlink(
+            outside.join("nonexistent").join("file"),
+            dir.join(&dangling),
+        )
+        .unwrap();
+
+        // Files that don't exist yet are reached through the link in their parent
+        for path in [
+            link.clone(),
+            format!("{link}/new_file.txt"),
+            format!("{link}/new_dir/new_file.txt"),
+            dangling.clone(),
+        ] {
+            let err = resolve("output_path", &path).unwrap_err();
+            assert_eq!(err.code, ErrorCode::InvalidParams, "{path}");
+        }
+        assert!(resolve("output_path", "new_dir/new_file.txt").is_ok());
+
+        std::fs::remove_file(dir.join(&link)).unwrap();
+        std::fs::remove_file(dir.join(&dangling)).unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use validator::{ValidationErrors, ValidationErrorsKind};
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+/// Machine readable category of a failed command, reported next to the error message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The raw command string couldn't be parsed into a `Command`
+    InvalidCommand,
+    /// The command parameters violate their declared constraints
+    InvalidParams,
+    /// The command was parsed but failed while executing
+    ExecutionFailed,
+    /// Decryption failed because the key, the associated data or the ciphertext is wrong
+    AuthenticationFailed,
+    /// The command panicked
+    Panic,
+}
+
+/// A parameter that failed validation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct FieldError {
+    /// Path of the field within `params`, e.g. `numbers` or `command.params.limit`
+    pub field: String,
+    /// Name of the violated constraint, e.g. `length` or `range`
+    pub code: String,
+    pub msg: String,
+}
+
+/// An error carrying an [`ErrorCode`] other than the default `ExecutionFailed`.
+///
+/// Commands return it through `eyre`, any context added on top is kept in the message while
+/// the code is recovered from the error chain.
+#[derive(Debug)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub msg: String,
+    pub field_errors: Vec<FieldError>,
+}
+
+impl CommandError {
+    pub fn new(code: ErrorCode, msg: impl Into<String>) -> Self {
+        Self {
+            code,
+            msg: msg.into(),
+            field_errors: Vec::new(),
+        }
+    }
+
+    pub fn invalid_params(mut field_errors: Vec<FieldError>) -> Self {
+        field_errors.sort_by(|a, b| a.field.cmp(&b.field));
+        let fields: Vec<&str> = field_errors.iter().map(|e| e.field.as_str()).collect();
+        Self {
+            code: ErrorCode::InvalidParams,
+            msg: format!("invalid parameters: {}", fields.join(", ")),
+            field_errors,
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// Finds the most specific error code in an error chain.
+pub fn error_code(err: &eyre::Error) -> ErrorCode {
+    command_error(err)
+        .map(|err| err.code)
+        .unwrap_or(ErrorCode::ExecutionFailed)
+}
+
+/// Finds the field level errors in an error chain, if any.
+pub fn field_errors(err: &eyre::Error) -> Option<Vec<FieldError>> {
+    command_error(err)
+        .filter(|err| !err.field_errors.is_empty())
+        .map(|err| err.field_errors.clone())
+}
+
+fn command_error(err: &eyre::Error) -> Option<&CommandError> {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<CommandError>())
+}
+
+/// Flattens `validator` errors into [`FieldError`]s, prefixing every field path.
+pub fn push_field_errors(errors: &ValidationErrors, prefix: &str, out: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let path = format!("{prefix}{field}");
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                out.extend(errors.iter().map(|err| {
+                    // Skip the offending value, it may be arbitrarily large
+                    let mut params: Vec<String> = err
+                        .params
+                        .iter()
+                        .filter(|(name, _)| *name != "value")
+                        .map(|(name, value)| format!("{name} = {value}"))
+                        .collect();
+                    params.sort();
+
+                    let msg = match &err.message {
+                        Some(msg) => msg.to_string(),
+                        None => format!("failed {} check ({})", err.code, params.join(", ")),
+                    };
+                    FieldError {
+                        field: path.clone(),
+                        code: err.code.to_string(),
+                        msg,
+                    }
+                }));
+            }
+            ValidationErrorsKind::Struct(errors) => {
+                push_field_errors(errors, &format!("{path}."), out);
+            }
+            ValidationErrorsKind::List(items) => {
+                for (i, errors) in items {
+                    push_field_errors(errors, &format!("{path}[{i}]."), out);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::WrapErr;
+
+    #[test]
+    fn test_error_code_from_chain() {
+        let err: eyre::Error = Err::<(), _>(CommandError::new(ErrorCode::InvalidCommand, "bad"))
+            .wrap_err("context")
+            .unwrap_err();
+        assert_eq!(error_code(&err), ErrorCode::InvalidCommand);
+
+        let err = eyre::eyre!("plain failure");
+        assert_eq!(error_code(&err), ErrorCode::ExecutionFailed);
+    }
+}
@@ -1,15 +1,17 @@
 #![deny(clippy::disallowed_methods)]
 
 use cmd::{execute_cmd, Command};
+use error::{error_code, field_errors, ErrorCode, FieldError};
 use eyre::WrapErr;
 use lazy_static::lazy_static;
 use panic_handler::handle_panic;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use std::{
     ffi::{c_char, CStr, CString},
     panic,
     sync::Arc,
+    time::Instant,
 };
 use tokio::runtime::{Builder, Runtime};
 use tracing::error;
@@ -19,25 +21,59 @@ use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::EnvFilter;
 
+pub mod buffers;
 pub mod cmd;
-mod commands;
+pub mod commands;
+pub mod data_dir;
+pub mod error;
+pub mod math;
 mod panic_handler;
+pub mod progress;
+pub mod session;
 mod tracing_collector;
 
+static MESSAGE_STORE: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
 lazy_static! {
     pub static ref RUNTIME: Arc<Runtime> =
         Arc::new(Builder::new_multi_thread().enable_all().build().unwrap());
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct CommandResult {
     pub res: Option<String>,
     pub error: Option<String>,
+    pub error_code: Option<ErrorCode>,
+    pub field_errors: Option<Vec<FieldError>>,
     pub panic: bool,
     pub panic_details: Option<PanicDetails>,
 }
 
-#[derive(Serialize)]
+impl CommandResult {
+    pub(crate) fn from_exec(cmd_res: Result<String, eyre::Error>) -> Self {
+        match cmd_res {
+            Ok(res) => CommandResult {
+                res: Some(res),
+                error: None,
+                error_code: None,
+                field_errors: None,
+                panic: false,
+                panic_details: None,
+            },
+            // Handled errors
+            Err(err) => CommandResult {
+                res: None,
+                error: Some(format!("{} -> {}", err, err.root_cause())),
+                error_code: Some(error_code(&err)),
+                field_errors: field_errors(&err),
+                panic: false,
+                panic_details: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct PanicDetails {
     pub cmd: String,
     pub msg: String,
@@ -62,7 +98,6 @@ pub extern "C" fn rust_execute(raw_cmd: *const c_char) -> *const c_char {
     });
 
     static SETUP_TRACING: std::sync::Once = std::sync::Once::new();
-    static MESSAGE_STORE: Mutex<Vec<String>> = Mutex::new(Vec::new());
     SETUP_TRACING.call_once(|| {
         let vec_make_writer = VecMakeWriter::new(&MESSAGE_STORE);
 
@@ -76,20 +111,30 @@ pub extern "C" fn rust_execute(raw_cmd: *const c_char) -> *const c_char {
             .init();
     });
 
-    // Parse the command ahead of time, returning early with error message if invalid
     let cmd_str = unsafe { CStr::from_ptr(raw_cmd).to_str().unwrap() };
+
+    let start = Instant::now();
+    let res_str = execute_cmd_str(cmd_str);
+    session::record(cmd_str, &res_str, start.elapsed());
+
+    CString::new(res_str.as_bytes()).unwrap().into_raw()
+}
+
+fn execute_cmd_str(cmd_str: &str) -> String {
+    // Parse the command ahead of time, returning early with error message if invalid
     let cmd = match parse_command(cmd_str) {
         Ok(cmd) => Arc::new(cmd),
         Err(err) => {
             let invalid_cmd_res = CommandResult {
                 res: None,
                 error: Some(err.to_string()),
+                error_code: Some(ErrorCode::InvalidCommand),
+                field_errors: None,
                 panic: false,
                 panic_details: None,
             };
             error!("cmd error: {:#}", err);
-            let invalid_cmd_res = serde_json::to_string(&invalid_cmd_res).unwrap();
-            return CString::new(invalid_cmd_res.as_bytes()).unwrap().into_raw();
+            return serde_json::to_string(&invalid_cmd_res).unwrap();
         }
     };
 
@@ -97,35 +142,14 @@ pub extern "C" fn rust_execute(raw_cmd: *const c_char) -> *const c_char {
     let exec_res = panic::catch_unwind(|| {
         RUNTIME.block_on(async move {
             let cmd_res = execute_cmd(exec_cmd, &MESSAGE_STORE).await;
-
-            let cmd_res = match cmd_res {
-                Ok(res) => CommandResult {
-                    res: Some(res),
-                    error: None,
-                    panic: false,
-                    panic_details: None,
-                },
-                // Handled errors
-                Err(err) => CommandResult {
-                    res: None,
-                    error: Some(format!("{} -> {}", err, err.root_cause())),
-                    panic: false,
-                    panic_details: None,
-                },
-            };
-
-            let res_str = serde_json::to_string(&cmd_res).unwrap();
-            CString::new(res_str.as_bytes()).unwrap().into_raw()
+            serde_json::to_string(&CommandResult::from_exec(cmd_res)).unwrap()
         })
     });
 
     // Handle panics, if any
     match exec_res {
         Ok(res) => res,
-        Err(err) => {
-            let panic_res_str = handle_panic(err, cmd);
-            CString::new(panic_res_str.as_bytes()).unwrap().into_raw()
-        }
+        Err(err) => handle_panic(err, cmd),
     }
 }
 
@@ -139,6 +163,26 @@ pub extern "C" fn rust_free_string(ptr: *mut c_char) {
     }
 }
 
+/// Copies `len` bytes at `data` into a buffer that commands can refer to by the returned id.
+///
+/// The buffer stays alive until it is released with [`rust_release_buffer`].
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn rust_register_buffer(data: *const u8, len: usize) -> u64 {
+    let bytes = if data.is_null() || len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(data, len) }.to_vec()
+    };
+    buffers::insert(bytes)
+}
+
+/// Releases a buffer registered with [`rust_register_buffer`], returning whether it existed.
+#[no_mangle]
+pub extern "C" fn rust_release_buffer(id: u64) -> bool {
+    buffers::remove(id)
+}
+
 fn parse_command(cmd_str: &str) -> Result<Command, eyre::Error> {
     serde_json::from_str::<Command>(cmd_str).wrap_err(format!(
         "failed to extract Command from raw command string: {cmd_str}"
@@ -0,0 +1,720 @@
+//! Integer sequence arithmetic shared by the `fibonacci` and `sequence` commands.
+
+use dashu_int::{IBig, UBig};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::{ValidationError, ValidationErrors};
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+/// How the terms of a sequence are represented.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+#[serde(rename_all = "snake_case")]
+pub enum ArithmeticMode {
+    /// 64-bit arithmetic, reporting an overflow once a term no longer fits
+    #[default]
+    Fixed,
+    /// Exact values as decimal strings
+    BigInt,
+    /// Values reduced modulo `modulus`
+    Modular,
+}
+
+/// Checks that `modulus` is given exactly when the mode needs one.
+pub fn check_modulus(mode: ArithmeticMode, modulus: Option<u64>, errors: &mut ValidationErrors) {
+    match (mode, modulus) {
+        (ArithmeticMode::Modular, None) => errors.add(
+            "modulus",
+            ValidationError::new("required")
+                .with_message("modulus is required in modular mode".into()),
+        ),
+        (ArithmeticMode::Modular, Some(0)) => {
+            let mut err = ValidationError::new("range");
+            err.add_param("min".into(), &1);
+            errors.add("modulus", err);
+        }
+        (ArithmeticMode::Fixed | ArithmeticMode::BigInt, Some(_)) => errors.add(
+            "modulus",
+            ValidationError::new("mode")
+                .with_message("modulus is only used in modular mode".into()),
+        ),
+        _ => {}
+    }
+}
+
+/// Number system the sequences are evaluated in.
+///
+/// Operations return `None` when the result isn't representable, which only happens with
+/// [`Checked`].
+pub trait Arithmetic {
+    type Value: Clone;
+
+    fn of_i64(&self, value: i64) -> Option<Self::Value>;
+    fn add(&self, a: &Self::Value, b: &Self::Value) -> Option<Self::Value>;
+    fn mul(&self, a: &Self::Value, b: &Self::Value) -> Option<Self::Value>;
+    /// `(F(n), F(n + 1))` by fast doubling
+    fn fibonacci_pair(&self, n: u64) -> Option<(Self::Value, Self::Value)>;
+    /// Terms `start..start + count` of n!, stopping before the first unrepresentable one
+    fn factorials(&self, start: u64, count: usize) -> Vec<Self::Value>;
+    /// Terms `start..start + count` of the Catalan numbers, stopping likewise
+    fn catalan(&self, start: u64, count: usize) -> Vec<Self::Value>;
+    fn to_decimal(&self, value: &Self::Value) -> String;
+    /// Upper bound of the length of `to_decimal`, without formatting the value
+    fn decimal_len(&self, value: &Self::Value) -> usize;
+}
+
+/// `i64` arithmetic failing on overflow.
+pub struct Checked;
+
+/// Arithmetic modulo `m`, with `m > 0`.
+pub struct Modular(pub u64);
+
+/// Arbitrary-precision arithmetic.
+pub struct Exact;
+
+impl Arithmetic for Checked {
+    type Value = i64;
+
+    fn of_i64(&self, value: i64) -> Option<i64> {
+        Some(value)
+    }
+
+    fn add(&self, a: &i64, b: &i64) -> Option<i64> {
+        a.checked_add(*b)
+    }
+
+    fn mul(&self, a: &i64, b: &i64) -> Option<i64> {
+        a.checked_mul(*b)
+    }
+
+    fn fibonacci_pair(&self, n: u64) -> Option<(i64, i64)> {
+        let next = i64::try_from(fibonacci_u64(n.checked_add(1)?)?).ok()?;
+        Some((fibonacci_u64(n)? as i64, next))
+    }
+
+    fn factorials(&self, start: u64, count: usize) -> Vec<i64> {
+        step_terms(1, start, count, |value, k| {
+            value.checked_mul(i64::try_from(k).ok()?)
+        })
+    }
+
+    fn catalan(&self, start: u64, count: usize) -> Vec<i64> {
+        step_terms(1, start, count, |value, k| {
+            // C(k) = C(k-1) * 2(2k-1) / (k+1), the product only fits in an i128
+            let next = *value as i128 * (2 * (2 * k as i128 - 1)) / (k as i128 + 1);
+            i64::try_from(next).ok()
+        })
+    }
+
+    fn to_decimal(&self, value: &i64) -> String {
+        value.to_string()
+    }
+
+    fn decimal_len(&self, _value: &i64) -> usize {
+        20
+    }
+}
+
+impl Arithmetic for Modular {
+    type Value = u64;
+
+    fn of_i64(&self, value: i64) -> Option<u64> {
+        Some((value as i128).rem_euclid(self.0 as i128) as u64)
+    }
+
+    fn add(&self, a: &u64, b: &u64) -> Option<u64> {
+        Some(((*a as u128 + *b as u128) % self.0 as u128) as u64)
+    }
+
+    fn mul(&self, a: &u64, b: &u64) -> Option<u64> {
+        Some((*a as u128 * *b as u128 % self.0 as u128) as u64)
+    }
+
+    fn fibonacci_pair(&self, n: u64) -> Option<(u64, u64)> {
+        Some(fibonacci_pair_mod(n, self.0))
+    }
+
+    fn factorials(&self, start: u64, count: usize) -> Vec<u64> {
+        step_terms(1 % self.0, start, count, |value, k| {
+            self.mul(value, &(k % self.0))
+        })
+    }
+
+    fn catalan(&self, start: u64, count: usize) -> Vec<u64> {
+        // Dividing by k+1 needs an inverse, which only exists for the part coprime to the
+        // modulus. The primes shared with it are kept as exponents instead, only those up to
+        // the largest factor 2(2k-1) can ever show up.
+        let last = start + count as u64;
+        let primes = prime_factors_up_to(self.0, 4 * last);
+        let first = ModularCatalan {
+            unit: 1 % self.0,
+            exponents: vec![0; primes.len()],
+        };
+        let states = step_terms(first, start, count, |state, k| {
+            let mut next = state.clone();
+            let numerator = next.split(&primes, 2 * (2 * k - 1), 1);
+            next.unit = self.mul(&next.unit, &(numerator % self.0))?;
+            let denominator = next.split(&primes, k + 1, -1);
+            next.unit = self.mul(&next.unit, &mod_inverse(denominator, self.0))?;
+            Some(next)
+        });
+
+        states
+            .iter()
+            .map(|state| {
+                primes
+                    .iter()
+                    .zip(&state.exponents)
+                    .fold(state.unit, |value, (&p, &e)| {
+                        (value as u128 * pow_mod(p, e, self.0) as u128 % self.0 as u128) as u64
+                    })
+            })
+            .collect()
+    }
+
+    fn to_decimal(&self, value: &u64) -> String {
+        value.to_string()
+    }
+
+    fn decimal_len(&self, _value: &u64) -> usize {
+        20
+    }
+}
+
+impl Arithmetic for Exact {
+    type Value = IBig;
+
+    fn of_i64(&self, value: i64) -> Option<IBig> {
+        Some(IBig::from(value))
+    }
+
+    fn add(&self, a: &IBig, b: &IBig) -> Option<IBig> {
+        Some(a + b)
+    }
+
+    fn mul(&self, a: &IBig, b: &IBig) -> Option<IBig> {
+        Some(a * b)
+    }
+
+    fn fibonacci_pair(&self, n: u64) -> Option<(IBig, IBig)> {
+        let (a, b) = fibonacci_pair_big(n);
+        Some((IBig::from(a), IBig::from(b)))
+    }
+
+    fn factorials(&self, start: u64, count: usize) -> Vec<IBig> {
+        factorials(start, count)
+            .into_iter()
+            .map(IBig::from)
+            .collect()
+    }
+
+    fn catalan(&self, start: u64, count: usize) -> Vec<IBig> {
+        catalan(start, count).into_iter().map(IBig::from).collect()
+    }
+
+    fn to_decimal(&self, value: &IBig) -> String {
+        value.to_string()
+    }
+
+    fn decimal_len(&self, value: &IBig) -> usize {
+        // log10(2) < 0.302, plus the sign and rounding
+        let (_, words) = value.as_sign_words();
+        std::mem::size_of_val(words) * 8 * 302 / 1000 + 2
+    }
+}
+
+/// Largest `n` whose Fibonacci number fits in a `u64`
+pub const MAX_FIBONACCI_U64_N: u64 = 93;
+
+/// F(n) in `u64`, `None` once it no longer fits.
+pub fn fibonacci_u64(n: u64) -> Option<u64> {
+    if n > MAX_FIBONACCI_U64_N {
+        return None;
+    }
+    if n == 0 {
+        return Some(0);
+    }
+
+    let mut prev = 0u64;
+    let mut curr = 1u64;
+    for _ in 1..n {
+        let next = prev + curr;
+        prev = curr;
+        curr = next;
+    }
+    Some(curr)
+}
+
+/// `(F(n), F(n + 1))` exactly, by fast doubling:
+/// F(2k) = F(k) * (2F(k+1) - F(k)) and F(2k+1) = F(k)^2 + F(k+1)^2.
+pub fn fibonacci_pair_big(n: u64) -> (UBig, UBig) {
+    let mut a = UBig::ZERO;
+    let mut b = UBig::ONE;
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        let c = &a * (&b * 2u8 - &a);
+        let d = a.sqr() + b.sqr();
+        if (n >> bit) & 1 == 0 {
+            (a, b) = (c, d);
+        } else {
+            b = &c + &d;
+            a = d;
+        }
+    }
+    (a, b)
+}
+
+/// `(F(n) mod m, F(n + 1) mod m)` by fast doubling, in O(log n) steps for any `n`.
+pub fn fibonacci_pair_mod(n: u64, m: u64) -> (u64, u64) {
+    let m = m as u128;
+    let mut a = 0u128;
+    let mut b = 1 % m;
+    for bit in (0..u64::BITS - n.leading_zeros()).rev() {
+        let c = a * ((2 * b + m - a) % m) % m;
+        let d = (a * a % m + b * b % m) % m;
+        if (n >> bit) & 1 == 0 {
+            (a, b) = (c, d);
+        } else {
+            (a, b) = (d, (c + d) % m);
+        }
+    }
+    (a as u64, b as u64)
+}
+
+/// Square matrix stored row by row.
+#[derive(Debug, Clone)]
+struct Matrix<V> {
+    size: usize,
+    cells: Vec<V>,
+}
+
+impl<V: Clone> Matrix<V> {
+    fn identity<A: Arithmetic<Value = V>>(ar: &A, size: usize) -> Option<Self> {
+        let (zero, one) = (ar.of_i64(0)?, ar.of_i64(1)?);
+        let cells = (0..size * size)
+            .map(|i| {
+                if i / size == i % size {
+                    one.clone()
+                } else {
+                    zero.clone()
+                }
+            })
+            .collect();
+        Some(Self { size, cells })
+    }
+
+    fn mul<A: Arithmetic<Value = V>>(&self, ar: &A, other: &Self) -> Option<Self> {
+        let size = self.size;
+        let mut cells = Vec::with_capacity(size * size);
+        for row in 0..size {
+            for col in 0..size {
+                let mut sum = ar.of_i64(0)?;
+                for k in 0..size {
+                    let product =
+                        ar.mul(&self.cells[row * size + k], &other.cells[k * size + col])?;
+                    sum = ar.add(&sum, &product)?;
+                }
+                cells.push(sum);
+            }
+        }
+        Some(Self { size, cells })
+    }
+
+    fn pow<A: Arithmetic<Value = V>>(&self, ar: &A, mut exp: u64) -> Option<Self> {
+        let mut result = Self::identity(ar, self.size)?;
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(ar, &base)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.mul(ar, &base)?;
+            }
+        }
+        Some(result)
+    }
+}
+
+/// Terms `start..start + count` of a(n) = c1 a(n-1) + ... + ck a(n-k).
+///
+/// `initial` holds a(0)..a(k-1) and must be as long as `coefficients`. The first terms are
+/// found by raising the companion matrix to the power `start`, the rest by stepping the
+/// recurrence. Stops early when a term isn't representable.
+pub fn linear_recurrence<A: Arithmetic>(
+    ar: &A,
+    coefficients: &[i64],
+    initial: &[i64],
+    start: u64,
+    count: usize,
+) -> Vec<A::Value> {
+    let order = coefficients.len();
+    let mut terms = Vec::with_capacity(count);
+    if order == 0 || initial.len() != order || count == 0 {
+        return terms;
+    }
+
+    let Some(window) = recurrence_window(ar, coefficients, initial, start) else {
+        return terms;
+    };
+    let Some(coefficients) = coefficients
+        .iter()
+        .map(|&c| ar.of_i64(c))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return terms;
+    };
+
+    // `window` holds a(start)..a(start + k - 1), oldest first
+    let mut window = std::collections::VecDeque::from(window);
+    while terms.len() < count {
+        terms.push(window[0].clone());
+        if terms.len() == count {
+            break;
+        }
+
+        let mut next = match ar.of_i64(0) {
+            Some(zero) => zero,
+            None => break,
+        };
+        let mut ok = true;
+        for (i, coefficient) in coefficients.iter().enumerate() {
+            match ar
+                .mul(coefficient, &window[order - 1 - i])
+                .and_then(|product| ar.add(&next, &product))
+            {
+                Some(sum) => next = sum,
+                None => {
+                    ok = false;
+                    break;
+                }
+            }
+        }
+        if !ok {
+            // The terms still in the window are valid, only the ones after them aren't
+            terms.extend(window.iter().skip(1).take(count - terms.len()).cloned());
+            break;
+        }
+        window.pop_front();
+        window.push_back(next);
+    }
+
+    terms
+}
+
+/// a(start)..a(start + k - 1), oldest first.
+fn recurrence_window<A: Arithmetic>(
+    ar: &A,
+    coefficients: &[i64],
+    initial: &[i64],
+    start: u64,
+) -> Option<Vec<A::Value>> {
+    let order = coefficients.len();
+    let initial: Vec<A::Value> = initial
+        .iter()
+        .map(|&v| ar.of_i64(v))
+        .collect::<Option<_>>()?;
+    if start == 0 {
+        return Some(initial);
+    }
+
+    // Companion matrix mapping (a(n+k-1), .., a(n)) to (a(n+k), .., a(n+1))
+    let (zero, one) = (ar.of_i64(0)?, ar.of_i64(1)?);
+    let mut cells = Vec::with_capacity(order * order);
+    for &coefficient in coefficients {
+        cells.push(ar.of_i64(coefficient)?);
+    }
+    for row in 1..order {
+        for col in 0..order {
+            cells.push(if col == row - 1 {
+                one.clone()
+            } else {
+                zero.clone()
+            });
+        }
+    }
+    let power = Matrix { size: order, cells }.pow(ar, start)?;
+
+    // Newest first, matching the matrix layout
+    let state: Vec<&A::Value> = initial.iter().rev().collect();
+    let mut window = Vec::with_capacity(order);
+    for row in (0..order).rev() {
+        let mut sum = zero.clone();
+        for (col, value) in state.iter().enumerate() {
+            sum = ar.add(&sum, &ar.mul(&power.cells[row * order + col], value)?)?;
+        }
+        window.push(sum);
+    }
+    Some(window)
+}
+
+/// Terms `start..start + count` of the Lucas numbers, through the Fibonacci fast path:
+/// L(n) = 2F(n-1) + F(n).
+pub fn lucas<A: Arithmetic>(ar: &A, start: u64, count: usize) -> Vec<A::Value> {
+    let first = if start == 0 {
+        ar.of_i64(2).zip(ar.of_i64(1))
+    } else {
+        ar.fibonacci_pair(start - 1).and_then(|(a, b)| {
+            // L(n) = 2a + b and L(n+1) = a + 3b
+            let twice_a = ar.add(&a, &a)?;
+            let twice_b = ar.add(&b, &b)?;
+            Some((ar.add(&twice_a, &b)?, ar.add(&ar.add(&a, &b)?, &twice_b)?))
+        })
+    };
+    let Some((a, b)) = first else {
+        return Vec::new();
+    };
+    step_pair(ar, a, b, count)
+}
+
+/// Terms `start..start + count` of the Fibonacci numbers.
+pub fn fibonacci<A: Arithmetic>(ar: &A, start: u64, count: usize) -> Vec<A::Value> {
+    match ar.fibonacci_pair(start) {
+        Some((a, b)) => step_pair(ar, a, b, count),
+        None => Vec::new(),
+    }
+}
+
+/// Continues x(n+2) = x(n+1) + x(n) from its first two terms.
+fn step_pair<A: Arithmetic>(
+    ar: &A,
+    mut a: A::Value,
+    mut b: A::Value,
+    count: usize,
+) -> Vec<A::Value> {
+    let mut terms = Vec::with_capacity(count);
+    while terms.len() < count {
+        terms.push(a.clone());
+        let Some(next) = ar.add(&a, &b) else {
+            if terms.len() < count {
+                terms.push(b);
+            }
+            break;
+        };
+        (a, b) = (b, next);
+    }
+    terms
+}
+
+/// Product of `lo..=hi`, splitting the range in halves so the operands stay balanced.
+fn product(lo: u64, hi: u64) -> UBig {
+    if lo > hi {
+        return UBig::ONE;
+    }
+    if hi - lo < 16 {
+        return (lo..=hi).fold(UBig::ONE, |acc, k| acc * UBig::from(k));
+    }
+    let mid = lo + (hi - lo) / 2;
+    product(lo, mid) * product(mid + 1, hi)
+}
+
+/// Terms `start..start + count` of a sequence defined by a(0) and a(k) = `step`(a(k-1), k),
+/// computed from a(0) and stopping before the first term `step` can't represent.
+fn step_terms<V: Clone>(
+    first: V,
+    start: u64,
+    count: usize,
+    step: impl Fn(&V, u64) -> Option<V>,
+) -> Vec<V> {
+    let mut terms = Vec::with_capacity(count);
+    let mut value = first;
+    for k in 0..start + count as u64 {
+        if k > 0 {
+            let Some(next) = step(&value, k) else {
+                break;
+            };
+            value = next;
+        }
+        if k >= start {
+            terms.push(value.clone());
+        }
+    }
+    terms
+}
+
+/// A Catalan number modulo `m`, as a unit coprime to `m` times powers of the primes of `m`.
+#[derive(Clone)]
+struct ModularCatalan {
+    unit: u64,
+    exponents: Vec<i64>,
+}
+
+impl ModularCatalan {
+    /// Moves the factors `primes` out of `value` into the exponents, scaled by `sign`, and
+    /// returns the rest.
+    fn split(&mut self, primes: &[u64], mut value: u64, sign: i64) -> u64 {
+        for (&p, exponent) in primes.iter().zip(&mut self.exponents) {
+            while value.is_multiple_of(p) {
+                value /= p;
+                *exponent += sign;
+            }
+        }
+        value
+    }
+}
+
+/// Distinct prime factors of `m` that are at most `bound`, by trial division.
+fn prime_factors_up_to(mut m: u64, bound: u64) -> Vec<u64> {
+    let mut primes = Vec::new();
+    let mut d = 2;
+    while d <= bound && m > 1 {
+        if d.saturating_mul(d) > m {
+            // What's left is prime
+            if m <= bound {
+                primes.push(m);
+            }
+            break;
+        }
+        if m.is_multiple_of(d) {
+            primes.push(d);
+            while m.is_multiple_of(d) {
+                m /= d;
+            }
+        }
+        d += 1;
+    }
+    primes
+}
+
+/// `a^-1 mod m` for `a` coprime to `m`, by the extended Euclidean algorithm.
+fn mod_inverse(a: u64, m: u64) -> u64 {
+    let (mut r0, mut r1) = (m as i128, (a % m) as i128);
+    let (mut t0, mut t1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    t0.rem_euclid(m as i128) as u64
+}
+
+/// `base^exp mod m` by square and multiply, with `exp >= 0`.
+fn pow_mod(base: u64, exp: i64, m: u64) -> u64 {
+    let m = m as u128;
+    let (mut base, mut exp, mut result) = (base as u128 % m, exp as u64, 1 % m);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+/// Exact terms `start..start + count` of n!.
+pub fn factorials(start: u64, count: usize) -> Vec<UBig> {
+    let mut terms = Vec::with_capacity(count);
+    let mut value = product(1, start);
+    for k in start..start + count as u64 {
+        if k > start {
+            value *= UBig::from(k);
+        }
+        terms.push(value.clone());
+    }
+    terms
+}
+
+/// Exact terms `start..start + count` of the Catalan numbers, C(n) = (2n)! / (n! (n+1)!).
+pub fn catalan(start: u64, count: usize) -> Vec<UBig> {
+    let mut terms = Vec::with_capacity(count);
+    let mut value = product(start + 2, 2 * start) / product(1, start);
+    for k in start..start + count as u64 {
+        if k > start {
+            // C(k) = C(k-1) * 2(2k-1) / (k+1), which is always exact
+            value = value * UBig::from(2 * (2 * k - 1)) / UBig::from(k + 1);
+        }
+        terms.push(value.clone());
+    }
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_recurrence_matches_fibonacci() {
+        let terms = linear_recurrence(&Checked, &[1, 1], &[0, 1], 0, 94);
+        let expected = fibonacci(&Checked, 0, 94);
+        assert_eq!(terms, expected);
+        // F(92) is the last one that fits in an i64
+        assert_eq!(terms.len(), 93);
+
+        let terms = linear_recurrence(&Modular(1_000_000_007), &[1, 1], &[0, 1], 1_000, 3);
+        assert_eq!(terms, fibonacci(&Modular(1_000_000_007), 1_000, 3));
+
+        let terms = linear_recurrence(&Exact, &[1, 1], &[0, 1], 300, 2);
+        assert_eq!(terms, fibonacci(&Exact, 300, 2));
+    }
+
+    #[test]
+    fn test_negative_coefficients() {
+        // a(n) = 2a(n-1) - a(n-2) counts up from its initial terms
+        let terms = linear_recurrence(&Checked, &[2, -1], &[5, 6], 1_000_000, 2);
+        assert_eq!(terms, vec![1_000_005, 1_000_006]);
+
+        let terms = linear_recurrence(&Modular(7), &[2, -1], &[5, 6], 10, 1);
+        assert_eq!(terms, vec![15 % 7]);
+    }
+
+    #[test]
+    fn test_lucas() {
+        let expected = [2, 1, 3, 4, 7, 11, 18, 29];
+        assert_eq!(lucas(&Checked, 0, 8), expected);
+        assert_eq!(lucas(&Checked, 3, 5), expected[3..]);
+        assert_eq!(
+            lucas(&Exact, 50, 1),
+            linear_recurrence(&Exact, &[1, 1], &[2, 1], 50, 1)
+        );
+    }
+
+    #[test]
+    fn test_factorials_and_catalan() {
+        let factorials: Vec<String> = factorials(0, 6).iter().map(UBig::to_string).collect();
+        assert_eq!(factorials, ["1", "1", "2", "6", "24", "120"]);
+
+        let catalan: Vec<String> = catalan(0, 8).iter().map(UBig::to_string).collect();
+        assert_eq!(catalan, ["1", "1", "2", "5", "14", "42", "132", "429"]);
+        assert_eq!(super::catalan(30, 1)[0].to_string(), "3814986502092304");
+    }
+
+    #[test]
+    fn test_factorials_and_catalan_without_big_ints() {
+        // 20! is the last factorial and C(35) the last Catalan number that fit in an i64
+        let exact = factorials(0, 21);
+        let checked = Checked.factorials(0, 30);
+        assert_eq!(checked.len(), 21);
+        assert!(checked
+            .iter()
+            .zip(&exact)
+            .all(|(&a, b)| UBig::from(a as u64) == *b));
+        assert_eq!(Checked.catalan(30, 10).len(), 6);
+        assert!(Checked.factorials(100_000, 2).is_empty());
+
+        // Composite moduli share primes with the factors Catalan numbers are divided by
+        for m in [
+            1,
+            7,
+            1_000_000,
+            2 * 3 * 5 * 7 * 11 * 13,
+            1_000_000_007,
+            u64::MAX,
+        ] {
+            let reduce = |terms: Vec<UBig>| -> Vec<u64> {
+                terms
+                    .iter()
+                    .map(|term| u64::try_from(term % UBig::from(m)).unwrap())
+                    .collect()
+            };
+            assert_eq!(
+                Modular(m).factorials(40, 30),
+                reduce(factorials(40, 30)),
+                "{m}"
+            );
+            assert_eq!(Modular(m).catalan(0, 200), reduce(catalan(0, 200)), "{m}");
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{cmd::Command, CommandResult, PanicDetails};
+use crate::{cmd::Command, error::ErrorCode, CommandResult, PanicDetails};
 use std::sync::Arc;
 use tracing::error;
 
@@ -18,6 +18,8 @@ pub fn handle_panic(err: Box<dyn std::any::Any + Send>, cmd: Arc<Command>) -> St
     let panic_result = CommandResult {
         res: None,
         error: None,
+        error_code: Some(ErrorCode::Panic),
+        field_errors: None,
         panic: true,
         panic_details: Some(PanicDetails {
             cmd: cmd_name,
@@ -26,6 +28,6 @@ pub fn handle_panic(err: Box<dyn std::any::Any + Send>, cmd: Arc<Command>) -> St
     };
 
     serde_json::to_string(&panic_result).unwrap_or_else(|_| {
-        r#"{"res":null,"error":"Failed to serialize panic result","panic":true,"panic_details":null}"#.to_string()
+        r#"{"res":null,"error":"Failed to serialize panic result","error_code":"panic","field_errors":null,"panic":true,"panic_details":null}"#.to_string()
     })
 }
@@ -0,0 +1,146 @@
+//! Progress of long running commands, polled with the `progress` command.
+//!
+//! Commands that support it take a caller chosen `progress_id` and report the amount of work
+//! done under it while they run.
+
+use eyre::{eyre, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use validator::Validate;
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+/// Largest number of finished tasks kept around for a last poll
+const MAX_FINISHED_TASKS: usize = 64;
+
+lazy_static::lazy_static! {
+    static ref TASKS: Mutex<HashMap<String, Arc<TaskState>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Default)]
+struct TaskState {
+    processed: AtomicU64,
+    total: AtomicU64,
+    done: AtomicBool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct ProgressInput {
+    #[validate(length(min = 1))]
+    pub id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct Progress {
+    pub id: String,
+    /// Units of work done so far, bytes for file commands
+    pub processed: u64,
+    pub total: u64,
+    /// `processed / total`, 1.0 once done
+    pub fraction: f64,
+    pub done: bool,
+}
+
+/// Reports the progress of a task while it is alive and marks it done when dropped.
+pub struct ProgressHandle {
+    state: Option<Arc<TaskState>>,
+}
+
+impl ProgressHandle {
+    /// Starts tracking `id`, replacing any previous task with the same id.
+    ///
+    /// Without an id the handle is a no-op, so commands can report progress unconditionally.
+    pub fn start(id: Option<&str>, total: u64) -> Self {
+        let Some(id) = id else {
+            return Self { state: None };
+        };
+
+        let state = Arc::new(TaskState::default());
+        state.total.store(total, Ordering::Relaxed);
+        if let Ok(mut tasks) = TASKS.lock() {
+            prune_finished(&mut tasks);
+            tasks.insert(id.to_string(), state.clone());
+        }
+        Self { state: Some(state) }
+    }
+
+    pub fn advance(&self, amount: u64) {
+        if let Some(state) = &self.state {
+            state.processed.fetch_add(amount, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for ProgressHandle {
+    fn drop(&mut self) {
+        if let Some(state) = &self.state {
+            state.done.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+fn prune_finished(tasks: &mut HashMap<String, Arc<TaskState>>) {
+    let finished = tasks
+        .values()
+        .filter(|state| state.done.load(Ordering::Relaxed))
+        .count();
+    if finished >= MAX_FINISHED_TASKS {
+        tasks.retain(|_, state| !state.done.load(Ordering::Relaxed));
+    }
+}
+
+pub fn progress(input: &ProgressInput) -> Result<Progress> {
+    let state = TASKS
+        .lock()
+        .map_err(|_| eyre!("progress lock poisoned"))?
+        .get(&input.id)
+        .cloned()
+        .ok_or_else(|| eyre!("no task with progress id `{}`", input.id))?;
+
+    let processed = state.processed.load(Ordering::Relaxed);
+    let total = state.total.load(Ordering::Relaxed);
+    let done = state.done.load(Ordering::Relaxed);
+    let fraction = match (done, total) {
+        (true, _) => 1.0,
+        (false, 0) => 0.0,
+        (false, total) => (processed as f64 / total as f64).min(1.0),
+    };
+
+    Ok(Progress {
+        id: input.id.clone(),
+        processed,
+        total,
+        fraction,
+        done,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_lifecycle() {
+        let input = ProgressInput {
+            id: "test_progress_lifecycle".to_string(),
+        };
+        let handle = ProgressHandle::start(Some(&input.id), 200);
+        handle.advance(50);
+
+        let status = progress(&input).unwrap();
+        assert_eq!(status.processed, 50);
+        assert_eq!(status.fraction, 0.25);
+        assert!(!status.done);
+
+        drop(handle);
+        assert!(progress(&input).unwrap().done);
+    }
+}
@@ -0,0 +1,674 @@
+//! Opt-in recording of `rust_execute` traffic and deterministic replay of recorded sessions.
+//!
+//! A session file is JSON Lines: a [`SessionHeader`] followed by one [`SessionEntry`] per
+//! executed command. Sensitive fields are redacted before anything touches the disk, and
+//! commands that had fields redacted are skipped on replay since they can't be reproduced.
+
+use crate::cmd::{execute_nested, Command};
+use crate::data_dir;
+use crate::panic_handler::handle_panic;
+use crate::CommandResult;
+use eyre::{eyre, Result, WrapErr};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{error, info};
+use validator::Validate;
+
+#[cfg(feature = "ts-rs")]
+use ts_rs::TS;
+
+pub const SESSION_VERSION: u32 = 1;
+
+/// Field names that are always redacted, regardless of the recorder configuration.
+pub const DEFAULT_REDACTED_FIELDS: &[&str] = &[
+    "password",
+    "passphrase",
+    "pin",
+    "secret",
+    "token",
+    "key",
+    "private_key",
+    "private_key_bytes",
+    "secret_key",
+    "otpauth_uri",
+];
+
+/// Fields that are only sensitive for particular commands, such as plaintexts, redacted in both
+/// the params and the result of those commands.
+const COMMAND_REDACTED_FIELDS: &[(&str, &[&str])] = &[
+    ("derive_key", &["bytes"]),
+    ("encrypt", &["data"]),
+    ("decrypt", &["data", "bytes"]),
+    ("seal", &["data"]),
+    ("open", &["data", "bytes"]),
+    ("key_agreement", &["bytes"]),
+    ("random", &["values"]),
+    ("totp_generate", &["code"]),
+    ("totp_verify", &["code"]),
+    ("hotp_generate", &["code"]),
+];
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Result fields that legitimately differ between runs and are ignored when diffing.
+const NON_DETERMINISTIC_FIELDS: &[&str] = &["computation_time_ms"];
+
+/// Commands that manage sessions themselves and are never recorded or replayed.
+const SESSION_COMMANDS: &[&str] = &["start_recording", "stop_recording", "replay_session"];
+
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct StartRecordingInput {
+    /// Path relative to the data directory
+    #[validate(length(min = 1))]
+    pub path: String,
+    /// Extra field names to redact on top of [`DEFAULT_REDACTED_FIELDS`]
+    #[serde(default)]
+    pub redact_fields: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct RecordingStatus {
+    pub path: Option<String>,
+    pub recorded_commands: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Validate)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct ReplaySessionInput {
+    /// Path relative to the data directory
+    #[validate(length(min = 1))]
+    pub path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct ReplaySessionResult {
+    pub total: usize,
+    pub matched: usize,
+    pub mismatched: usize,
+    pub skipped: usize,
+    pub entries: Vec<ReplayEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct ReplayEntry {
+    pub index: usize,
+    pub cmd: String,
+    pub status: ReplayStatus,
+    pub diffs: Vec<ResultDiff>,
+    pub recorded_duration_us: u64,
+    pub replayed_duration_us: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub enum ReplayStatus {
+    #[serde(rename = "matched")]
+    Matched,
+    #[serde(rename = "mismatched")]
+    Mismatched,
+    #[serde(rename = "skipped")]
+    Skipped,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-rs", derive(TS))]
+#[cfg_attr(feature = "ts-rs", ts(export))]
+pub struct ResultDiff {
+    /// JSON pointer to the differing value within the command result
+    pub path: String,
+    #[cfg_attr(feature = "ts-rs", ts(type = "any"))]
+    pub recorded: Value,
+    #[cfg_attr(feature = "ts-rs", ts(type = "any"))]
+    pub replayed: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionHeader {
+    pub version: u32,
+    pub redact_fields: Vec<String>,
+    pub started_at_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionEntry {
+    /// The command envelope, with sensitive fields redacted
+    pub cmd: Value,
+    /// The `CommandResult`, with its `res` payload expanded and redacted
+    pub result: Value,
+    pub redacted: bool,
+    pub duration_us: u64,
+    pub recorded_at_ms: u64,
+}
+
+struct Recorder {
+    path: String,
+    writer: BufWriter<File>,
+    redact_fields: Vec<String>,
+    recorded_commands: usize,
+}
+
+pub fn start_recording(input: &StartRecordingInput) -> Result<RecordingStatus> {
+    let path = data_dir::resolve("path", &input.path)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&path)
+        .wrap_err_with(|| format!("failed to create session file {}", input.path))?;
+
+    let mut redact_fields: Vec<String> = DEFAULT_REDACTED_FIELDS
+        .iter()
+        .map(|field| field.to_string())
+        .collect();
+    redact_fields.extend(input.redact_fields.iter().map(|f| f.to_lowercase()));
+    redact_fields.sort();
+    redact_fields.dedup();
+
+    let mut writer = BufWriter::new(file);
+    let header = SessionHeader {
+        version: SESSION_VERSION,
+        redact_fields: redact_fields.clone(),
+        started_at_ms: unix_time_ms(),
+    };
+    write_line(&mut writer, &header)?;
+
+    let mut recorder = RECORDER
+        .lock()
+        .map_err(|_| eyre!("session recorder lock poisoned"))?;
+    if let Some(previous) = recorder.as_ref() {
+        info!("replacing active session recording {}", previous.path);
+    }
+    *recorder = Some(Recorder {
+        path: input.path.clone(),
+        writer,
+        redact_fields,
+        recorded_commands: 0,
+    });
+
+    info!("recording session to {}", input.path);
+    Ok(RecordingStatus {
+        path: Some(input.path.clone()),
+        recorded_commands: 0,
+    })
+}
+
+pub fn stop_recording() -> Result<RecordingStatus> {
+    let mut recorder = RECORDER
+        .lock()
+        .map_err(|_| eyre!("session recorder lock poisoned"))?;
+
+    let Some(mut active) = recorder.take() else {
+        return Ok(RecordingStatus {
+            path: None,
+            recorded_commands: 0,
+        });
+    };
+
+    active
+        .writer
+        .flush()
+        .wrap_err("failed to flush session file")?;
+
+    Ok(RecordingStatus {
+        path: Some(active.path),
+        recorded_commands: active.recorded_commands,
+    })
+}
+
+/// Appends an executed command to the active session, if any.
+///
+/// Recording failures are logged rather than surfaced so they never affect command results.
+pub(crate) fn record(cmd_str: &str, res_str: &str, duration: Duration) {
+    let Ok(mut recorder) = RECORDER.lock() else {
+        return;
+    };
+    let Some(active) = recorder.as_mut() else {
+        return;
+    };
+
+    let mut cmd = serde_json::from_str::<Value>(cmd_str)
+        .unwrap_or_else(|_| Value::String(cmd_str.to_string()));
+    let name = cmd_name(&cmd);
+    if SESSION_COMMANDS.contains(&name.as_str()) {
+        return;
+    }
+
+    let mut redact_fields = active.redact_fields.clone();
+    if let Some((_, fields)) = COMMAND_REDACTED_FIELDS.iter().find(|(cmd, _)| *cmd == name) {
+        redact_fields.extend(fields.iter().map(|field| field.to_string()));
+    }
+    let redacted = redact(&mut cmd, &redact_fields);
+    let mut result = normalize_result(res_str);
+    redact(&mut result, &redact_fields);
+
+    let entry = SessionEntry {
+        cmd,
+        result,
+        redacted,
+        duration_us: duration.as_micros() as u64,
+        recorded_at_ms: unix_time_ms(),
+    };
+
+    match write_line(&mut active.writer, &entry) {
+        Ok(()) => active.recorded_commands += 1,
+        Err(err) => error!("failed to record command: {:#}", err),
+    }
+}
+
+/// Re-executes every command of a recorded session and diffs the results.
+pub async fn replay_session(
+    input: &ReplaySessionInput,
+    logs: &'static Mutex<Vec<String>>,
+) -> Result<ReplaySessionResult> {
+    let path = data_dir::resolve("path", &input.path)?;
+    let contents = tokio::fs::read_to_string(&path)
+        .await
+        .wrap_err_with(|| format!("failed to read session file {}", input.path))?;
+
+    let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+    let header_line = lines
+        .next()
+        .ok_or_else(|| eyre!("session file {} is empty", input.path))?;
+    let header = serde_json::from_str::<SessionHeader>(header_line)
+        .wrap_err("failed to parse session header")?;
+    if header.version != SESSION_VERSION {
+        return Err(eyre!(
+            "unsupported session version {} (expected {})",
+            header.version,
+            SESSION_VERSION
+        ));
+    }
+
+    let mut entries = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let recorded = serde_json::from_str::<SessionEntry>(line)
+            .wrap_err_with(|| format!("failed to parse session entry {index}"))?;
+        entries.push(replay_entry(index, recorded, &header, logs).await?);
+    }
+
+    let count = |status: ReplayStatus| entries.iter().filter(|e| e.status == status).count();
+    Ok(ReplaySessionResult {
+        total: entries.len(),
+        matched: count(ReplayStatus::Matched),
+        mismatched: count(ReplayStatus::Mismatched),
+        skipped: count(ReplayStatus::Skipped),
+        entries,
+    })
+}
+
+async fn replay_entry(
+    index: usize,
+    recorded: SessionEntry,
+    header: &SessionHeader,
+    logs: &'static Mutex<Vec<String>>,
+) -> Result<ReplayEntry> {
+    let cmd_name = cmd_name(&recorded.cmd);
+    let mut entry = ReplayEntry {
+        index,
+        cmd: cmd_name,
+        status: ReplayStatus::Skipped,
+        diffs: Vec::new(),
+        recorded_duration_us: recorded.duration_us,
+        replayed_duration_us: 0,
+    };
+
+    if recorded.redacted {
+        return Ok(entry);
+    }
+
+    let start = std::time::Instant::now();
+    match serde_json::from_value::<Command>(recorded.cmd.clone()) {
+        Ok(cmd) => {
+            let res_str = execute_isolated(cmd, logs).await?;
+            let mut replayed = normalize_result(&res_str);
+            redact(&mut replayed, &header.redact_fields);
+
+            diff_values("", &recorded.result, &replayed, &mut entry.diffs);
+        }
+        // The command still fails to parse, which only matches if the recording failed too
+        Err(err) if recorded.result["error"].is_null() => entry.diffs.push(ResultDiff {
+            path: "/error".to_string(),
+            recorded: Value::Null,
+            replayed: Value::String(err.to_string()),
+        }),
+        Err(_) => {}
+    }
+    entry.replayed_duration_us = start.elapsed().as_micros() as u64;
+
+    entry.status = if entry.diffs.is_empty() {
+        ReplayStatus::Matched
+    } else {
+        ReplayStatus::Mismatched
+    };
+
+    Ok(entry)
+}
+
+/// Executes a command on its own task so that panics are reported like `rust_execute` does.
+async fn execute_isolated(cmd: Command, logs: &'static Mutex<Vec<String>>) -> Result<String> {
+    let cmd = Arc::new(cmd);
+    match tokio::spawn(execute_nested(cmd.clone(), logs)).await {
+        Ok(cmd_res) => serde_json::to_string(&CommandResult::from_exec(cmd_res))
+            .wrap_err("failed to serialize replayed command result"),
+        Err(err) if err.is_panic() => Ok(handle_panic(err.into_panic(), cmd)),
+        Err(err) => Err(eyre!("replayed command was cancelled: {err}")),
+    }
+}
+
+fn cmd_name(cmd: &Value) -> String {
+    cmd.get("cmd")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// Parses a serialized `CommandResult`, expanding the `res` payload into structured JSON.
+fn normalize_result(res_str: &str) -> Value {
+    let Ok(mut result) = serde_json::from_str::<Value>(res_str) else {
+        return Value::String(res_str.to_string());
+    };
+
+    if let Some(Value::String(inner)) = result.get("res") {
+        if let Ok(inner) = serde_json::from_str::<Value>(inner) {
+            result["res"] = inner;
+        }
+    }
+
+    result
+}
+
+/// Replaces the values of sensitive object fields, returning whether anything was redacted.
+fn redact(value: &mut Value, fields: &[String]) -> bool {
+    match value {
+        Value::Object(map) => {
+            let mut redacted = false;
+            for (name, field) in map.iter_mut() {
+                if fields.iter().any(|f| f.eq_ignore_ascii_case(name)) {
+                    *field = Value::String(REDACTED.to_string());
+                    redacted = true;
+                } else {
+                    redacted |= redact(field, fields);
+                }
+            }
+            redacted
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .fold(false, |redacted, item| redact(item, fields) | redacted),
+        _ => false,
+    }
+}
+
+fn diff_values(path: &str, recorded: &Value, replayed: &Value, diffs: &mut Vec<ResultDiff>) {
+    match (recorded, replayed) {
+        (Value::Object(recorded), Value::Object(replayed)) => {
+            let mut keys: Vec<&String> = recorded.keys().chain(replayed.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                if NON_DETERMINISTIC_FIELDS.contains(&key.as_str()) {
+                    continue;
+                }
+                let field_path = format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+                diff_values(
+                    &field_path,
+                    recorded.get(key).unwrap_or(&Value::Null),
+                    replayed.get(key).unwrap_or(&Value::Null),
+                    diffs,
+                );
+            }
+        }
+        (Value::Array(recorded_items), Value::Array(replayed_items))
+            if recorded_items.len() == replayed_items.len() =>
+        {
+            for (i, (recorded, replayed)) in recorded_items.iter().zip(replayed_items).enumerate() {
+                diff_values(&format!("{path}/{i}"), recorded, replayed, diffs);
+            }
+        }
+        (recorded, replayed) if recorded != replayed => diffs.push(ResultDiff {
+            path: path.to_string(),
+            recorded: recorded.clone(),
+            replayed: replayed.clone(),
+        }),
+        _ => {}
+    }
+}
+
+fn write_line(writer: &mut BufWriter<File>, value: &impl Serialize) -> Result<()> {
+    serde_json::to_writer(&mut *writer, value).wrap_err("failed to serialize session line")?;
+    writer.write_all(b"\n")?;
+    writer.flush().wrap_err("failed to write session line")
+}
+
+fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_nested_fields() {
+        let fields = vec!["password".to_string(), "token".to_string()];
+        let mut value = json!({
+            "cmd": "login",
+            "params": {"user": "ada", "Password": "hunter2", "items": [{"token": "abc"}]}
+        });
+
+        assert!(redact(&mut value, &fields));
+        assert_eq!(value["params"]["user"], "ada");
+        assert_eq!(value["params"]["Password"], REDACTED);
+        assert_eq!(value["params"]["items"][0]["token"], REDACTED);
+    }
+
+    /// Serializes the tests that go through the global recorder
+    static RECORDER_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    fn record_entry(name: &str, cmd: Value, res: Value) -> SessionEntry {
+        let _guard = RECORDER_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = std::env::temp_dir();
+        crate::data_dir::set_data_dir(&crate::data_dir::SetDataDirInput {
+            path: dir.display().to_string(),
+        })
+        .unwrap();
+        let name = format!("test_session_{name}_{}.jsonl", std::process::id());
+        let path = dir.join(&name);
+        start_recording(&StartRecordingInput {
+            path: name,
+            redact_fields: vec![],
+        })
+        .unwrap();
+
+        let result = json!({"res": res.to_string(), "error": null, "panic": false});
+        record(
+            &cmd.to_string(),
+            &result.to_string(),
+            Duration::from_micros(10),
+        );
+        assert_eq!(stop_recording().unwrap().recorded_commands, 1);
+
+        let session = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let entry = session.lines().nth(1).unwrap();
+        serde_json::from_str(entry).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_session_paths_stay_in_data_dir() {
+        crate::data_dir::set_data_dir(&crate::data_dir::SetDataDirInput {
+            path: std::env::temp_dir().display().to_string(),
+        })
+        .unwrap();
+        static LOGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        for path in ["/tmp/session.jsonl", "../session.jsonl"] {
+            let err = start_recording(&StartRecordingInput {
+                path: path.to_string(),
+                redact_fields: vec![],
+            })
+            .unwrap_err();
+            assert_eq!(
+                crate::error::field_errors(&err).unwrap()[0].field,
+                "path",
+                "{path}"
+            );
+            let input = ReplaySessionInput {
+                path: path.to_string(),
+            };
+            let err = replay_session(&input, &LOGS).await.unwrap_err();
+            assert_eq!(
+                crate::error::field_errors(&err).unwrap()[0].field,
+                "path",
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_record_redacts_plaintexts() {
+        let entry = record_entry(
+            "encrypt",
+            json!({"cmd": "encrypt", "params": {"data": "attack at dawn", "key": "k"}}),
+            json!({"envelope": "ciphertext", "bytes": null}),
+        );
+        assert!(entry.redacted);
+        assert_eq!(entry.cmd["params"]["data"], REDACTED);
+        assert_eq!(entry.cmd["params"]["key"], REDACTED);
+        assert_eq!(entry.result["res"]["envelope"], "ciphertext");
+
+        let entry = record_entry(
+            "open",
+            json!({"cmd": "open", "params": {"sealed": "ciphertext", "private_key": "k"}}),
+            json!({"data": "", "bytes": [97, 116, 116, 97, 99, 107]}),
+        );
+        assert_eq!(entry.result["res"]["data"], REDACTED);
+        assert_eq!(entry.result["res"]["bytes"], REDACTED);
+
+        // Fields named like plaintexts stay readable for other commands
+        let entry = record_entry(
+            "hash_data",
+            json!({"cmd": "hash_data", "params": {"data": "hello"}}),
+            json!({"digests": []}),
+        );
+        assert!(!entry.redacted);
+        assert_eq!(entry.cmd["params"]["data"], "hello");
+    }
+
+    #[test]
+    fn test_record_redacts_private_key_bytes() {
+        let entry = record_entry(
+            "generate_keypair",
+            json!({"cmd": "generate_keypair", "params": {"output_encoding": "bytes"}}),
+            json!({"private_key": "", "private_key_bytes": [1, 2, 3], "public_key_bytes": [4, 5, 6]}),
+        );
+        assert_eq!(entry.result["res"]["private_key"], REDACTED);
+        assert_eq!(entry.result["res"]["private_key_bytes"], REDACTED);
+        assert_eq!(entry.result["res"]["public_key_bytes"], json!([4, 5, 6]));
+    }
+
+    #[test]
+    fn test_record_redacts_x25519_keys() {
+        let entry = record_entry(
+            "x25519_keypair",
+            json!({"cmd": "x25519_keypair", "params": {"output_encoding": "bytes"}}),
+            json!({"private_key": "", "private_key_bytes": [1, 2], "public_key_bytes": [3, 4]}),
+        );
+        assert_eq!(entry.result["res"]["private_key_bytes"], REDACTED);
+        assert_eq!(entry.result["res"]["public_key_bytes"], json!([3, 4]));
+
+        let entry = record_entry(
+            "key_agreement",
+            json!({"cmd": "key_agreement", "params": {"private_key": "a", "public_key": "b"}}),
+            json!({"key": "", "bytes": [5, 6], "hash": "sha256"}),
+        );
+        assert_eq!(entry.cmd["params"]["private_key"], REDACTED);
+        assert_eq!(entry.cmd["params"]["public_key"], "b");
+        assert_eq!(entry.result["res"]["key"], REDACTED);
+        assert_eq!(entry.result["res"]["bytes"], REDACTED);
+    }
+
+    #[test]
+    fn test_record_redacts_derived_key_bytes() {
+        let entry = record_entry(
+            "derive_key",
+            json!({"cmd": "derive_key", "params": {"password": "hunter2", "salt": "s"}}),
+            json!({"key": "", "bytes": [1, 2, 3], "algorithm": "pbkdf2"}),
+        );
+        assert_eq!(entry.result["res"]["key"], REDACTED);
+        assert_eq!(entry.result["res"]["bytes"], REDACTED);
+        assert_eq!(entry.result["res"]["algorithm"], "pbkdf2");
+    }
+
+    #[test]
+    fn test_record_redacts_random_values() {
+        let entry = record_entry(
+            "random",
+            json!({"cmd": "random", "params": {"mode": {"type": "bytes"}, "count": 3}}),
+            json!({"values": [7, 42, 255], "seeded": false, "computation_time_ms": 0}),
+        );
+        assert_eq!(entry.result["res"]["values"], REDACTED);
+        assert_eq!(entry.result["res"]["seeded"], false);
+    }
+
+    #[test]
+    fn test_record_redacts_otp_codes() {
+        let entry = record_entry(
+            "totp_generate",
+            json!({"cmd": "totp_generate", "params": {"secret": "JBSWY3DPEHPK3PXP"}}),
+            json!({"code": "123456", "counter": 1, "valid_for_secs": 30, "period": 30, "digits": 6}),
+        );
+        assert_eq!(entry.result["res"]["code"], REDACTED);
+        assert_eq!(entry.result["res"]["counter"], 1);
+
+        let entry = record_entry(
+            "totp_verify",
+            json!({"cmd": "totp_verify", "params": {"secret": "JBSWY3DPEHPK3PXP", "code": "123456"}}),
+            json!({"valid": true, "drift": 0, "counter": 1, "computation_time_ms": 0}),
+        );
+        assert_eq!(entry.cmd["params"]["code"], REDACTED);
+        assert_eq!(entry.result["res"]["valid"], true);
+
+        let entry = record_entry(
+            "hotp_generate",
+            json!({"cmd": "hotp_generate", "params": {"secret": "JBSWY3DPEHPK3PXP", "counter": 7}}),
+            json!({"code": "654321", "counter": 7, "digits": 6, "algorithm": "sha1"}),
+        );
+        assert_eq!(entry.result["res"]["code"], REDACTED);
+    }
+
+    #[test]
+    fn test_diff_ignores_timing() {
+        let recorded = json!({"res": {"sorted_numbers": [1, 2, 3], "computation_time_ms": 4}});
+        let replayed = json!({"res": {"sorted_numbers": [1, 3, 2], "computation_time_ms": 9}});
+
+        let mut diffs = Vec::new();
+        diff_values("", &recorded, &replayed, &mut diffs);
+        let paths: Vec<&str> = diffs.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["/res/sorted_numbers/1", "/res/sorted_numbers/2"]
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use std::io::{self, Write};
-use std::sync::{Arc, Mutex};
+use std::sync::Mutex;
 
 /// A writer that collects messages into a Vec<String>
 pub struct VecMakeWriter {
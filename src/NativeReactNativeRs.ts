@@ -4,6 +4,8 @@ import { TurboModuleRegistry } from 'react-native';
 export interface Spec extends TurboModule {
   execute(cmd: string): Promise<string>;
   getAppHomeDir(): Promise<string>;
+  registerBuffer(base64: string): Promise<string>;
+  releaseBuffer(id: string): Promise<boolean>;
 }
 
 export default TurboModuleRegistry.getEnforcing<Spec>('ReactNativeRs');
@@ -0,0 +1,78 @@
+import { NativeModules } from 'react-native';
+import { RustBridge, RustError } from '../index';
+
+const native = NativeModules.ReactNativeRs as Record<
+  'execute' | 'getAppHomeDir' | 'registerBuffer' | 'releaseBuffer',
+  jest.Mock
+>;
+
+const reply = (res: unknown, extra: Record<string, unknown> = {}) =>
+  JSON.stringify({
+    res: res === null ? null : JSON.stringify(res),
+    error: null,
+    error_code: null,
+    field_errors: null,
+    panic: false,
+    panic_details: null,
+    ...extra,
+  });
+
+describe('RustBridge.execute', () => {
+  beforeEach(() => native.execute.mockReset());
+
+  it('parses the JSON-encoded result', async () => {
+    native.execute.mockResolvedValue(reply({ value: 5, overflowed: false, mode: 'fixed' }));
+
+    const result = await RustBridge.execute({ cmd: 'fibonacci', params: { n: 5 } });
+
+    expect(native.execute).toHaveBeenCalledWith('{"cmd":"fibonacci","params":{"n":5}}');
+    expect(result.value).toBe(5);
+    expect(result.mode).toBe('fixed');
+  });
+
+  it('returns null when there is no result', async () => {
+    native.execute.mockResolvedValue(reply(null));
+
+    await expect(RustBridge.execute({ cmd: 'fibonacci' })).resolves.toBeNull();
+  });
+
+  it('throws a RustError carrying the error code and field errors', async () => {
+    const fieldErrors = [{ field: 'n', code: 'range', msg: 'n must be at most 1000' }];
+    native.execute.mockResolvedValue(
+      reply(null, { error: 'invalid params', error_code: 'invalid_params', field_errors: fieldErrors })
+    );
+
+    const error = await RustBridge.execute({ cmd: 'fibonacci', params: { n: 5000 } }).catch((e) => e);
+
+    expect(error).toBeInstanceOf(RustError);
+    expect(error.code).toBe('invalid_params');
+    expect(error.fieldErrors).toEqual(fieldErrors);
+  });
+});
+
+describe('RustBridge.useAppDataDir', () => {
+  beforeEach(() => native.execute.mockReset());
+
+  it('returns the canonical data directory reported by Rust', async () => {
+    native.getAppHomeDir.mockResolvedValue('/data/user/0/app/files/../files');
+    native.execute.mockResolvedValue(reply({ path: '/data/user/0/app/files' }));
+
+    await expect(RustBridge.useAppDataDir()).resolves.toBe('/data/user/0/app/files');
+    expect(native.execute).toHaveBeenCalledWith(
+      '{"cmd":"set_data_dir","params":{"path":"/data/user/0/app/files/../files"}}'
+    );
+  });
+});
+
+describe('RustBridge buffers', () => {
+  it('registers and releases buffers by their string ids', async () => {
+    native.registerBuffer.mockResolvedValue('18446744073709551615');
+    native.releaseBuffer.mockResolvedValue(true);
+
+    const id = await RustBridge.registerBuffer('aGVsbG8=');
+    expect(native.registerBuffer).toHaveBeenCalledWith('aGVsbG8=');
+    expect(id).toBe('18446744073709551615');
+    await expect(RustBridge.releaseBuffer(id)).resolves.toBe(true);
+    expect(native.releaseBuffer).toHaveBeenCalledWith('18446744073709551615');
+  });
+});
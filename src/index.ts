@@ -1,4 +1,7 @@
 import { NativeModules, Platform } from 'react-native';
+import { RustClient } from './types/client';
+import type { CommandName, CommandOf, CommandResultMap, ErrorCode } from './types/client';
+import type { FieldError } from './types/FieldError';
 
 const LINKING_ERROR =
   'The package \'react-native-rs\' doesn\'t seem to be linked. Make sure: \n\n' +
@@ -40,9 +43,12 @@ export interface Command {
 /**
  * Result interface for Rust bridge operations
  */
-export interface CommandResult<T = any> {
-  res?: T;
+export interface CommandResult {
+  /** JSON-encoded result of the command, `null` when it failed */
+  res: string | null;
   error?: string;
+  error_code?: ErrorCode;
+  field_errors?: FieldError[];
   panic: boolean;
   panic_details?: {
     cmd: string;
@@ -50,6 +56,20 @@ export interface CommandResult<T = any> {
   };
 }
 
+/**
+ * Error thrown when a Rust command fails or panics
+ */
+export class RustError extends Error {
+  constructor(
+    message: string,
+    readonly code: ErrorCode,
+    readonly fieldErrors: FieldError[] = []
+  ) {
+    super(message);
+    this.name = 'RustError';
+  }
+}
+
 /**
  * Main RustBridge class for executing commands
  */
@@ -58,23 +78,31 @@ export class RustBridge {
    * Execute a command in Rust
    * @param command - The command object with cmd and optional params
    * @returns Promise resolving to the command result
+   * @throws RustError if the command fails or panics
    */
+  static execute<K extends CommandName>(command: CommandOf<K>): Promise<CommandResultMap[K]>;
+  static execute<T = any>(command: Command): Promise<T>;
   static async execute<T = any>(command: Command): Promise<T> {
     const cmdString = JSON.stringify(command);
     const resultString = await ReactNativeRs.execute(cmdString);
-    const result: CommandResult<T> = JSON.parse(resultString);
+    const result: CommandResult = JSON.parse(resultString);
     
     if (result.panic) {
-      throw new Error(
-        `Rust panic in command '${result.panic_details?.cmd}': ${result.panic_details?.msg}`
+      throw new RustError(
+        `Rust panic in command '${result.panic_details?.cmd}': ${result.panic_details?.msg}`,
+        'panic'
       );
     }
     
     if (result.error) {
-      throw new Error(`Rust error: ${result.error}`);
+      throw new RustError(
+        `Rust error: ${result.error}`,
+        result.error_code ?? 'execution_failed',
+        result.field_errors ?? []
+      );
     }
     
-    return result.res as T;
+    return result.res == null ? (null as T) : (JSON.parse(result.res) as T);
   }
 
   /**
@@ -84,8 +112,44 @@ export class RustBridge {
   static async getAppHomeDir(): Promise<string> {
     return ReactNativeRs.getAppHomeDir();
   }
+
+  /**
+   * Hand binary data over to Rust once, so commands can refer to it with the `buffer` encoding
+   * @param base64 - The data, base64 encoded
+   * @returns Promise resolving to the buffer id, a decimal string since ids are 64-bit
+   */
+  static async registerBuffer(base64: string): Promise<string> {
+    return ReactNativeRs.registerBuffer(base64);
+  }
+
+  /**
+   * Release a buffer registered with `registerBuffer`
+   * @param id - The buffer id
+   * @returns Promise resolving to whether the buffer was still registered
+   */
+  static async releaseBuffer(id: string): Promise<boolean> {
+    return ReactNativeRs.releaseBuffer(id);
+  }
+
+  /**
+   * Resolve the file paths of commands like `hash_file` against the app's home directory
+   * @returns Promise resolving to the canonical data directory
+   */
+  static async useAppDataDir(): Promise<string> {
+    const path = await RustBridge.getAppHomeDir();
+    const status = await RustBridge.execute({ cmd: 'set_data_dir', params: { path } });
+    return status.path;
+  }
 }
 
+/**
+ * Typed client with one method per Rust command
+ */
+export const rust = new RustClient(
+  <K extends CommandName>(command: CommandOf<K>) => RustBridge.execute<K>(command)
+);
+
 // Export types and main class
-export { Command, CommandResult };
+export { Command, CommandResult, RustClient };
+export type { CommandName, CommandOf, CommandResultMap, ErrorCode, FieldError };
 export default RustBridge;
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Command } from "./Command";
+
+export interface BenchmarkInput { command: Command, compare: Array<Command>, iterations: number, warmup: number, }
\ No newline at end of file
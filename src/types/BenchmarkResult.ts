@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BenchmarkRun } from "./BenchmarkRun";
+
+export interface BenchmarkResult { iterations: number, warmup: number, runs: Array<BenchmarkRun>, computation_time_ms: bigint, }
\ No newline at end of file
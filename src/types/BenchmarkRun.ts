@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Command } from "./Command";
+
+export interface BenchmarkRun { command: Command, min_ms: number, max_ms: number, mean_ms: number, std_dev_ms: number, p50_ms: number, p90_ms: number, p95_ms: number, p99_ms: number, throughput: number, relative_mean: number, }
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CipherAlgorithm = "aes_256_gcm" | "chacha20_poly1305";
\ No newline at end of file
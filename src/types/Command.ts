@@ -0,0 +1,41 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BenchmarkInput } from "./BenchmarkInput";
+import type { ConvertKeyInput } from "./ConvertKeyInput";
+import type { DecryptInput } from "./DecryptInput";
+import type { DeriveKeyInput } from "./DeriveKeyInput";
+import type { EncryptInput } from "./EncryptInput";
+import type { FibonacciInput } from "./FibonacciInput";
+import type { FindPrimesInput } from "./FindPrimesInput";
+import type { GenerateKeypairInput } from "./GenerateKeypairInput";
+import type { HashDataInput } from "./HashDataInput";
+import type { HashFileInput } from "./HashFileInput";
+import type { HmacInput } from "./HmacInput";
+import type { HotpGenerateInput } from "./HotpGenerateInput";
+import type { ImageProcessingInput } from "./ImageProcessingInput";
+import type { KeyAgreementInput } from "./KeyAgreementInput";
+import type { MerkleInput } from "./MerkleInput";
+import type { MerkleVerifyInput } from "./MerkleVerifyInput";
+import type { NeedsRehashInput } from "./NeedsRehashInput";
+import type { OpenInput } from "./OpenInput";
+import type { PasswordHashInput } from "./PasswordHashInput";
+import type { PasswordVerifyInput } from "./PasswordVerifyInput";
+import type { ProgressInput } from "./ProgressInput";
+import type { RandomInput } from "./RandomInput";
+import type { ReplaySessionInput } from "./ReplaySessionInput";
+import type { SchemaInput } from "./SchemaInput";
+import type { SealInput } from "./SealInput";
+import type { SequenceInput } from "./SequenceInput";
+import type { SetDataDirInput } from "./SetDataDirInput";
+import type { SignInput } from "./SignInput";
+import type { SortFileInput } from "./SortFileInput";
+import type { SortNumbersInput } from "./SortNumbersInput";
+import type { SortRecordsInput } from "./SortRecordsInput";
+import type { SortValuesInput } from "./SortValuesInput";
+import type { StartRecordingInput } from "./StartRecordingInput";
+import type { StatsInput } from "./StatsInput";
+import type { TotpGenerateInput } from "./TotpGenerateInput";
+import type { TotpVerifyInput } from "./TotpVerifyInput";
+import type { VerifyInput } from "./VerifyInput";
+import type { X25519KeypairInput } from "./X25519KeypairInput";
+
+export type Command = { "cmd": "logs" } | { "cmd": "fibonacci", "params": FibonacciInput } | { "cmd": "sequence", "params": SequenceInput } | { "cmd": "hash_data", "params": HashDataInput } | { "cmd": "hash_file", "params": HashFileInput } | { "cmd": "hmac", "params": HmacInput } | { "cmd": "derive_key", "params": DeriveKeyInput } | { "cmd": "password_hash", "params": PasswordHashInput } | { "cmd": "password_verify", "params": PasswordVerifyInput } | { "cmd": "needs_rehash", "params": NeedsRehashInput } | { "cmd": "encrypt", "params": EncryptInput } | { "cmd": "decrypt", "params": DecryptInput } | { "cmd": "x25519_keypair", "params": X25519KeypairInput } | { "cmd": "key_agreement", "params": KeyAgreementInput } | { "cmd": "seal", "params": SealInput } | { "cmd": "open", "params": OpenInput } | { "cmd": "random", "params": RandomInput } | { "cmd": "generate_keypair", "params": GenerateKeypairInput } | { "cmd": "convert_key", "params": ConvertKeyInput } | { "cmd": "sign", "params": SignInput } | { "cmd": "verify", "params": VerifyInput } | { "cmd": "merkle", "params": MerkleInput } | { "cmd": "merkle_verify", "params": MerkleVerifyInput } | { "cmd": "totp_generate", "params": TotpGenerateInput } | { "cmd": "totp_verify", "params": TotpVerifyInput } | { "cmd": "hotp_generate", "params": HotpGenerateInput } | { "cmd": "sort_numbers", "params": SortNumbersInput } | { "cmd": "sort_values", "params": SortValuesInput } | { "cmd": "sort_records", "params": SortRecordsInput } | { "cmd": "sort_file", "params": SortFileInput } | { "cmd": "stats", "params": StatsInput } | { "cmd": "find_primes", "params": FindPrimesInput } | { "cmd": "process_image", "params": ImageProcessingInput } | { "cmd": "start_recording", "params": StartRecordingInput } | { "cmd": "stop_recording" } | { "cmd": "replay_session", "params": ReplaySessionInput } | { "cmd": "benchmark", "params": BenchmarkInput } | { "cmd": "schema", "params": SchemaInput } | { "cmd": "set_data_dir", "params": SetDataDirInput } | { "cmd": "progress", "params": ProgressInput };
\ No newline at end of file
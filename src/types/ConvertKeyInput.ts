@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataEncoding } from "./DataEncoding";
+import type { KeyFormat } from "./KeyFormat";
+import type { KeyKind } from "./KeyKind";
+import type { OutputEncoding } from "./OutputEncoding";
+import type { SignatureAlgorithm } from "./SignatureAlgorithm";
+
+export interface ConvertKeyInput { algorithm: SignatureAlgorithm, key: string, kind: KeyKind, format?: KeyFormat, key_encoding?: DataEncoding, to_format: KeyFormat, output_encoding?: OutputEncoding, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CipherAlgorithm } from "./CipherAlgorithm";
+import type { PlaintextEncoding } from "./PlaintextEncoding";
+
+export interface DecryptResult { data: string, bytes: Array<number> | null, algorithm: CipherAlgorithm, output_encoding: PlaintextEncoding, computation_time_ms: bigint, }
\ No newline at end of file
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataEncoding } from "./DataEncoding";
+import type { KdfAlgorithm } from "./KdfAlgorithm";
+import type { MacAlgorithm } from "./MacAlgorithm";
+import type { OutputEncoding } from "./OutputEncoding";
+
+export interface DeriveKeyInput { algorithm: KdfAlgorithm, hash?: MacAlgorithm, secret: string, secret_encoding?: DataEncoding, salt?: string, salt_encoding?: DataEncoding, info?: string, info_encoding?: DataEncoding, iterations?: number, length: number, output_encoding?: OutputEncoding, }
\ No newline at end of file
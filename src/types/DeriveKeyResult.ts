@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { KdfAlgorithm } from "./KdfAlgorithm";
+import type { MacAlgorithm } from "./MacAlgorithm";
+import type { OutputEncoding } from "./OutputEncoding";
+
+export interface DeriveKeyResult { key: string, bytes: Array<number> | null, algorithm: KdfAlgorithm, hash: MacAlgorithm, iterations: number | null, output_encoding: OutputEncoding, computation_time_ms: bigint, }
\ No newline at end of file
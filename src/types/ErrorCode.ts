@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ErrorCode = "invalid_command" | "invalid_params" | "execution_failed" | "authentication_failed" | "panic";
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HashAlgorithm } from "./HashAlgorithm";
+
+export interface FileDigest { algorithm: HashAlgorithm, hash: string, bytes: Array<number> | null, }
\ No newline at end of file
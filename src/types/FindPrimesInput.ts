@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PrimePage } from "./PrimePage";
+
+export interface FindPrimesInput { start?: number, limit: number, use_parallel: boolean, count_only?: boolean, page?: PrimePage, }
\ No newline at end of file
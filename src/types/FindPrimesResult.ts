@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface FindPrimesResult { primes: Array<number>, count: number, start: number, limit: number, computation_time_ms: bigint, used_parallel: boolean, }
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { KeyFormat } from "./KeyFormat";
+import type { OutputEncoding } from "./OutputEncoding";
+import type { SignatureAlgorithm } from "./SignatureAlgorithm";
+
+export interface GenerateKeypairInput { algorithm: SignatureAlgorithm, format?: KeyFormat, output_encoding?: OutputEncoding, }
\ No newline at end of file
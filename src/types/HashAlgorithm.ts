@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type HashAlgorithm = "sha256" | "sha512" | "sha3_256" | "sha1" | "md5" | "blake2b" | "blake3" | "crc32" | "xxh3";
\ No newline at end of file
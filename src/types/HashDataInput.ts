@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataEncoding } from "./DataEncoding";
+import type { HashAlgorithm } from "./HashAlgorithm";
+import type { OutputEncoding } from "./OutputEncoding";
+
+export interface HashDataInput { data: string, encoding?: DataEncoding, algorithm: HashAlgorithm, output_encoding?: OutputEncoding, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HashAlgorithm } from "./HashAlgorithm";
+import type { OutputEncoding } from "./OutputEncoding";
+
+export interface HashDataResult { hash: string, bytes: Array<number> | null, algorithm: HashAlgorithm, output_encoding: OutputEncoding, input_length: number, computation_time_ms: bigint, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HashAlgorithm } from "./HashAlgorithm";
+import type { OutputEncoding } from "./OutputEncoding";
+
+export interface HashFileInput { path: string, algorithms: Array<HashAlgorithm>, output_encoding?: OutputEncoding, offset?: number, length?: number, progress_id?: string, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FileDigest } from "./FileDigest";
+import type { OutputEncoding } from "./OutputEncoding";
+
+export interface HashFileResult { digests: Array<FileDigest>, output_encoding: OutputEncoding, file_size: number, offset: number, input_length: number, computation_time_ms: bigint, }
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataEncoding } from "./DataEncoding";
+import type { MacAlgorithm } from "./MacAlgorithm";
+import type { OutputEncoding } from "./OutputEncoding";
+
+export interface HmacInput { data: string, encoding?: DataEncoding, key: string, key_encoding?: DataEncoding, algorithm?: MacAlgorithm, output_encoding?: OutputEncoding, expected?: string, expected_encoding?: DataEncoding, }
\ No newline at end of file
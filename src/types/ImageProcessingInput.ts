@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImageFilter } from "./ImageFilter";
+
+export interface ImageProcessingInput { data: string, filter: ImageFilter, intensity: number | null, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImageFilter } from "./ImageFilter";
+
+export interface ImageProcessingResult { data: string, filter: ImageFilter, original_size: [number, number], computation_time_ms: bigint, }
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataEncoding } from "./DataEncoding";
+import type { MacAlgorithm } from "./MacAlgorithm";
+import type { OutputEncoding } from "./OutputEncoding";
+
+export interface KeyAgreementInput { private_key: string, public_key: string, key_encoding?: DataEncoding, hash?: MacAlgorithm, salt?: string, salt_encoding?: DataEncoding, info?: string, info_encoding?: DataEncoding, length: number, output_encoding?: OutputEncoding, }
\ No newline at end of file
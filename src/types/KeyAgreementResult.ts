@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MacAlgorithm } from "./MacAlgorithm";
+import type { OutputEncoding } from "./OutputEncoding";
+
+export interface KeyAgreementResult { key: string, bytes: Array<number> | null, hash: MacAlgorithm, output_encoding: OutputEncoding, computation_time_ms: bigint, }
\ No newline at end of file
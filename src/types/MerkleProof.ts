@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MerkleProofStep } from "./MerkleProofStep";
+
+export interface MerkleProof { index: number, path: Array<MerkleProofStep>, }
\ No newline at end of file
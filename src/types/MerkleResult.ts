@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HashAlgorithm } from "./HashAlgorithm";
+import type { MerkleProof } from "./MerkleProof";
+import type { OutputEncoding } from "./OutputEncoding";
+
+export interface MerkleResult { root: string, leaf_count: number, depth: number, algorithm: HashAlgorithm, output_encoding: OutputEncoding, proofs: Array<MerkleProof> | null, computation_time_ms: bigint, }
\ No newline at end of file
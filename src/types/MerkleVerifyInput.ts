@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataEncoding } from "./DataEncoding";
+import type { HashAlgorithm } from "./HashAlgorithm";
+import type { MerkleProofStep } from "./MerkleProofStep";
+
+export interface MerkleVerifyInput { item: string, encoding?: DataEncoding, root: string, proof: Array<MerkleProofStep>, algorithm?: HashAlgorithm, hash_encoding?: DataEncoding, }
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface NeedsRehashResult { needs_rehash: boolean, computation_time_ms: bigint, }
\ No newline at end of file
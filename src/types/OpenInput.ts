@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataEncoding } from "./DataEncoding";
+import type { PlaintextEncoding } from "./PlaintextEncoding";
+
+export interface OpenInput { sealed: string, encoding?: DataEncoding, private_key: string, key_encoding?: DataEncoding, aad?: string, aad_encoding?: DataEncoding, output_encoding?: PlaintextEncoding, }
\ No newline at end of file
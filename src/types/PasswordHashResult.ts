@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PasswordAlgorithm } from "./PasswordAlgorithm";
+
+export interface PasswordHashResult { hash: string, algorithm: PasswordAlgorithm, computation_time_ms: bigint, }
\ No newline at end of file
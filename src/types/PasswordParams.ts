@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PasswordAlgorithm } from "./PasswordAlgorithm";
+
+export interface PasswordParams { algorithm?: PasswordAlgorithm, memory_cost?: number, time_cost?: number, parallelism?: number, log_n?: number, block_size?: number, }
\ No newline at end of file
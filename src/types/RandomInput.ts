@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RandomMode } from "./RandomMode";
+
+export interface RandomInput { mode: RandomMode, count: number, seed?: number, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OutputEncoding } from "./OutputEncoding";
+import type { UuidVersion } from "./UuidVersion";
+
+export type RandomMode = { "type": "bytes", length: number, output_encoding?: OutputEncoding, } | { "type": "int", min: number, max: number, } | { "type": "float", min?: number, max?: number, } | { "type": "uuid", version?: UuidVersion, } | { "type": "ulid" } | { "type": "nanoid", length?: number, alphabet?: string, } | { "type": "choice", items: Array<any>, weights?: Array<number>, } | { "type": "shuffle", items: Array<any>, };
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface RandomResult { values: Array<any>, seeded: boolean, computation_time_ms: bigint, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReplayStatus } from "./ReplayStatus";
+import type { ResultDiff } from "./ResultDiff";
+
+export interface ReplayEntry { index: number, cmd: string, status: ReplayStatus, diffs: Array<ResultDiff>, recorded_duration_us: bigint, replayed_duration_us: bigint, }
\ No newline at end of file
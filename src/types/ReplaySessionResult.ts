@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReplayEntry } from "./ReplayEntry";
+
+export interface ReplaySessionResult { total: number, matched: number, mismatched: number, skipped: number, entries: Array<ReplayEntry>, }
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CipherAlgorithm } from "./CipherAlgorithm";
+import type { DataEncoding } from "./DataEncoding";
+import type { OutputEncoding } from "./OutputEncoding";
+
+export interface SealInput { data: string, encoding?: DataEncoding, public_key: string, key_encoding?: DataEncoding, algorithm?: CipherAlgorithm, aad?: string, aad_encoding?: DataEncoding, output_encoding?: OutputEncoding, }
\ No newline at end of file
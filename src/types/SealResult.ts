@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CipherAlgorithm } from "./CipherAlgorithm";
+import type { OutputEncoding } from "./OutputEncoding";
+
+export interface SealResult { sealed: string, bytes: Array<number> | null, algorithm: CipherAlgorithm, output_encoding: OutputEncoding, computation_time_ms: bigint, }
\ No newline at end of file
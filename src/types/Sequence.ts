@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Sequence = { "family": "linear", coefficients: Array<number>, initial: Array<number>, } | { "family": "fibonacci" } | { "family": "lucas" } | { "family": "tribonacci" } | { "family": "catalan" } | { "family": "factorial" };
\ No newline at end of file
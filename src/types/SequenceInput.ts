@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ArithmeticMode } from "./ArithmeticMode";
+import type { Sequence } from "./Sequence";
+
+export interface SequenceInput { sequence: Sequence, n: number, count: number, mode?: ArithmeticMode, modulus?: number, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ArithmeticMode } from "./ArithmeticMode";
+
+export interface SequenceResult { terms: Array<string>, n: number, mode: ArithmeticMode, overflowed: boolean, computation_time_ms: bigint, }
\ No newline at end of file
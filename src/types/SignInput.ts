@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataEncoding } from "./DataEncoding";
+import type { KeyFormat } from "./KeyFormat";
+import type { OutputEncoding } from "./OutputEncoding";
+import type { SignatureAlgorithm } from "./SignatureAlgorithm";
+
+export interface SignInput { algorithm: SignatureAlgorithm, data: string, encoding?: DataEncoding, private_key: string, key_format?: KeyFormat, key_encoding?: DataEncoding, output_encoding?: OutputEncoding, }
\ No newline at end of file
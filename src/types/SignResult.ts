@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OutputEncoding } from "./OutputEncoding";
+import type { SignatureAlgorithm } from "./SignatureAlgorithm";
+
+export interface SignResult { signature: string, bytes: Array<number> | null, algorithm: SignatureAlgorithm, output_encoding: OutputEncoding, computation_time_ms: bigint, }
\ No newline at end of file
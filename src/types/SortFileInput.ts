@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LineFormat } from "./LineFormat";
+
+export interface SortFileInput { path: string, output_path: string, format: LineFormat, memory_budget?: number, progress_id?: string, }
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface SortFileResult { line_count: number, runs: number, merge_passes: number, file_size: number, computation_time_ms: bigint, }
\ No newline at end of file
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { KeyCoercion } from "./KeyCoercion";
+import type { NullPlacement } from "./NullPlacement";
+import type { SortDirection } from "./SortDirection";
+import type { StringOrder } from "./StringOrder";
+
+export interface SortKey { path: string, direction?: SortDirection, nulls?: NullPlacement, coerce?: KeyCoercion, string_order?: StringOrder, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SortAlgorithm } from "./SortAlgorithm";
+import type { SortDirection } from "./SortDirection";
+
+export interface SortNumbersInput { numbers: Array<number>, algorithm: SortAlgorithm, direction?: SortDirection, stable?: boolean, argsort?: boolean, dedup?: boolean, top_k?: number, bottom_k?: number, }
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RecordsOutput } from "./RecordsOutput";
+import type { SortAlgorithm } from "./SortAlgorithm";
+import type { SortKey } from "./SortKey";
+
+export interface SortRecordsInput { records: Array<any>, keys: Array<SortKey>, output?: RecordsOutput, algorithm: SortAlgorithm, }
\ No newline at end of file
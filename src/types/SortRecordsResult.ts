@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SortAlgorithm } from "./SortAlgorithm";
+
+export interface SortRecordsResult { records: Array<any> | null, indices: Array<number> | null, algorithm: SortAlgorithm, input_length: number, computation_time_ms: bigint, }
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SortValues = { "type": "i64", items: Array<number>, } | { "type": "u64", items: Array<number>, } | { "type": "f64", items: Array<number | null>, } | { "type": "string", items: Array<string>, };
\ No newline at end of file
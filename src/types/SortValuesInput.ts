@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NanPlacement } from "./NanPlacement";
+import type { SortAlgorithm } from "./SortAlgorithm";
+import type { SortValues } from "./SortValues";
+import type { StringOrder } from "./StringOrder";
+
+export interface SortValuesInput { values: SortValues, algorithm: SortAlgorithm, nan_placement?: NanPlacement, string_order?: StringOrder, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SortAlgorithm } from "./SortAlgorithm";
+import type { SortValues } from "./SortValues";
+
+export interface SortValuesResult { values: SortValues, algorithm: SortAlgorithm, input_length: number, computation_time_ms: bigint, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HistogramOptions } from "./HistogramOptions";
+import type { VarianceKind } from "./VarianceKind";
+
+export interface StatsInput { values: Array<number>, variance?: VarianceKind, quantiles?: Array<number>, histogram?: HistogramOptions, paired?: Array<number>, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Correlation } from "./Correlation";
+import type { Histogram } from "./Histogram";
+
+export interface StatsResult { count: number, sum: number, min: number | null, max: number | null, mean: number | null, variance: number | null, std_dev: number | null, median: number | null, quantiles: Array<number> | null, mode: number | null, mode_count: number, histogram: Histogram | null, correlation: Correlation | null, computation_time_ms: bigint, }
\ No newline at end of file
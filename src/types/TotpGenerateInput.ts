@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OtpAlgorithm } from "./OtpAlgorithm";
+
+export interface TotpGenerateInput { secret?: string, otpauth_uri?: string, algorithm?: OtpAlgorithm, digits?: number, period?: number, timestamp?: number, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OtpAlgorithm } from "./OtpAlgorithm";
+
+export interface TotpResult { code: string, counter: number, valid_for_secs: number, period: number, digits: number, algorithm: OtpAlgorithm, issuer: string | null, account: string | null, computation_time_ms: bigint, }
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataEncoding } from "./DataEncoding";
+import type { KeyFormat } from "./KeyFormat";
+import type { SignatureAlgorithm } from "./SignatureAlgorithm";
+
+export interface VerifyInput { algorithm: SignatureAlgorithm, data: string, encoding?: DataEncoding, public_key: string, key_format?: KeyFormat, key_encoding?: DataEncoding, signature: string, signature_encoding?: DataEncoding, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SignatureAlgorithm } from "./SignatureAlgorithm";
+
+export interface VerifyResult { valid: boolean, algorithm: SignatureAlgorithm, computation_time_ms: bigint, }
\ No newline at end of file
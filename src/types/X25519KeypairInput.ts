@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OutputEncoding } from "./OutputEncoding";
+
+export interface X25519KeypairInput { output_encoding?: OutputEncoding, }
\ No newline at end of file
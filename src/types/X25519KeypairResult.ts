@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OutputEncoding } from "./OutputEncoding";
+
+export interface X25519KeypairResult { private_key: string, private_key_bytes: Array<number> | null, public_key: string, public_key_bytes: Array<number> | null, output_encoding: OutputEncoding, computation_time_ms: bigint, }
\ No newline at end of file
@@ -0,0 +1,297 @@
+// This file was generated by `generate-types`. Do not edit this file manually.
+import type { BenchmarkInput } from "./BenchmarkInput";
+import type { BenchmarkResult } from "./BenchmarkResult";
+import type { Command } from "./Command";
+import type { ConvertKeyInput } from "./ConvertKeyInput";
+import type { DataDirStatus } from "./DataDirStatus";
+import type { DecryptInput } from "./DecryptInput";
+import type { DecryptResult } from "./DecryptResult";
+import type { DeriveKeyInput } from "./DeriveKeyInput";
+import type { DeriveKeyResult } from "./DeriveKeyResult";
+import type { EncryptInput } from "./EncryptInput";
+import type { EncryptResult } from "./EncryptResult";
+import type { ErrorCode } from "./ErrorCode";
+import type { FibonacciInput } from "./FibonacciInput";
+import type { FibonacciResult } from "./FibonacciResult";
+import type { FindPrimesInput } from "./FindPrimesInput";
+import type { FindPrimesResult } from "./FindPrimesResult";
+import type { GenerateKeypairInput } from "./GenerateKeypairInput";
+import type { HashDataInput } from "./HashDataInput";
+import type { HashDataResult } from "./HashDataResult";
+import type { HashFileInput } from "./HashFileInput";
+import type { HashFileResult } from "./HashFileResult";
+import type { HmacInput } from "./HmacInput";
+import type { HmacResult } from "./HmacResult";
+import type { HotpGenerateInput } from "./HotpGenerateInput";
+import type { HotpResult } from "./HotpResult";
+import type { ImageProcessingInput } from "./ImageProcessingInput";
+import type { ImageProcessingResult } from "./ImageProcessingResult";
+import type { KeyAgreementInput } from "./KeyAgreementInput";
+import type { KeyAgreementResult } from "./KeyAgreementResult";
+import type { KeyPairResult } from "./KeyPairResult";
+import type { MerkleInput } from "./MerkleInput";
+import type { MerkleResult } from "./MerkleResult";
+import type { MerkleVerifyInput } from "./MerkleVerifyInput";
+import type { MerkleVerifyResult } from "./MerkleVerifyResult";
+import type { NeedsRehashInput } from "./NeedsRehashInput";
+import type { NeedsRehashResult } from "./NeedsRehashResult";
+import type { OpenInput } from "./OpenInput";
+import type { PasswordHashInput } from "./PasswordHashInput";
+import type { PasswordHashResult } from "./PasswordHashResult";
+import type { PasswordVerifyInput } from "./PasswordVerifyInput";
+import type { PasswordVerifyResult } from "./PasswordVerifyResult";
+import type { Progress } from "./Progress";
+import type { ProgressInput } from "./ProgressInput";
+import type { RandomInput } from "./RandomInput";
+import type { RandomResult } from "./RandomResult";
+import type { RecordingStatus } from "./RecordingStatus";
+import type { ReplaySessionInput } from "./ReplaySessionInput";
+import type { ReplaySessionResult } from "./ReplaySessionResult";
+import type { SchemaInput } from "./SchemaInput";
+import type { SchemaResult } from "./SchemaResult";
+import type { SealInput } from "./SealInput";
+import type { SealResult } from "./SealResult";
+import type { SequenceInput } from "./SequenceInput";
+import type { SequenceResult } from "./SequenceResult";
+import type { SetDataDirInput } from "./SetDataDirInput";
+import type { SignInput } from "./SignInput";
+import type { SignResult } from "./SignResult";
+import type { SortFileInput } from "./SortFileInput";
+import type { SortFileResult } from "./SortFileResult";
+import type { SortNumbersInput } from "./SortNumbersInput";
+import type { SortNumbersResult } from "./SortNumbersResult";
+import type { SortRecordsInput } from "./SortRecordsInput";
+import type { SortRecordsResult } from "./SortRecordsResult";
+import type { SortValuesInput } from "./SortValuesInput";
+import type { SortValuesResult } from "./SortValuesResult";
+import type { StartRecordingInput } from "./StartRecordingInput";
+import type { StatsInput } from "./StatsInput";
+import type { StatsResult } from "./StatsResult";
+import type { TotpGenerateInput } from "./TotpGenerateInput";
+import type { TotpResult } from "./TotpResult";
+import type { TotpVerifyInput } from "./TotpVerifyInput";
+import type { TotpVerifyResult } from "./TotpVerifyResult";
+import type { VerifyInput } from "./VerifyInput";
+import type { VerifyResult } from "./VerifyResult";
+import type { X25519KeypairInput } from "./X25519KeypairInput";
+import type { X25519KeypairResult } from "./X25519KeypairResult";
+
+export type { ErrorCode };
+
+/** Name of every command, as used in the `cmd` tag. */
+export type CommandName = Command["cmd"];
+
+/** The `Command` variant with the given name. */
+export type CommandOf<K extends CommandName> = Extract<Command, { cmd: K }>;
+
+/** Result type of every command, keyed by command name. */
+export interface CommandResultMap {
+  "logs": Array<string>;
+  "fibonacci": FibonacciResult;
+  "sequence": SequenceResult;
+  "hash_data": HashDataResult;
+  "hash_file": HashFileResult;
+  "hmac": HmacResult;
+  "derive_key": DeriveKeyResult;
+  "password_hash": PasswordHashResult;
+  "password_verify": PasswordVerifyResult;
+  "needs_rehash": NeedsRehashResult;
+  "encrypt": EncryptResult;
+  "decrypt": DecryptResult;
+  "x25519_keypair": X25519KeypairResult;
+  "key_agreement": KeyAgreementResult;
+  "seal": SealResult;
+  "open": DecryptResult;
+  "random": RandomResult;
+  "generate_keypair": KeyPairResult;
+  "convert_key": KeyPairResult;
+  "sign": SignResult;
+  "verify": VerifyResult;
+  "merkle": MerkleResult;
+  "merkle_verify": MerkleVerifyResult;
+  "totp_generate": TotpResult;
+  "totp_verify": TotpVerifyResult;
+  "hotp_generate": HotpResult;
+  "sort_numbers": SortNumbersResult;
+  "sort_values": SortValuesResult;
+  "sort_records": SortRecordsResult;
+  "sort_file": SortFileResult;
+  "stats": StatsResult;
+  "find_primes": FindPrimesResult;
+  "start_recording": RecordingStatus;
+  "stop_recording": RecordingStatus;
+  "replay_session": ReplaySessionResult;
+  "benchmark": BenchmarkResult;
+  "schema": SchemaResult;
+  "set_data_dir": DataDirStatus;
+  "progress": Progress;
+  "process_image": ImageProcessingResult;
+}
+
+/** Sends a command over the bridge and resolves to its result. */
+export type Executor = <K extends CommandName>(command: CommandOf<K>) => Promise<CommandResultMap[K]>;
+
+/** Typed wrapper exposing one method per command. */
+export class RustClient {
+  constructor(private readonly execute: Executor) {}
+
+  logs(): Promise<Array<string>> {
+    return this.execute<"logs">({ cmd: "logs" });
+  }
+
+  fibonacci(params: FibonacciInput): Promise<FibonacciResult> {
+    return this.execute<"fibonacci">({ cmd: "fibonacci", params });
+  }
+
+  sequence(params: SequenceInput): Promise<SequenceResult> {
+    return this.execute<"sequence">({ cmd: "sequence", params });
+  }
+
+  hashData(params: HashDataInput): Promise<HashDataResult> {
+    return this.execute<"hash_data">({ cmd: "hash_data", params });
+  }
+
+  hashFile(params: HashFileInput): Promise<HashFileResult> {
+    return this.execute<"hash_file">({ cmd: "hash_file", params });
+  }
+
+  hmac(params: HmacInput): Promise<HmacResult> {
+    return this.execute<"hmac">({ cmd: "hmac", params });
+  }
+
+  deriveKey(params: DeriveKeyInput): Promise<DeriveKeyResult> {
+    return this.execute<"derive_key">({ cmd: "derive_key", params });
+  }
+
+  passwordHash(params: PasswordHashInput): Promise<PasswordHashResult> {
+    return this.execute<"password_hash">({ cmd: "password_hash", params });
+  }
+
+  passwordVerify(params: PasswordVerifyInput): Promise<PasswordVerifyResult> {
+    return this.execute<"password_verify">({ cmd: "password_verify", params });
+  }
+
+  needsRehash(params: NeedsRehashInput): Promise<NeedsRehashResult> {
+    return this.execute<"needs_rehash">({ cmd: "needs_rehash", params });
+  }
+
+  encrypt(params: EncryptInput): Promise<EncryptResult> {
+    return this.execute<"encrypt">({ cmd: "encrypt", params });
+  }
+
+  decrypt(params: DecryptInput): Promise<DecryptResult> {
+    return this.execute<"decrypt">({ cmd: "decrypt", params });
+  }
+
+  x25519Keypair(params: X25519KeypairInput): Promise<X25519KeypairResult> {
+    return this.execute<"x25519_keypair">({ cmd: "x25519_keypair", params });
+  }
+
+  keyAgreement(params: KeyAgreementInput): Promise<KeyAgreementResult> {
+    return this.execute<"key_agreement">({ cmd: "key_agreement", params });
+  }
+
+  seal(params: SealInput): Promise<SealResult> {
+    return this.execute<"seal">({ cmd: "seal", params });
+  }
+
+  open(params: OpenInput): Promise<DecryptResult> {
+    return this.execute<"open">({ cmd: "open", params });
+  }
+
+  random(params: RandomInput): Promise<RandomResult> {
+    return this.execute<"random">({ cmd: "random", params });
+  }
+
+  generateKeypair(params: GenerateKeypairInput): Promise<KeyPairResult> {
+    return this.execute<"generate_keypair">({ cmd: "generate_keypair", params });
+  }
+
+  convertKey(params: ConvertKeyInput): Promise<KeyPairResult> {
+    return this.execute<"convert_key">({ cmd: "convert_key", params });
+  }
+
+  sign(params: SignInput): Promise<SignResult> {
+    return this.execute<"sign">({ cmd: "sign", params });
+  }
+
+  verify(params: VerifyInput): Promise<VerifyResult> {
+    return this.execute<"verify">({ cmd: "verify", params });
+  }
+
+  merkle(params: MerkleInput): Promise<MerkleResult> {
+    return this.execute<"merkle">({ cmd: "merkle", params });
+  }
+
+  merkleVerify(params: MerkleVerifyInput): Promise<MerkleVerifyResult> {
+    return this.execute<"merkle_verify">({ cmd: "merkle_verify", params });
+  }
+
+  totpGenerate(params: TotpGenerateInput): Promise<TotpResult> {
+    return this.execute<"totp_generate">({ cmd: "totp_generate", params });
+  }
+
+  totpVerify(params: TotpVerifyInput): Promise<TotpVerifyResult> {
+    return this.execute<"totp_verify">({ cmd: "totp_verify", params });
+  }
+
+  hotpGenerate(params: HotpGenerateInput): Promise<HotpResult> {
+    return this.execute<"hotp_generate">({ cmd: "hotp_generate", params });
+  }
+
+  sortNumbers(params: SortNumbersInput): Promise<SortNumbersResult> {
+    return this.execute<"sort_numbers">({ cmd: "sort_numbers", params });
+  }
+
+  sortValues(params: SortValuesInput): Promise<SortValuesResult> {
+    return this.execute<"sort_values">({ cmd: "sort_values", params });
+  }
+
+  sortRecords(params: SortRecordsInput): Promise<SortRecordsResult> {
+    return this.execute<"sort_records">({ cmd: "sort_records", params });
+  }
+
+  sortFile(params: SortFileInput): Promise<SortFileResult> {
+    return this.execute<"sort_file">({ cmd: "sort_file", params });
+  }
+
+  stats(params: StatsInput): Promise<StatsResult> {
+    return this.execute<"stats">({ cmd: "stats", params });
+  }
+
+  findPrimes(params: FindPrimesInput): Promise<FindPrimesResult> {
+    return this.execute<"find_primes">({ cmd: "find_primes", params });
+  }
+
+  startRecording(params: StartRecordingInput): Promise<RecordingStatus> {
+    return this.execute<"start_recording">({ cmd: "start_recording", params });
+  }
+
+  stopRecording(): Promise<RecordingStatus> {
+    return this.execute<"stop_recording">({ cmd: "stop_recording" });
+  }
+
+  replaySession(params: ReplaySessionInput): Promise<ReplaySessionResult> {
+    return this.execute<"replay_session">({ cmd: "replay_session", params });
+  }
+
+  benchmark(params: BenchmarkInput): Promise<BenchmarkResult> {
+    return this.execute<"benchmark">({ cmd: "benchmark", params });
+  }
+
+  schema(params: SchemaInput): Promise<SchemaResult> {
+    return this.execute<"schema">({ cmd: "schema", params });
+  }
+
+  setDataDir(params: SetDataDirInput): Promise<DataDirStatus> {
+    return this.execute<"set_data_dir">({ cmd: "set_data_dir", params });
+  }
+
+  progress(params: ProgressInput): Promise<Progress> {
+    return this.execute<"progress">({ cmd: "progress", params });
+  }
+
+  processImage(params: ImageProcessingInput): Promise<ImageProcessingResult> {
+    return this.execute<"process_image">({ cmd: "process_image", params });
+  }
+}
@@ -2,16 +2,124 @@
 // Generated types will be placed here by the ts-rs tool
 
 export * from './Command';
+export * from './ErrorCode';
+export * from './FieldError';
 export * from './FibonacciInput';
 export * from './FibonacciResult';
+export * from './ArithmeticMode';
+export * from './SequenceInput';
+export * from './SequenceResult';
+export * from './Sequence';
 export * from './HashDataInput';
 export * from './HashDataResult';
 export * from './HashAlgorithm';
+export * from './OutputEncoding';
+export * from './DataEncoding';
+export * from './HashFileInput';
+export * from './HashFileResult';
+export * from './FileDigest';
+export * from './MacAlgorithm';
+export * from './HmacInput';
+export * from './HmacResult';
+export * from './KdfAlgorithm';
+export * from './DeriveKeyInput';
+export * from './DeriveKeyResult';
+export * from './PasswordAlgorithm';
+export * from './PasswordParams';
+export * from './PasswordHashInput';
+export * from './PasswordHashResult';
+export * from './PasswordVerifyInput';
+export * from './PasswordVerifyResult';
+export * from './NeedsRehashInput';
+export * from './NeedsRehashResult';
+export * from './CipherAlgorithm';
+export * from './PlaintextEncoding';
+export * from './EncryptInput';
+export * from './EncryptResult';
+export * from './DecryptInput';
+export * from './DecryptResult';
+export * from './X25519KeypairInput';
+export * from './X25519KeypairResult';
+export * from './KeyAgreementInput';
+export * from './KeyAgreementResult';
+export * from './SealInput';
+export * from './SealResult';
+export * from './OpenInput';
+export * from './UuidVersion';
+export * from './RandomMode';
+export * from './RandomInput';
+export * from './RandomResult';
+export * from './SignatureAlgorithm';
+export * from './KeyFormat';
+export * from './KeyKind';
+export * from './GenerateKeypairInput';
+export * from './ConvertKeyInput';
+export * from './KeyPairResult';
+export * from './SignInput';
+export * from './SignResult';
+export * from './VerifyInput';
+export * from './VerifyResult';
+export * from './MerkleInput';
+export * from './MerkleSide';
+export * from './MerkleProofStep';
+export * from './MerkleProof';
+export * from './MerkleResult';
+export * from './MerkleVerifyInput';
+export * from './MerkleVerifyResult';
+export * from './OtpAlgorithm';
+export * from './OtpParams';
+export * from './TotpGenerateInput';
+export * from './TotpResult';
+export * from './TotpVerifyInput';
+export * from './TotpVerifyResult';
+export * from './HotpGenerateInput';
+export * from './HotpResult';
 export * from './SortNumbersInput';
 export * from './SortNumbersResult';
 export * from './SortAlgorithm';
+export * from './SortValues';
+export * from './NanPlacement';
+export * from './StringOrder';
+export * from './SortValuesInput';
+export * from './SortValuesResult';
+export * from './SortDirection';
+export * from './NullPlacement';
+export * from './KeyCoercion';
+export * from './SortKey';
+export * from './RecordsOutput';
+export * from './SortRecordsInput';
+export * from './SortRecordsResult';
+export * from './LineFormat';
+export * from './SortFileInput';
+export * from './SortFileResult';
+export * from './VarianceKind';
+export * from './HistogramOptions';
+export * from './StatsInput';
+export * from './Histogram';
+export * from './Correlation';
+export * from './StatsResult';
+export * from './PrimePage';
 export * from './FindPrimesInput';
 export * from './FindPrimesResult';
+export * from './BenchmarkInput';
+export * from './BenchmarkResult';
+export * from './BenchmarkRun';
+export * from './SchemaInput';
+export * from './SchemaResult';
+export * from './SetDataDirInput';
+export * from './DataDirStatus';
+export * from './ProgressInput';
+export * from './Progress';
+export * from './StartRecordingInput';
+export * from './RecordingStatus';
+export * from './ReplaySessionInput';
+export * from './ReplaySessionResult';
+export * from './ReplayEntry';
+export * from './ReplayStatus';
+export * from './ResultDiff';
+
+// Typed client generated from the Command enum
+export * from './client';
 
 // Image processing types (only available with image-processing feature)
 // export * from './ImageProcessingInput';